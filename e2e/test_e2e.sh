@@ -200,21 +200,21 @@ ASSERT_EQUAL "$token_uri" "https://m.media-amazon.com/images/I/21IAeMeSa5L.jpg"
 function test_duplicate_ask_denoms {
     # make a listing with 2 unique but duplicate denoms, ensure the denoms are merged correctly on ask
     # failure to do this = the user can not purchase the listing, even if they sent 2ujunox
-    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"id":"vault_duplicate","ask":{"native":[{"denom":"ujunox","amount":"1"},{"denom":"ujunox","amount":"1"}],"cw20":[],"nfts":[]}}}}' "1ucosm" show_logs
+    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"ask":{"native":[{"denom":"ujunox","amount":"1"},{"denom":"ujunox","amount":"1"}],"cw20":[],"nfts":[]}}}}' "1ucosm" show_logs
     ASSERT_CONTAINS "$CMD_LOG" "Duplicates found in Ask Price"
 
     # ensure it removes and 0 denoms, but does not error if no duplicates
-    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"id":"vault_c1","ask":{"native":[{"denom":"ujunox","amount":"2"},{"denom":"ujunox","amount":"0"}],"cw20":[],"nfts":[]}}}}' "1ucosm" show_logs    
-    asking_values=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_c1"}}' | jq -rc '.data.ask')
+    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"ask":{"native":[{"denom":"ujunox","amount":"2"},{"denom":"ujunox","amount":"0"}],"cw20":[],"nfts":[]}}}}' "1ucosm" show_logs    
+    asking_values=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"1"}}' | jq -rc '.data.ask')
     ASSERT_EQUAL "$asking_values" '[["ujunox","2"]]'
 
     # finalize
-    wasm_cmd $VAULT_CONTRACT '{"finalize":{"listing_id":"vault_c1","seconds":5000}}' "" show_log
+    wasm_cmd $VAULT_CONTRACT '{"finalize":{"listing_id":"1","seconds":5000}}' "" show_log
 
     # buy the listing to keep future test clean
     wasm_cmd $VAULT_CONTRACT '{"create_bucket":{"bucket_id":"buyer_com"}}' "2ujunox" show_log
-    wasm_cmd $VAULT_CONTRACT '{"buy_listing":{"listing_id":"vault_c1","bucket_id":"buyer_com"}}' "" show_log 
-    wasm_cmd $VAULT_CONTRACT '{"withdraw_purchased":{"listing_id":"vault_c1"}}' "" dont_show
+    wasm_cmd $VAULT_CONTRACT '{"buy_listing":{"listing_id":"1","bucket_id":"buyer_com"}}' "" show_log 
+    wasm_cmd $VAULT_CONTRACT '{"withdraw_purchased":{"listing_id":"1"}}' "" dont_show
 
     # ensure the listing was removed        
     listings=$(query_contract $VAULT_CONTRACT '{"get_all_listings":{}}' --output json)       
@@ -223,19 +223,15 @@ function test_duplicate_ask_denoms {
 
 function test_all_listings {
     # Selling 10ucosm for 5ujunox
-    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"id":"vault_1","ask":{"native":[{"denom":"ujunox","amount":"5"}],"cw20":[],"nfts":[]}}}}' "10ucosm" show_log
+    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"ask":{"native":[{"denom":"ujunox","amount":"5"}],"cw20":[],"nfts":[]}}}}' "10ucosm" show_log
 
     # Ensure listing went up correctly
-    listing_1=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_1"}}')
-    ASSERT_EQUAL "$listing_1" '{"data":{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","status":"Being Prepared","for_sale":[["ucosm","10"]],"ask":[["ujunox","5"]],"expiration":"None","whitelisted_buyer":"None"}}'
-
-    # Ensure duplicate vault_id fails
-    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"id":"vault_1","ask":{"native":[{"denom":"ujunox","amount":"1"}],"cw20":[],"nfts":[]}}}}' "1ujunox"
-    ASSERT_CONTAINS "$CMD_LOG" 'ID already taken'
+    listing_1=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"2"}}')
+    ASSERT_EQUAL "$listing_1" '{"data":{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","status":"Being Prepared","for_sale":[["ucosm","10"]],"ask":[["ujunox","5"]],"expiration":"None","whitelisted_buyers":[],"whitelist_until":null,"interest":0}}'
 
     echo "Sending NFT id 1 to the listing"
-    send_nft_to_listing $VAULT_CONTRACT $CW721_CONTRACT "1" "vault_1"
-    query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_1"}}'
+    send_nft_to_listing $VAULT_CONTRACT $CW721_CONTRACT "1" "2"
+    query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"2"}}'
 
     # owner should now be the VAULT_CONTRACT after sending (We check that the NFT is in the listing after the CW20)
     owner_of_nft=$(query_contract $CW721_CONTRACT '{"all_nft_info":{"token_id":"1"}}' | jq -r '.data.access.owner')
@@ -243,30 +239,30 @@ function test_all_listings {
 
     # Send 20 CW20 coin to the listing
     echo "Sending 20 CW20 token to the listing"
-    send_cw20_to_listing $VAULT_CONTRACT $CW20_CONTRACT "20" "vault_1"
+    send_cw20_to_listing $VAULT_CONTRACT $CW20_CONTRACT "20" "2"
 
     # Ensure the CW20 token & CW721 is now apart of the listing
     # todo: this will fail if the order of the array changes given there is no difference between cw20 and cw721 in it right? or does jq sort deterministically?
-    listing_values=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_1"}}' | jq -r '.data.for_sale')
+    listing_values=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"2"}}' | jq -r '.data.for_sale')
     ASSERT_EQUAL $listing_values `printf '[["ucosm","10"],["%s","20"],["%s":"1"]]' $CW20_CONTRACT $CW721_CONTRACT`
 
     # Finalize the listing for purchase after everything is added
-    wasm_cmd $VAULT_CONTRACT '{"finalize":{"listing_id":"vault_1","seconds":5000}}' "" show_log
+    wasm_cmd $VAULT_CONTRACT '{"finalize":{"listing_id":"2","seconds":5000}}' "" show_log
     # try to finalize again, will fail
-    wasm_cmd $VAULT_CONTRACT '{"finalize":{"listing_id":"vault_1","seconds":100}}' ""
+    wasm_cmd $VAULT_CONTRACT '{"finalize":{"listing_id":"2","seconds":100}}' ""
     ASSERT_CONTAINS "$CMD_LOG" 'Listing already finalized'
 
     # Create bucket so we can purchase the listing
     echo "Creating bucket and purchasing listing"
     wasm_cmd $VAULT_CONTRACT '{"create_bucket":{"bucket_id":"buyer_1"}}' "5ujunox" show_log
     # purchase listing
-    wasm_cmd $VAULT_CONTRACT '{"buy_listing":{"listing_id":"vault_1","bucket_id":"buyer_1"}}' "" show_log
+    wasm_cmd $VAULT_CONTRACT '{"buy_listing":{"listing_id":"2","bucket_id":"buyer_1"}}' "" show_log
     echo "Withdrawing rewards... (Should do this in buy listing function?)"
 
     # check users balance changes here after we  execute_withdraw_purchased
-    # query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_1"}}' <- ensure it is closed, but I feel when we buy it should auto transfer? Why not?
+    # query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"2"}}' <- ensure it is closed, but I feel when we buy it should auto transfer? Why not?
 
-    wasm_cmd $VAULT_CONTRACT '{"withdraw_purchased":{"listing_id":"vault_1"}}' "" show_log
+    wasm_cmd $VAULT_CONTRACT '{"withdraw_purchased":{"listing_id":"2"}}' "" show_log
     # ensure listings are empty now
     listings=$(query_contract $VAULT_CONTRACT '{"get_all_listings":{}}' | jq -r '.data.listings')
     ASSERT_EQUAL "$listings" '[]'
@@ -274,10 +270,10 @@ function test_all_listings {
 
 function test_whitelist {    
     # Selling 25ucosm for 5ujunox
-    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"id":"vault_2","ask":{"native":[{"denom":"ujunox","amount":"5"}],"cw20":[],"nfts":[]},"whitelisted_buyer":"juno1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq93ryqp"}}}' "25ucosm" show_log
+    wasm_cmd $VAULT_CONTRACT '{"create_listing":{"create_msg":{"ask":{"native":[{"denom":"ujunox","amount":"5"}],"cw20":[],"nfts":[]},"whitelisted_buyer":"juno1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq93ryqp"}}}' "25ucosm" show_log
     # Ensure listing went up correctly
-    listing_1=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_2"}}')
-    ASSERT_EQUAL "$listing_1" '{"data":{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","status":"Being Prepared","for_sale":[["ucosm","25"]],"ask":[["ujunox","5"]],"expiration":"None","whitelisted_buyer":"juno1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq93ryqp"}}'
+    listing_1=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"3"}}')
+    ASSERT_EQUAL "$listing_1" '{"data":{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","status":"Being Prepared","for_sale":[["ucosm","25"]],"ask":[["ujunox","5"]],"expiration":"None","whitelisted_buyers":["juno1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq93ryqp"],"whitelist_until":null,"interest":0}}'
 
     # is hidden from market listings, but would be found in the all listings query
     listings=$(query_contract $VAULT_CONTRACT '{"get_listings_for_market":{"page_num":1}}' | jq -r '.data.listings')
@@ -285,36 +281,36 @@ function test_whitelist {
 
     # Ensure the listing is in the whitelist only query
     listings=$(query_contract $VAULT_CONTRACT '{"get_whitelisted_listings":{"address":"juno1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq93ryqp"}}' | jq -rc '.data.listings')
-    ASSERT_EQUAL $listings '[{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","id":"vault_2","finalized_time":null,"expiration_time":null,"status":"being_prepared","claimant":null,"whitelisted_buyer":"juno1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq93ryqp","for_sale":{"native":[{"denom":"ucosm","amount":"25"}],"cw20":[],"nfts":[]},"ask":{"native":[{"denom":"ujunox","amount":"5"}],"cw20":[],"nfts":[]}}]'
+    ASSERT_EQUAL $listings '[{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","id":"3","finalized_time":null,"expiration_time":null,"expiration_height":null,"status":"being_prepared","claimant":null,"whitelisted_buyers":["juno1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq93ryqp"],"whitelist_until":null,"payout_address":null,"auto_swap_to":null,"units_available":null,"on_sale_callback":null,"min_buyer_purchases":0,"vesting":null,"on_expire":"refund","private":false,"frozen":false,"for_sale":{"native":[{"denom":"ucosm","amount":"25"}],"cw20":[],"nfts":[]},"ask":{"native":[{"denom":"ujunox","amount":"5"}],"cw20":[],"nfts":[]},"ask_options":[]}]'
 
     # remove whitelisted buyer test
-    wasm_cmd $VAULT_CONTRACT '{"remove_whitelisted_buyer":{"listing_id":"vault_2"}}' "" show_log
-    listing_1_change=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_2"}}')
-    ASSERT_EQUAL "$listing_1_change" '{"data":{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","status":"Being Prepared","for_sale":[["ucosm","25"]],"ask":[["ujunox","5"]],"expiration":"None","whitelisted_buyer":"None"}}'
+    wasm_cmd $VAULT_CONTRACT '{"remove_whitelisted_buyer":{"listing_id":"3"}}' "" show_log
+    listing_1_change=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"3"}}')
+    ASSERT_EQUAL "$listing_1_change" '{"data":{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","status":"Being Prepared","for_sale":[["ucosm","25"]],"ask":[["ujunox","5"]],"expiration":"None","whitelisted_buyers":[],"whitelist_until":null,"interest":0}}'
 
     # ensure the address no longer is in the whitelist query
     listings=$(query_contract $VAULT_CONTRACT '{"get_whitelisted_listings":{"address":"juno1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq93ryqp"}}' | jq -rc '.data.listings')
     ASSERT_EQUAL $listings '[]'
     # and that it is in the market listings query now
     listing_no_whitelist=$(query_contract $VAULT_CONTRACT '{"get_all_listings":{}}')
-    ASSERT_EQUAL "$listing_no_whitelist" '{"data":{"listings":[{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","id":"vault_2","finalized_time":null,"expiration_time":null,"status":"being_prepared","claimant":null,"whitelisted_buyer":null,"for_sale":{"native":[{"denom":"ucosm","amount":"25"}],"cw20":[],"nfts":[]},"ask":{"native":[{"denom":"ujunox","amount":"5"}],"cw20":[],"nfts":[]}}]}}'
+    ASSERT_EQUAL "$listing_no_whitelist" '{"data":{"listings":[{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","id":"3","finalized_time":null,"expiration_time":null,"expiration_height":null,"status":"being_prepared","claimant":null,"whitelisted_buyers":[],"whitelist_until":null,"payout_address":null,"auto_swap_to":null,"units_available":null,"on_sale_callback":null,"min_buyer_purchases":0,"vesting":null,"on_expire":"refund","private":false,"frozen":false,"for_sale":{"native":[{"denom":"ucosm","amount":"25"}],"cw20":[],"nfts":[]},"ask":{"native":[{"denom":"ujunox","amount":"5"}],"cw20":[],"nfts":[]},"ask_options":[]}]}}'
 
     # change whitelisted buyer to correct address
-    wasm_cmd $VAULT_CONTRACT '{"change_whitelisted_buyer":{"listing_id":"vault_2","new_address":"juno1efd63aw40lxf3n4mhf7dzhjkr453axurv2zdzk"}}' "" show_log
-    listing_1_change=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_2"}}')
-    ASSERT_EQUAL "$listing_1_change" '{"data":{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","status":"Being Prepared","for_sale":[["ucosm","25"]],"ask":[["ujunox","5"]],"expiration":"None","whitelisted_buyer":"juno1efd63aw40lxf3n4mhf7dzhjkr453axurv2zdzk"}}'
+    wasm_cmd $VAULT_CONTRACT '{"change_whitelisted_buyer":{"listing_id":"3","new_address":"juno1efd63aw40lxf3n4mhf7dzhjkr453axurv2zdzk"}}' "" show_log
+    listing_1_change=$(query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"3"}}')
+    ASSERT_EQUAL "$listing_1_change" '{"data":{"creator":"juno1hj5fveer5cjtn4wd6wstzugjfdxzl0xps73ftl","status":"Being Prepared","for_sale":[["ucosm","25"]],"ask":[["ujunox","5"]],"expiration":"None","whitelisted_buyers":["juno1efd63aw40lxf3n4mhf7dzhjkr453axurv2zdzk"],"whitelist_until":null,"interest":0}}'
 
     # finalize just the natives
-    wasm_cmd $VAULT_CONTRACT '{"finalize":{"listing_id":"vault_2","seconds":5000}}' "" show_log
+    wasm_cmd $VAULT_CONTRACT '{"finalize":{"listing_id":"3","seconds":5000}}' "" show_log
 
     # try to buy as the incorrect user (test-user) which is not whitelisted
     wasm_cmd $VAULT_CONTRACT '{"create_bucket":{"bucket_id":"buyer_2"}}' "5ujunox" show_log
-    wasm_cmd $VAULT_CONTRACT '{"buy_listing":{"listing_id":"vault_2","bucket_id":"buyer_2"}}' "" dont_show_log
+    wasm_cmd $VAULT_CONTRACT '{"buy_listing":{"listing_id":"3","bucket_id":"buyer_2"}}' "" dont_show_log
     ASSERT_CONTAINS "$CMD_LOG" 'Not whitelisted'
     # Buy as the whitelisted person
     wasm_cmd $VAULT_CONTRACT '{"create_bucket":{"bucket_id":"buyer_3"}}' "5ujunox" show_log "$TX_FLAGS --keyring-backend test --from other-user"
-    wasm_cmd $VAULT_CONTRACT '{"buy_listing":{"listing_id":"vault_2","bucket_id":"buyer_3"}}' "" show_log "$TX_FLAGS --keyring-backend test --from other-user"
-    wasm_cmd $VAULT_CONTRACT '{"withdraw_purchased":{"listing_id":"vault_2"}}' "" show_log "$TX_FLAGS --keyring-backend test --from other-user"
+    wasm_cmd $VAULT_CONTRACT '{"buy_listing":{"listing_id":"3","bucket_id":"buyer_3"}}' "" show_log "$TX_FLAGS --keyring-backend test --from other-user"
+    wasm_cmd $VAULT_CONTRACT '{"withdraw_purchased":{"listing_id":"3"}}' "" show_log "$TX_FLAGS --keyring-backend test --from other-user"
     # ensure there are 0 listings left
     listings=$(query_contract $VAULT_CONTRACT '{"get_all_listings":{}}' | jq -r '.data.listings')
     ASSERT_EQUAL "$listings" '[]'
@@ -336,7 +332,7 @@ exit $FINAL_STATUS_CODE
 # manual queries
 # query_contract $VAULT_CONTRACT '{"get_config":{}}'
 # query_contract $VAULT_CONTRACT '{"get_all_listings":{}}'
-# query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"vault_1"}}'
+# query_contract $VAULT_CONTRACT '{"get_listing_info":{"listing_id":"2"}}'
 # query_contract $VAULT_CONTRACT '{"get_listings_by_owner":{"owner":"juno1efd63aw40lxf3n4mhf7dzhjkr453axurv2zdzk"}}'
 # query_contract $VAULT_CONTRACT '{"get_buckets":{"bucket_owner":"juno1efd63aw40lxf3n4mhf7dzhjkr453axurv2zdzk"}}'
 # query_contract $VAULT_CONTRACT '{"get_listings_for_market":{"page_num":1}}'
\ No newline at end of file
@@ -1,7 +1,7 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
 };
 use cw2::set_contract_version;
 use cw20::{Balance, Cw20CoinVerified, Cw20ReceiveMsg};
@@ -9,23 +9,57 @@ use cw721::Cw721ReceiveMsg;
 
 use crate::error::ContractError;
 use crate::execute::{
+    execute_accept_highest_bid, execute_accept_offer, execute_accept_partial_offer,
     execute_add_funds_to_sale, execute_add_to_bucket, execute_add_to_bucket_cw721,
-    execute_add_to_sale_cw721, execute_buy_listing, execute_change_ask, execute_create_bucket,
-    execute_create_bucket_cw721, execute_create_listing, execute_create_listing_cw20,
-    execute_create_listing_cw721, execute_finalize, execute_modify_whitelisted_buyer,
-    execute_refund, execute_remove_listing, execute_withdraw_bucket, execute_withdraw_purchased,
+    execute_add_to_sale_cw721, execute_buy_bundle, execute_buy_listing, execute_change_ask,
+    execute_claim_nft, execute_clone_listing, execute_commit_bid, execute_commit_to_listing,
+    execute_convert_to_auction, execute_create_bucket, execute_create_bucket_cw721,
+    execute_create_bundle, execute_create_listing, execute_create_listing_cw20,
+    execute_create_listing_cw20_via_allowance, execute_create_listing_cw721,
+    execute_express_interest, execute_finalize, execute_force_remove_listing,
+    execute_freeze_listing, execute_lock_bucket_to_listing, execute_nft_payout_reply,
+    execute_place_bid, execute_post_bond, execute_process_expiry, execute_propose_swap,
+    execute_prune_archive, execute_queue_admin_action, execute_queued_action, execute_refund,
+    execute_reject_swap, execute_remove_all_listings, execute_remove_listing, execute_reveal_bid,
+    execute_set_bucket_target, execute_set_conversion_rate, execute_set_creator_allowed,
+    execute_set_nft_receiver, execute_set_whitelisted_buyers, execute_settle_auction,
+    execute_settle_commitment, execute_start_sealed_auction, execute_swap_listings,
+    execute_swap_reply, execute_transfer_bucket, execute_transfer_listing,
+    execute_update_auction_params, execute_update_config, execute_wind_down,
+    execute_withdraw_all_buckets, execute_withdraw_bucket, execute_withdraw_purchased,
+    execute_withdraw_purchased_partial, validate_default_listing_ttl, validate_nonzero_limit,
+    ALLOWANCE_LISTING_REPLY_ID, DEFAULT_MAX_NFTS_PER_COLLECTION_PER_LISTING, DEFAULT_MAX_PAGE_SIZE,
+    DEFAULT_MAX_TRANSFERS_PER_BUY, MAX_FEE_BPS, MAX_REFERRAL_BPS, MAX_REVEAL_SLASH_BPS,
+    NFT_PAYOUT_REPLY_ID, SALE_CALLBACK_REPLY_ID, SWAP_REPLY_ID,
 };
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg, ReceiveNftMsg};
 use crate::query::{
-    get_admin, get_all_listings, get_buckets, get_config, get_listing_info, get_listings_by_owner,
-    get_listings_for_market, get_whitelisted_listings,
+    can_buy, get_active_collections, get_active_denoms, get_address_activity, get_admin,
+    get_all_listings, get_archived_listing, get_auction, get_bucket_power, get_bucket_shortfall,
+    get_buckets, get_buckets_with_min_value, get_bundle, get_bundle_contents, get_config,
+    get_floor_history, get_last_sale_price, get_limits, get_listing_for_nft, get_listing_history,
+    get_listing_info, get_listings_accepting_nft, get_listings_by_collection,
+    get_listings_by_owner, get_listings_by_status, get_listings_for_market,
+    get_my_whitelisted_listings, get_next_listing_id, get_offers_by_buyer,
+    get_offers_expiring_soon, get_offers_sorted, get_orphaned_buckets, get_queued_actions,
+    get_recent_sales, get_reconciliation, get_sealed_auction, get_seller_earnings,
+    get_trending_listings, get_tvl_in_denom, get_unique_users, get_whitelisted_listings,
+    simulate_buy,
 };
-use crate::state::{Config, Nft, CONFIG};
+use crate::state::{Config, Nft, CONFIG, NEXT_LISTING_ID, PENDING_ALLOWANCE_LISTING};
 use std::str;
 
 const CONTRACT_NAME: &str = "crates.io:juno_vaults";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Added to every instantiate, execute & reply response as attr_schema_version
+// Bumped whenever the attributes of a response change shape, never within a release
+pub const ATTR_SCHEMA_VERSION: &str = "1";
+
+fn with_schema_version(res: Response) -> Response {
+    res.add_attribute("attr_schema_version", ATTR_SCHEMA_VERSION)
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Instantiate
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -41,19 +75,86 @@ pub fn instantiate(
 
     let validated_admin =
         deps.api.addr_validate(&msg.admin.unwrap_or_else(|| info.sender.to_string()))?;
+    let fee_collector = match msg.fee_collector {
+        Some(fee_collector) => deps.api.addr_validate(&fee_collector)?,
+        None => validated_admin.clone(),
+    };
+
+    let maker_fee_bps = msg.maker_fee_bps.unwrap_or_default();
+    let taker_fee_bps = msg.taker_fee_bps.unwrap_or_default();
+    if maker_fee_bps > MAX_FEE_BPS || taker_fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::FeeTooHigh {
+            max: MAX_FEE_BPS,
+        });
+    }
+
+    let swap_router = msg.swap_router.map(|router| deps.api.addr_validate(&router)).transpose()?;
+
+    let default_listing_ttl = msg.default_listing_ttl.unwrap_or_default();
+    validate_default_listing_ttl(default_listing_ttl)?;
+
+    let max_transfers_per_buy = msg.max_transfers_per_buy.unwrap_or(DEFAULT_MAX_TRANSFERS_PER_BUY);
+    validate_nonzero_limit("max_transfers_per_buy", max_transfers_per_buy)?;
+    let max_page_size = msg.max_page_size.unwrap_or(DEFAULT_MAX_PAGE_SIZE);
+    validate_nonzero_limit("max_page_size", max_page_size)?;
+
+    let referral_bps = msg.referral_bps.unwrap_or_default();
+    if referral_bps > MAX_REFERRAL_BPS {
+        return Err(ContractError::ReferralTooHigh {
+            max: MAX_REFERRAL_BPS,
+        });
+    }
+
+    let reveal_deposit_slash_bps = msg.reveal_deposit_slash_bps.unwrap_or_default();
+    if reveal_deposit_slash_bps > MAX_REVEAL_SLASH_BPS {
+        return Err(ContractError::SlashTooHigh {
+            max: MAX_REVEAL_SLASH_BPS,
+        });
+    }
 
     CONFIG
         .save(
             deps.storage,
             &Config {
                 admin: validated_admin.clone(),
+                refund_grace_seconds: msg.refund_grace_seconds.unwrap_or_default(),
+                auto_match: msg.auto_match.unwrap_or_default(),
+                rounding: msg.rounding.unwrap_or_default(),
+                max_transfers_per_buy,
+                maker_fee_bps,
+                taker_fee_bps,
+                fee_collector,
+                validate_cw20_asks: msg.validate_cw20_asks.unwrap_or_default(),
+                wind_down: false,
+                admin_timelock_seconds: msg.admin_timelock_seconds.unwrap_or_default(),
+                listing_bond: msg.listing_bond.filter(|bond| !bond.amount.is_zero()),
+                min_market_ask_value: msg.min_market_ask_value.unwrap_or_default(),
+                default_listing_ttl,
+                referral_bps,
+                max_page_size,
+                min_active_seconds: msg.min_active_seconds.unwrap_or_default(),
+                swap_router,
+                max_nfts_per_collection_per_listing: msg
+                    .max_nfts_per_collection_per_listing
+                    .unwrap_or(DEFAULT_MAX_NFTS_PER_COLLECTION_PER_LISTING),
+                restrict_creators: msg.restrict_creators.unwrap_or_default(),
+                fee_holiday_until: msg.fee_holiday_until,
+                reveal_deposit_slash_bps,
+                buy_cooldown_seconds: msg.buy_cooldown_seconds.unwrap_or_default(),
+                min_offer_value: msg.min_offer_value.unwrap_or_default(),
+                max_offers: msg.max_offers.unwrap_or_default(),
+                dust_threshold: msg.dust_threshold.unwrap_or_default(),
             },
         )
         .map_err(|_e| ContractError::InitInvalidAddr)?;
 
-    Ok(Response::new()
-        .add_attribute("action", "instantiate")
-        .add_attribute("admin", validated_admin.to_string()))
+    NEXT_LISTING_ID.save(deps.storage, &1)?;
+
+    Ok(with_schema_version(
+        Response::new()
+            .add_attribute("action", "instantiate")
+            .add_attribute("admin", validated_admin.to_string()),
+    ))
 }
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -67,40 +168,80 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    let res = match msg {
         // ~~~~
         // Receive Wrappers
         ExecuteMsg::Receive(receive_msg) => execute_receive(deps, &env, &info, &receive_msg),
-        ExecuteMsg::ReceiveNft(receive_nft_msg) => execute_receive_nft(deps, info, receive_nft_msg),
+        ExecuteMsg::ReceiveNft(receive_nft_msg) => {
+            execute_receive_nft(deps, &env, info, receive_nft_msg)
+        }
         // ~~~~
         // Listing Executions
         ExecuteMsg::CreateListing {
             create_msg,
-        } => execute_create_listing(deps, &info.sender, &Balance::from(info.funds), create_msg),
+        } => {
+            execute_create_listing(deps, &env, &info.sender, &Balance::from(info.funds), create_msg)
+        }
+        ExecuteMsg::CloneListing {
+            source_listing_id,
+        } => execute_clone_listing(
+            deps,
+            &env,
+            &info.sender,
+            source_listing_id,
+            &Balance::from(info.funds),
+        ),
+        ExecuteMsg::CreateListingCw20ViaAllowance {
+            create_msg,
+            token,
+            amount,
+        } => execute_create_listing_cw20_via_allowance(
+            deps,
+            &env,
+            &info.sender,
+            create_msg,
+            &token,
+            amount,
+        ),
         ExecuteMsg::AddFundsToSaleNative {
             listing_id,
         } => execute_add_funds_to_sale(deps, Balance::from(info.funds), &info.sender, listing_id),
         ExecuteMsg::ChangeAsk {
             listing_id,
             new_ask,
-        } => execute_change_ask(deps, &info.sender, listing_id, new_ask),
+        } => execute_change_ask(deps, &env, &info.sender, listing_id, new_ask),
         ExecuteMsg::ChangeWhitelistedBuyer {
             listing_id,
             new_address,
-        } => execute_modify_whitelisted_buyer(deps, &info.sender, listing_id, Some(new_address)),
+        } => execute_set_whitelisted_buyers(deps, &info.sender, listing_id, vec![new_address]),
         ExecuteMsg::RemoveWhitelistedBuyer {
             listing_id,
-        } => execute_modify_whitelisted_buyer(deps, &info.sender, listing_id, None),
+        } => execute_set_whitelisted_buyers(deps, &info.sender, listing_id, Vec::new()),
+        ExecuteMsg::SetWhitelistedBuyers {
+            listing_id,
+            addresses,
+        } => execute_set_whitelisted_buyers(deps, &info.sender, listing_id, addresses),
+        ExecuteMsg::TransferListing {
+            listing_id,
+            new_owner,
+        } => execute_transfer_listing(deps, &info.sender, listing_id, &new_owner),
         ExecuteMsg::RemoveListing {
             listing_id,
-        } => execute_remove_listing(deps, &info.sender, listing_id),
+        } => execute_remove_listing(deps, &env, &info.sender, listing_id),
         ExecuteMsg::Finalize {
             listing_id,
             seconds,
-        } => execute_finalize(deps, &env, &info.sender, listing_id, seconds),
+            expiration_height,
+        } => execute_finalize(deps, &env, &info.sender, listing_id, seconds, expiration_height),
         ExecuteMsg::RefundExpired {
             listing_id,
         } => execute_refund(deps, &env, &info.sender, listing_id),
+        ExecuteMsg::ProcessExpiry {
+            listing_id,
+        } => execute_process_expiry(deps, &env, listing_id),
+        ExecuteMsg::ExpressInterest {
+            listing_id,
+        } => execute_express_interest(deps, &info.sender, listing_id),
         // ~~~~
         // Bucket Executions <purchasing>
         ExecuteMsg::CreateBucket {
@@ -108,26 +249,216 @@ pub fn execute(
         } => execute_create_bucket(deps, &Balance::from(info.funds), &info.sender, &bucket_id),
         ExecuteMsg::AddToBucket {
             bucket_id,
-        } => execute_add_to_bucket(deps, Balance::from(info.funds), &info.sender, bucket_id),
+        } => execute_add_to_bucket(deps, &env, Balance::from(info.funds), &info.sender, bucket_id),
         ExecuteMsg::RemoveBucket {
             bucket_id,
-        } => execute_withdraw_bucket(deps, &info.sender, &bucket_id),
+        } => execute_withdraw_bucket(deps, &env, &info.sender, &bucket_id),
+        ExecuteMsg::TransferBucket {
+            bucket_id,
+            new_owner,
+        } => execute_transfer_bucket(deps, &env, &info.sender, &bucket_id, &new_owner),
+        ExecuteMsg::RemoveAllListings {
+            start_after,
+            limit,
+        } => execute_remove_all_listings(deps, &env, &info.sender, start_after, limit),
+        ExecuteMsg::WithdrawAllBuckets {
+            start_after,
+            limit,
+        } => execute_withdraw_all_buckets(deps, &env, &info.sender, start_after, limit),
+        ExecuteMsg::SetBucketTarget {
+            bucket_id,
+            target_listing,
+        } => execute_set_bucket_target(deps, &info.sender, &bucket_id, target_listing),
         // ~~~~
         // Marketplace Executions
         ExecuteMsg::BuyListing {
             listing_id,
             bucket_id,
-        } => execute_buy_listing(deps, &env, &info.sender, listing_id, &bucket_id),
+            max_price,
+            referrer,
+        } => execute_buy_listing(
+            deps,
+            &env,
+            &info.sender,
+            listing_id,
+            &bucket_id,
+            max_price,
+            referrer,
+        ),
+        ExecuteMsg::CommitToListing {
+            listing_id,
+            bucket_id,
+        } => execute_commit_to_listing(deps, &env, &info.sender, listing_id, &bucket_id),
+        ExecuteMsg::LockBucketToListing {
+            bucket_id,
+            listing_id,
+            expiration_seconds,
+        } => execute_lock_bucket_to_listing(
+            deps,
+            &env,
+            &info.sender,
+            &bucket_id,
+            listing_id,
+            expiration_seconds,
+        ),
+        ExecuteMsg::AcceptOffer {
+            listing_id,
+            buyer,
+            bucket_id,
+        } => execute_accept_offer(deps, &env, &info.sender, listing_id, &buyer, &bucket_id),
+        ExecuteMsg::AcceptPartialOffer {
+            listing_id,
+            buyer,
+            bucket_id,
+            accepted_assets,
+        } => execute_accept_partial_offer(
+            deps,
+            &env,
+            &info.sender,
+            listing_id,
+            &buyer,
+            &bucket_id,
+            accepted_assets,
+        ),
+        ExecuteMsg::SettleCommitment {
+            listing_id,
+        } => execute_settle_commitment(deps, &env, listing_id),
         ExecuteMsg::WithdrawPurchased {
             listing_id,
-        } => execute_withdraw_purchased(deps, &info.sender, listing_id),
-    }
+        } => execute_withdraw_purchased(deps, &env, &info.sender, listing_id),
+        ExecuteMsg::StartSealedAuction {
+            listing_id,
+            commit_seconds,
+            reveal_seconds,
+        } => execute_start_sealed_auction(
+            deps,
+            &env,
+            &info.sender,
+            listing_id,
+            commit_seconds,
+            reveal_seconds,
+        ),
+        ExecuteMsg::CommitBid {
+            listing_id,
+            commitment,
+        } => execute_commit_bid(deps, &env, &info, listing_id, commitment),
+        ExecuteMsg::RevealBid {
+            listing_id,
+            amount,
+            nonce,
+        } => execute_reveal_bid(deps, &env, &info.sender, listing_id, amount, &nonce),
+        ExecuteMsg::SettleAuction {
+            listing_id,
+        } => execute_settle_auction(deps, &env, listing_id),
+        ExecuteMsg::ConvertToAuction {
+            listing_id,
+            reserve,
+            min_increment,
+            end_time,
+        } => execute_convert_to_auction(
+            deps,
+            &env,
+            &info.sender,
+            listing_id,
+            reserve,
+            min_increment,
+            end_time,
+        ),
+        ExecuteMsg::UpdateAuctionParams {
+            listing_id,
+            reserve,
+            min_increment,
+            end_time,
+        } => execute_update_auction_params(
+            deps,
+            &env,
+            &info.sender,
+            listing_id,
+            reserve,
+            min_increment,
+            end_time,
+        ),
+        ExecuteMsg::CreateBundle {
+            listing_ids,
+            price,
+        } => execute_create_bundle(deps, &info.sender, listing_ids, price),
+        ExecuteMsg::BuyBundle {
+            bundle_id,
+            bucket_id,
+        } => execute_buy_bundle(deps, &env, &info.sender, bundle_id, &bucket_id),
+        ExecuteMsg::AcceptHighestBid {
+            listing_id,
+        } => execute_accept_highest_bid(deps, &env, &info.sender, listing_id),
+        ExecuteMsg::PlaceBid {
+            listing_id,
+            amount,
+        } => execute_place_bid(deps, &env, &info, listing_id, amount),
+        ExecuteMsg::ProposeSwap {
+            listing_id,
+            counter_listing_id,
+        } => execute_propose_swap(deps, &env, &info.sender, listing_id, counter_listing_id),
+        ExecuteMsg::AcceptSwap {
+            proposer_listing_id,
+            listing_id,
+        } => execute_swap_listings(deps, &env, &info.sender, proposer_listing_id, listing_id),
+        ExecuteMsg::RejectSwap {
+            proposer_listing_id,
+            listing_id,
+        } => execute_reject_swap(deps, &info.sender, proposer_listing_id, listing_id),
+        ExecuteMsg::WithdrawPurchasedPartial {
+            listing_id,
+            assets,
+        } => execute_withdraw_purchased_partial(deps, &info.sender, listing_id, assets),
+        // ~~~~
+        // Admin Executions
+        ExecuteMsg::PostBond {
+            listing_id,
+        } => execute_post_bond(deps, info, listing_id),
+        ExecuteMsg::ForceRemoveListing {
+            listing_id,
+        } => execute_force_remove_listing(deps, &info.sender, listing_id),
+        ExecuteMsg::FreezeListing {
+            listing_id,
+            frozen,
+        } => execute_freeze_listing(deps, &info.sender, listing_id, frozen),
+        ExecuteMsg::UpdateConfig {
+            config_msg,
+        } => execute_update_config(deps, &info.sender, config_msg),
+        ExecuteMsg::QueueAdminAction {
+            action,
+        } => execute_queue_admin_action(deps, &env, &info.sender, action),
+        ExecuteMsg::ExecuteQueuedAction {
+            action_id,
+        } => execute_queued_action(deps, &env, &info.sender, action_id),
+        ExecuteMsg::WindDown {} => execute_wind_down(deps, &info.sender),
+        ExecuteMsg::PruneArchive {
+            listing_ids,
+        } => execute_prune_archive(deps, &info.sender, listing_ids),
+        ExecuteMsg::SetNftReceiver {
+            address,
+            known,
+        } => execute_set_nft_receiver(deps, &info.sender, &address, known),
+        ExecuteMsg::SetCreatorAllowed {
+            address,
+            allowed,
+        } => execute_set_creator_allowed(deps, &info.sender, &address, allowed),
+        ExecuteMsg::SetConversionRate {
+            from,
+            to,
+            rate,
+        } => execute_set_conversion_rate(deps, &info.sender, &from, &to, rate),
+        ExecuteMsg::ClaimNft {
+            contract,
+            token_id,
+        } => execute_claim_nft(deps, &info.sender, &contract, &token_id),
+    };
+    Ok(with_schema_version(res?))
 }
 
 // CW20 Filter
 pub fn execute_receive(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
     wrapper: &Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
@@ -142,7 +473,9 @@ pub fn execute_receive(
     match msg {
         ReceiveMsg::CreateListingCw20 {
             create_msg,
-        } => execute_create_listing_cw20(deps, &user_wallet, &info.sender, &balance, create_msg),
+        } => {
+            execute_create_listing_cw20(deps, env, &user_wallet, &info.sender, &balance, create_msg)
+        }
         ReceiveMsg::AddFundsToSaleCw20 {
             listing_id,
         } => execute_add_funds_to_sale(deps, balance, &user_wallet, listing_id),
@@ -151,13 +484,14 @@ pub fn execute_receive(
         } => execute_create_bucket(deps, &balance, &user_wallet, &bucket_id),
         ReceiveMsg::AddToBucketCw20 {
             bucket_id,
-        } => execute_add_to_bucket(deps, balance, &user_wallet, bucket_id),
+        } => execute_add_to_bucket(deps, env, balance, &user_wallet, bucket_id),
     }
 }
 
 // CW721 filter
 pub fn execute_receive_nft(
     deps: DepsMut,
+    env: &Env,
     info: MessageInfo,
     wrapper: Cw721ReceiveMsg,
 ) -> Result<Response, ContractError> {
@@ -172,7 +506,7 @@ pub fn execute_receive_nft(
     match msg {
         ReceiveNftMsg::CreateListingCw721 {
             create_msg,
-        } => execute_create_listing_cw721(deps, &user_wallet, incoming_nft, create_msg),
+        } => execute_create_listing_cw721(deps, env, &user_wallet, incoming_nft, create_msg),
         ReceiveNftMsg::AddToListingCw721 {
             listing_id,
         } => execute_add_to_sale_cw721(deps, &user_wallet, incoming_nft, listing_id),
@@ -181,22 +515,85 @@ pub fn execute_receive_nft(
         } => execute_create_bucket_cw721(deps, &user_wallet, incoming_nft, &bucket_id),
         ReceiveNftMsg::AddToBucketCw721 {
             bucket_id,
-        } => execute_add_to_bucket_cw721(deps, &user_wallet, incoming_nft, bucket_id),
+        } => execute_add_to_bucket_cw721(deps, env, &user_wallet, incoming_nft, bucket_id),
     }
 }
 
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Reply
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let res = match msg.id {
+        // TransferFrom succeeded, the tokens are held by the contract now
+        ALLOWANCE_LISTING_REPLY_ID => {
+            let pending = PENDING_ALLOWANCE_LISTING.load(deps.storage)?;
+            PENDING_ALLOWANCE_LISTING.remove(deps.storage);
+
+            let balance = Balance::Cw20(Cw20CoinVerified {
+                address: pending.token.clone(),
+                amount: pending.amount,
+            });
+
+            execute_create_listing_cw20(
+                deps,
+                &env,
+                &pending.creator,
+                &pending.token,
+                &balance,
+                pending.create_msg,
+            )
+        }
+        // NFT payout finished, a failed one is saved as a claim
+        NFT_PAYOUT_REPLY_ID => execute_nft_payout_reply(deps, msg.result.is_err()),
+        // Swap of the sale proceeds finished, a failed one leaves them in the payee's bucket
+        SWAP_REPLY_ID => execute_swap_reply(deps, msg.result.is_err()),
+        // Seller's sale callback failed, the sale stands
+        SALE_CALLBACK_REPLY_ID => Ok(Response::new().add_attribute("sale_callback", "failed")),
+        id => Err(ContractError::UnknownReplyId {
+            id,
+        }),
+    };
+    Ok(with_schema_version(res?))
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Query
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    let res = match msg {
         QueryMsg::GetAdmin {} => to_binary(&get_admin(deps)?),
         QueryMsg::GetConfig {} => to_binary(&get_config(deps)?),
+        QueryMsg::GetLimits {} => to_binary(&get_limits(deps)?),
+        QueryMsg::GetQueuedActions {
+            start_after,
+            limit,
+        } => to_binary(&get_queued_actions(deps, start_after, limit)?),
+        QueryMsg::GetNextListingId {} => to_binary(&get_next_listing_id(deps)?),
+        QueryMsg::GetUniqueUsers {} => to_binary(&get_unique_users(deps)?),
         QueryMsg::GetListingInfo {
             listing_id,
         } => to_binary(&get_listing_info(deps, listing_id)?),
+        QueryMsg::GetBundleContents {
+            listing_id,
+        } => to_binary(&get_bundle_contents(deps, listing_id)?),
+        QueryMsg::GetActiveCollections {
+            start_after,
+            limit,
+        } => to_binary(&get_active_collections(deps, start_after, limit)?),
+        QueryMsg::GetActiveDenoms {
+            start_after,
+            limit,
+        } => to_binary(&get_active_denoms(deps, start_after, limit)?),
+        QueryMsg::GetArchivedListing {
+            listing_id,
+        } => to_binary(&get_archived_listing(deps, &listing_id)?),
+        QueryMsg::GetListingHistory {
+            listing_id,
+        } => to_binary(&get_listing_history(deps, &listing_id)?),
         QueryMsg::GetListingsByOwner {
             owner,
         } => to_binary(&get_listings_by_owner(deps, &owner)?),
@@ -204,11 +601,119 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetBuckets {
             bucket_owner,
         } => to_binary(&get_buckets(deps, &bucket_owner)?),
+        QueryMsg::GetBucketShortfall {
+            bucket_owner,
+            bucket_id,
+            listing_id,
+        } => to_binary(&get_bucket_shortfall(deps, &bucket_owner, &bucket_id, listing_id)?),
+        QueryMsg::GetBucketPower {
+            bucket_owner,
+            bucket_id,
+        } => to_binary(&get_bucket_power(deps, &env, &bucket_owner, &bucket_id)?),
+        QueryMsg::CanBuy {
+            listing_id,
+            address,
+        } => to_binary(&can_buy(deps, &env, listing_id, &address)?),
+        QueryMsg::SimulateBuy {
+            bucket_owner,
+            bucket_id,
+            listing_id,
+        } => to_binary(&simulate_buy(deps, &env, &bucket_owner, &bucket_id, listing_id)?),
+        QueryMsg::GetOrphanedBuckets {
+            owner,
+        } => to_binary(&get_orphaned_buckets(deps, &owner)?),
+        QueryMsg::GetBucketsWithMinValue {
+            denom,
+            min,
+            limit,
+        } => to_binary(&get_buckets_with_min_value(deps, &denom, min, limit)?),
         QueryMsg::GetListingsForMarket {
             page_num,
         } => to_binary(&get_listings_for_market(deps, &env, page_num)?),
+        QueryMsg::GetTrendingListings {
+            limit,
+        } => to_binary(&get_trending_listings(deps, &env, limit)?),
         QueryMsg::GetWhitelistedListings {
             address,
         } => to_binary(&get_whitelisted_listings(deps, &address)?),
-    }
+        QueryMsg::GetMyWhitelistedListings {
+            address,
+            status,
+            start_after,
+            limit,
+        } => to_binary(&get_my_whitelisted_listings(
+            deps,
+            &env,
+            &address,
+            status,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GetListingsAcceptingNft {
+            contract,
+            token_id,
+        } => to_binary(&get_listings_accepting_nft(deps, &contract, &token_id)?),
+        QueryMsg::GetListingsByStatus {
+            status,
+            start_after,
+            limit,
+        } => to_binary(&get_listings_by_status(deps, status, start_after, limit)?),
+        QueryMsg::GetAddressActivity {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&get_address_activity(deps, &address, start_after, limit)?),
+        QueryMsg::GetLastSalePrice {
+            contract,
+            denom,
+        } => to_binary(&get_last_sale_price(deps, &contract, &denom)?),
+        QueryMsg::GetSellerEarnings {
+            seller,
+        } => to_binary(&get_seller_earnings(deps, &seller)?),
+        QueryMsg::GetFloorHistory {
+            contract,
+            denom,
+            limit,
+        } => to_binary(&get_floor_history(deps, &contract, &denom, limit)?),
+        QueryMsg::GetTvlInDenom {
+            denom,
+        } => to_binary(&get_tvl_in_denom(deps, &denom)?),
+        QueryMsg::GetReconciliation {
+            denom,
+        } => to_binary(&get_reconciliation(deps, &env, &denom)?),
+        QueryMsg::GetSealedAuction {
+            listing_id,
+        } => to_binary(&get_sealed_auction(deps, listing_id)?),
+        QueryMsg::GetAuction {
+            listing_id,
+        } => to_binary(&get_auction(deps, listing_id)?),
+        QueryMsg::GetBundle {
+            bundle_id,
+        } => to_binary(&get_bundle(deps, bundle_id)?),
+        QueryMsg::GetRecentSales {
+            limit,
+        } => to_binary(&get_recent_sales(deps, limit)?),
+        QueryMsg::GetListingForNft {
+            contract,
+            token_id,
+        } => to_binary(&get_listing_for_nft(deps, &contract, &token_id)?),
+        QueryMsg::GetListingsByCollection {
+            contract,
+            start_after,
+            limit,
+        } => to_binary(&get_listings_by_collection(deps, &contract, start_after, limit)?),
+        QueryMsg::GetOffersExpiringSoon {
+            within_seconds,
+            limit,
+        } => to_binary(&get_offers_expiring_soon(deps, &env, within_seconds, limit)?),
+        QueryMsg::GetOffersSorted {
+            listing_id,
+            denom,
+        } => to_binary(&get_offers_sorted(deps, &env, listing_id, &denom)?),
+        QueryMsg::GetOffersByBuyer {
+            buyer,
+            limit,
+        } => to_binary(&get_offers_by_buyer(deps, &env, &buyer, limit)?),
+    };
+    Ok(res?)
 }
@@ -27,6 +27,9 @@ pub enum ContractError {
     #[error("No Tokens have been sent")]
     NoTokens {},
 
+    #[error("Only allowlisted creators can create listings")]
+    NotAllowedToCreate {},
+
     #[error("Listing already finalized")]
     AlreadyFinalized {},
 
@@ -52,8 +55,15 @@ pub enum ContractError {
         id: String,
     },
 
-    #[error("Load bucket error")]
-    LoadBucketError {},
+    #[error("Listing {id} not found")]
+    ListingNotFound {
+        id: String,
+    },
+
+    #[error("Bucket {id} not found")]
+    BucketNotFound {
+        id: String,
+    },
 
     #[error("Invalid Expiration")]
     InvalidExpiration {},
@@ -77,4 +87,212 @@ pub enum ContractError {
 
     #[error("Fee calculation error")]
     FeeCalc,
+
+    #[error("Coin amount overflowed or underflowed")]
+    Overflow {},
+
+    #[error("Listing is finalized, its contents can't change")]
+    ListingFinalized {},
+
+    #[error("Listing is frozen")]
+    ListingFrozen {},
+
+    #[error("Ask is higher than the max price")]
+    PriceSlippage {},
+
+    #[error("Buyer doesn't have enough completed purchases for this listing")]
+    BuyerReputationTooLow {},
+
+    #[error("Nothing has vested since the last withdrawal")]
+    NothingVested {},
+
+    #[error("Vesting listings are withdrawn as they vest, not in part")]
+    PartialWithdrawVesting {},
+
+    #[error("Assets to withdraw must be held by the listing")]
+    NotInListing {},
+
+    #[error("Contract is wound down, only withdrawals & refunds are allowed")]
+    WoundDown {},
+
+    #[error("Ask has a cw20 that isn't a token contract: {address}")]
+    InvalidCw20Ask {
+        address: String,
+    },
+
+    #[error("Maker & taker fees can't be more than {max} bps")]
+    FeeTooHigh {
+        max: u64,
+    },
+
+    #[error("Buy would make {count} transfers, more than the max of {max}")]
+    TooManyTransfers {
+        count: usize,
+        max: u32,
+    },
+
+    #[error("Bucket is committed to buying listing {listing_id}")]
+    BucketCommitted {
+        listing_id: String,
+    },
+
+    #[error("Listing is committed to another buyer")]
+    ListingCommitted {},
+
+    #[error("Bucket is offered for listing {listing_id}")]
+    BucketOffered {
+        listing_id: String,
+    },
+
+    #[error("Offer is expired")]
+    OfferExpired {},
+
+    #[error("Accepted assets must be held by the offered bucket")]
+    NotInOffer {},
+
+    #[error(
+        "Offers without NFTs must hold at least the minimum of each denom, in denoms with one"
+    )]
+    OfferTooLow {},
+
+    #[error("Listing can't be removed for another {seconds} seconds")]
+    ListingTooNew {
+        seconds: u64,
+    },
+
+    #[error("Address can't buy again for another {seconds} seconds")]
+    BuyCooldown {
+        seconds: u64,
+    },
+
+    #[error("Listing must post a bond of {bond} before it's finalized")]
+    BondRequired {
+        bond: String,
+    },
+
+    #[error("Bond must be exactly {bond}")]
+    InvalidBond {
+        bond: String,
+    },
+
+    #[error("Listing doesn't need a bond")]
+    BondNotRequired {},
+
+    #[error("Listing is sold by auction")]
+    SealedAuction {},
+
+    #[error("Listing isn't sold by sealed-bid auction")]
+    NotSealedAuction {},
+
+    #[error("Listing isn't sold by open auction")]
+    NotAuction {},
+
+    #[error("Auction params can't change once it has a bid")]
+    AuctionHasBids {},
+
+    #[error("Auction has no bid yet, bids have to meet its reserve")]
+    ReserveNotMet {},
+
+    #[error("Only finalized fixed-price listings without a pending buyer can be auctioned")]
+    NotConvertible {},
+
+    #[error("Sealed-bid auctions need an ask of a single native coin")]
+    InvalidAuctionAsk {},
+
+    #[error("Units need an evenly split fungible only listing, without vesting or an auction")]
+    InvalidUnits {},
+
+    #[error(
+        "Only finalized listings can be swapped, not once bought, vesting, in units or auctioned"
+    )]
+    NotSwappable {},
+
+    #[error("No swap was proposed between these listings")]
+    NoSwapProposal {},
+
+    #[error(
+        "Bundles need 2 to {max} listings of one owner, paid out to one address & not in units"
+    )]
+    InvalidBundle {
+        max: usize,
+    },
+
+    #[error("Bundled listing {id} is no longer for sale")]
+    BundleUnavailable {
+        id: String,
+    },
+
+    #[error("Auction isn't in its {phase} phase")]
+    AuctionPhase {
+        phase: String,
+    },
+
+    #[error("Address already bid on this auction")]
+    AlreadyBid {},
+
+    #[error("Bid escrow must be a single coin of {denom}")]
+    InvalidEscrow {
+        denom: String,
+    },
+
+    #[error("Revealed amount & nonce don't match the commitment")]
+    InvalidReveal {},
+
+    #[error("Bid must be at least the reserve & covered by its escrow, taker fee included")]
+    BidOutOfRange {},
+
+    #[error("Config changes must be queued while the admin timelock is set")]
+    TimelockActive {},
+
+    #[error("Queued action can't be executed before {eta}")]
+    ActionNotReady {
+        eta: u64,
+    },
+
+    #[error("Listing can't have more than {max} ask options")]
+    TooManyAskOptions {
+        max: usize,
+    },
+
+    #[error("Listing already has {max} offers, a new one must beat the lowest")]
+    TooManyOffers {
+        max: u32,
+    },
+
+    #[error("Listing can hold at most {max} NFTs from {collection}")]
+    TooManyNftsFromCollection {
+        collection: String,
+        max: u32,
+    },
+
+    #[error("Whitelist can't have more than {max} addresses")]
+    WhitelistTooLarge {
+        max: usize,
+    },
+
+    #[error("Referral can't be more than {max} bps of the marketplace fee")]
+    ReferralTooHigh {
+        max: u64,
+    },
+
+    #[error("Reveal slash can't be more than {max} bps of a bid's escrow")]
+    SlashTooHigh {
+        max: u64,
+    },
+
+    #[error("Referrer can't be the buyer or seller")]
+    InvalidReferrer {},
+
+    #[error("Invalid config, {field} can't be 0")]
+    InvalidConfig {
+        field: String,
+    },
+
+    #[error("Seller would net less than the listing's minimum proceeds after fees")]
+    NetProceedsTooLow {},
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId {
+        id: u64,
+    },
 }
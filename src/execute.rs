@@ -1,12 +1,85 @@
 use crate::error::ContractError;
-use crate::msg::CreateListingMsg;
+use crate::msg::{
+    AdminAction, CreateListingMsg, SaleCallbackMsg, SwapRouterExecuteMsg, UpdateConfigMsg,
+};
 use crate::state::{
-    genbal_from_nft, listingz, Bucket, GenericBalance, GenericBalanceUtil, Listing, Nft, Status,
-    ToGenericBalance, BUCKETS,
+    bucket_offers, genbal_from_nft, listingz, ActivityRole, AskChange, Auction, AuctionBid, Bucket,
+    Bundle, Config, FloorSnapshot, GenericBalance, GenericBalanceUtil, Listing, Nft, Offer,
+    OnExpire, PendingAllowanceListing, PendingSwap, QueuedAction, Sale, SaleCallback,
+    SealedAuction, SealedBid, Status, ToGenericBalance, Vesting, ACTIVE_COLLECTIONS,
+    ADDRESS_ACTIVITY, ARCHIVED_LISTINGS, ASK_HISTORY, AUCTIONS, BUCKETS, BUCKET_COMMITMENT,
+    BUNDLES, COLLECTION_LISTINGS, CONFIG, CONVERSION_RATES, CREATOR_ALLOWLIST, FLOOR_HISTORY,
+    INTEREST_COUNT, LAST_BUY, LAST_SALE_PRICE, LISTING_BOND, LISTING_COMMITMENT, LISTING_CREATED,
+    LISTING_INTEREST, LISTING_SELLER, MAX_ASK_HISTORY, MAX_FLOOR_HISTORY, MAX_RECENT_SALES,
+    NEXT_ACTION_ID, NEXT_BUNDLE_ID, NEXT_LISTING_ID, NFT_CLAIMS, NFT_LISTING, NFT_RECEIVERS,
+    PENDING_ALLOWANCE_LISTING, PENDING_NFT_PAYOUTS, PENDING_SWAP, PURCHASE_COUNT, QUEUED_ACTIONS,
+    RECENT_SALES, SEALED_AUCTION, SEALED_BIDS, SEEN_USERS, SELLER_EARNINGS, SWAP_PROPOSALS,
+    UNIQUE_USERS,
+};
+use crate::utils::{
+    add_juno, amount_of, bucket_satisfies_ask, calc_fee, checked_add, checked_sub,
+    maker_taker_fee_msg, maker_taker_fees, normalize_ask_error_on_dup, offer_meets_minimum,
+    send_tokens_cosmos, sub_juno, transfer_count,
 };
-use crate::utils::{calc_fee, normalize_ask_error_on_dup, send_tokens_cosmos};
-use cosmwasm_std::{Addr, DepsMut, Env, Response};
-use cw20::Balance;
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, HexBinary, MessageInfo,
+    Order, Response, StdResult, Storage, SubMsg, Timestamp, Uint128, WasmMsg,
+};
+use cw20::{Balance, Cw20ExecuteMsg, Cw20QueryMsg, TokenInfoResponse};
+use cw_storage_plus::{Bound, KeyDeserialize};
+use sha2::{Digest, Sha256};
+
+// Reply ID for the TransferFrom sent by CreateListingCw20ViaAllowance
+pub const ALLOWANCE_LISTING_REPLY_ID: u64 = 1;
+
+// Reply ID for each NFT paid out when a listing is removed
+pub const NFT_PAYOUT_REPLY_ID: u64 = 2;
+
+// Reply ID for the swap of a sale's proceeds through the config's swap router
+pub const SWAP_REPLY_ID: u64 = 3;
+
+// Reply ID for a listing's on sale callback, only replied to when it fails
+pub const SALE_CALLBACK_REPLY_ID: u64 = 4;
+
+// Max addresses in a listing's whitelist
+pub const MAX_WHITELISTED_BUYERS: usize = 50;
+
+// Max buckets refunded by a single WithdrawAllBuckets
+pub const MAX_BUCKETS_PER_WITHDRAW: u32 = 30;
+
+// Max listings removed by a single RemoveAllListings
+pub const MAX_LISTINGS_PER_REMOVE: u32 = 30;
+
+// Max alternatives to a listing's ask
+pub const MAX_ASK_OPTIONS: usize = 5;
+
+// Max transfers a buy can lead to unless set at instantiate
+pub const DEFAULT_MAX_TRANSFERS_PER_BUY: u32 = 50;
+
+// Max NFTs from one collection in a listing unless set at instantiate
+pub const DEFAULT_MAX_NFTS_PER_COLLECTION_PER_LISTING: u32 = 25;
+
+// Cap on paginated query limits unless set at instantiate
+pub const DEFAULT_MAX_PAGE_SIZE: u32 = 30;
+
+// Max maker or taker fee, 10%
+pub const MAX_FEE_BPS: u64 = 1_000;
+
+// Max share of the maker & taker fees paid to a referrer, all of it
+pub const MAX_REFERRAL_BPS: u64 = 10_000;
+
+// Max share of an unrevealed sealed bid's escrow paid to the seller, all of it
+pub const MAX_REVEAL_SLASH_BPS: u64 = 10_000;
+
+// Max listings in a bundle
+pub const MAX_BUNDLE_LISTINGS: usize = 10;
+
+// Bounds of the seconds a listing can be finalized for, 10 minutes to 14 days
+pub const MIN_FINALIZE_SECONDS: u64 = 600;
+pub const MAX_FINALIZE_SECONDS: u64 = 1_209_600;
+
+// Max finalized listings checked for an auto match when creating a listing
+pub const MAX_AUTO_MATCH_SCAN: usize = 30;
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Buckets
@@ -17,6 +90,8 @@ pub fn execute_create_bucket(
     creator: &Addr,
     bucket_id: &String,
 ) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+
     // Can't create an empty Bucket
     if funds.is_empty() {
         return Err(ContractError::NoTokens {});
@@ -28,12 +103,14 @@ pub fn execute_create_bucket(
     }
 
     // Save bucket
+    mark_user_seen(deps.storage, creator)?;
     BUCKETS.save(
         deps.storage,
         (creator.clone(), bucket_id),
         &Bucket {
             funds: funds.to_generic(),
             owner: creator.clone(),
+            target_listing: None,
         },
     )?;
 
@@ -48,6 +125,8 @@ pub fn execute_create_bucket_cw721(
     nft: Nft,
     bucket_id: &str,
 ) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+
     // Check that bucket_id isn't used
     if BUCKETS.has(deps.storage, (user_wallet.clone(), bucket_id)) {
         return Err(ContractError::IdAlreadyExists {});
@@ -55,25 +134,54 @@ pub fn execute_create_bucket_cw721(
 
     // NFT validation checks are handled in receiver wrapper
     // Save bucket
+    mark_user_seen(deps.storage, user_wallet)?;
     BUCKETS.save(
         deps.storage,
         (user_wallet.clone(), bucket_id),
         &Bucket {
             funds: genbal_from_nft(nft),
             owner: user_wallet.clone(),
+            target_listing: None,
         },
     )?;
 
     Ok(Response::default())
 }
 
+// Send msgs for a payout, NFTs only use SendNft for receivers known to implement the hook
+fn payout_msgs(
+    storage: &dyn Storage,
+    to: &Addr,
+    balance: &GenericBalance,
+) -> StdResult<Vec<CosmosMsg>> {
+    send_tokens_cosmos(to, balance, NFT_RECEIVERS.has(storage, to))
+}
+
+// Pays out NFTs one submessage each, a failed transfer is left to be claimed instead of reverting
+fn nft_payout_submsgs(
+    storage: &mut dyn Storage,
+    to: &Addr,
+    nfts: &[Nft],
+) -> StdResult<Vec<SubMsg>> {
+    let mut pending = PENDING_NFT_PAYOUTS.may_load(storage)?.unwrap_or_default();
+    let mut submsgs = Vec::new();
+    for nft in nfts {
+        for msg in payout_msgs(storage, to, &genbal_from_nft(nft.clone()))? {
+            submsgs.push(SubMsg::reply_always(msg, NFT_PAYOUT_REPLY_ID));
+        }
+        pending.push((to.clone(), nft.clone()));
+    }
+    PENDING_NFT_PAYOUTS.save(storage, &pending)?;
+    Ok(submsgs)
+}
+
 fn get_bucket_if_sender_is_owner(
     deps: &DepsMut,
     sender: &Addr,
     bucket_id: &str,
 ) -> Result<Bucket, ContractError> {
     let Some(the_bucket) = BUCKETS.may_load(deps.storage, (sender.clone(), bucket_id))? else {
-        return Err(ContractError::NotFound { typ: "Bucket".to_string(), id: bucket_id.to_string() })
+        return Err(ContractError::BucketNotFound { id: bucket_id.to_string() })
     };
 
     // Authorized check
@@ -84,8 +192,79 @@ fn get_bucket_if_sender_is_owner(
     Ok(the_bucket)
 }
 
+// A height expiry is checked as is, a time expiry once the refund grace period has passed too
+pub fn past_expiry(listing: &Listing, env: &Env, refund_grace_seconds: u64) -> bool {
+    match (listing.expiration_height, listing.expiration_time) {
+        (Some(height), _) => env.block.height > height,
+        (None, Some(exp)) => env.block.time > exp.plus_seconds(refund_grace_seconds),
+        (None, None) => false,
+    }
+}
+
+// Empty whitelists & ones past their whitelist_until let anyone buy
+fn whitelist_allows(listing: &Listing, env: &Env, buyer: &Addr) -> bool {
+    listing.whitelisted_buyers.is_empty()
+        || listing.whitelist_until.is_some_and(|until| env.block.time >= until)
+        || listing.whitelisted_buyers.contains(buyer)
+}
+
+// A commitment holds while its listing can still be bought
+fn live_commitment(
+    storage: &dyn Storage,
+    env: &Env,
+    listing_id: &str,
+) -> StdResult<Option<(Addr, String)>> {
+    let Some(commitment) = LISTING_COMMITMENT.may_load(storage, listing_id)? else {
+        return Ok(None);
+    };
+    let refund_grace_seconds = CONFIG.load(storage)?.refund_grace_seconds;
+    let buyable =
+        listingz().idx.id.item(storage, listing_id.to_string())?.is_some_and(|(_pk, listing)| {
+            listing.claimant.is_none() && !past_expiry(&listing, env, refund_grace_seconds)
+        });
+    Ok(buyable.then_some(commitment))
+}
+
+// Committed buckets can't be changed or withdrawn until the trade settles or the commitment lapses
+fn ensure_bucket_not_committed(
+    storage: &dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    bucket_id: &str,
+) -> Result<(), ContractError> {
+    if let Some(listing_id) = BUCKET_COMMITMENT.may_load(storage, (owner, bucket_id))? {
+        let locked =
+            live_commitment(storage, env, &listing_id)?.is_some_and(|(buyer, committed_bucket)| {
+                &buyer == owner && committed_bucket == bucket_id
+            });
+        if locked {
+            return Err(ContractError::BucketCommitted {
+                listing_id,
+            });
+        }
+    }
+    if let Some(offer) = bucket_offers().may_load(storage, (owner, bucket_id))? {
+        if live_offer(storage, env, &offer)? {
+            return Err(ContractError::BucketOffered {
+                listing_id: offer.listing_id,
+            });
+        }
+    }
+    Ok(())
+}
+
+// An offer holds until it expires, or its listing is gone or bought
+pub fn live_offer(storage: &dyn Storage, env: &Env, offer: &Offer) -> StdResult<bool> {
+    if offer.expiration.is_some_and(|expiration| env.block.time >= expiration) {
+        return Ok(false);
+    }
+    let listing = listingz().idx.id.item(storage, offer.listing_id.clone())?;
+    Ok(listing.is_some_and(|(_pk, listing)| listing.claimant.is_none()))
+}
+
 pub fn execute_add_to_bucket(
     deps: DepsMut,
+    env: &Env,
     funds: Balance,
     sender: &Addr,
     bucket_id: String,
@@ -96,12 +275,13 @@ pub fn execute_add_to_bucket(
     }
 
     let the_bucket = get_bucket_if_sender_is_owner(&deps, sender, &bucket_id)?;
+    ensure_bucket_not_committed(deps.storage, env, sender, &bucket_id)?;
 
     // Add tokens
     let new_bucket = {
         let old_funds = the_bucket.funds.clone();
         let mut new_bucket = the_bucket;
-        new_bucket.funds.add_tokens(funds);
+        new_bucket.funds.add_tokens(funds)?;
         if old_funds == new_bucket.funds {
             Err(ContractError::ErrorAdding("Tokens to bucket".to_string()))
         } else {
@@ -119,11 +299,13 @@ pub fn execute_add_to_bucket(
 
 pub fn execute_add_to_bucket_cw721(
     deps: DepsMut,
+    env: &Env,
     user_wallet: &Addr,
     nft: Nft,
     bucket_id: String,
 ) -> Result<Response, ContractError> {
     let the_bucket = get_bucket_if_sender_is_owner(&deps, user_wallet, &bucket_id)?;
+    ensure_bucket_not_committed(deps.storage, env, user_wallet, &bucket_id)?;
 
     // Create updated bucket
     let new_bucket = {
@@ -152,16 +334,20 @@ pub fn execute_add_to_bucket_cw721(
 
 pub fn execute_withdraw_bucket(
     deps: DepsMut,
+    env: &Env,
     user_wallet: &Addr,
     bucket_id: &str,
 ) -> Result<Response, ContractError> {
     let the_bucket = get_bucket_if_sender_is_owner(&deps, user_wallet, bucket_id)?;
+    ensure_bucket_not_committed(deps.storage, env, user_wallet, bucket_id)?;
 
     // Create Send Msgs
-    let msgs = send_tokens_cosmos(user_wallet, &the_bucket.funds)?;
+    let msgs = payout_msgs(deps.storage, user_wallet, &the_bucket.funds)?;
 
     // Remove Bucket
     BUCKETS.remove(deps.storage, (user_wallet.clone(), bucket_id));
+    BUCKET_COMMITMENT.remove(deps.storage, (user_wallet, bucket_id));
+    bucket_offers().remove(deps.storage, (user_wallet, bucket_id))?;
 
     Ok(Response::new()
         .add_attribute("action", "empty_bucket")
@@ -169,150 +355,798 @@ pub fn execute_withdraw_bucket(
         .add_messages(msgs))
 }
 
+// The new owner can withdraw or buy with the bucket, its target is cleared for them to set
+pub fn execute_transfer_bucket(
+    deps: DepsMut,
+    env: &Env,
+    owner: &Addr,
+    bucket_id: &str,
+    new_owner: &str,
+) -> Result<Response, ContractError> {
+    let new_owner = deps.api.addr_validate(new_owner)?;
+
+    let the_bucket = get_bucket_if_sender_is_owner(&deps, owner, bucket_id)?;
+    ensure_bucket_not_committed(deps.storage, env, owner, bucket_id)?;
+
+    // Check that bucket_id isn't used by the new owner
+    if BUCKETS.has(deps.storage, (new_owner.clone(), bucket_id)) {
+        return Err(ContractError::IdAlreadyExists {});
+    }
+
+    BUCKETS.remove(deps.storage, (owner.clone(), bucket_id));
+    BUCKET_COMMITMENT.remove(deps.storage, (owner, bucket_id));
+    bucket_offers().remove(deps.storage, (owner, bucket_id))?;
+    BUCKETS.save(
+        deps.storage,
+        (new_owner.clone(), bucket_id),
+        &Bucket {
+            funds: the_bucket.funds,
+            owner: new_owner.clone(),
+            target_listing: None,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_bucket")
+        .add_attribute("bucket_id", bucket_id)
+        .add_attribute("new_owner", new_owner))
+}
+
+// Refunds up to `limit` buckets, next_start_after is set when more remain
+// Committed buckets are skipped
+pub fn execute_withdraw_all_buckets(
+    deps: DepsMut,
+    env: &Env,
+    user_wallet: &Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(MAX_BUCKETS_PER_WITHDRAW).min(MAX_BUCKETS_PER_WITHDRAW) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let mut buckets: Vec<(String, Bucket)> = BUCKETS
+        .prefix(user_wallet.clone())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let has_more = buckets.len() > limit;
+    buckets.truncate(limit);
+
+    let mut res = Response::new().add_attribute("action", "empty_all_buckets");
+    let mut withdrawn_count = 0;
+
+    for (bucket_id, the_bucket) in &buckets {
+        if ensure_bucket_not_committed(deps.storage, env, user_wallet, bucket_id).is_err() {
+            continue;
+        }
+        res = res.add_messages(payout_msgs(deps.storage, user_wallet, &the_bucket.funds)?);
+        BUCKETS.remove(deps.storage, (user_wallet.clone(), bucket_id));
+        BUCKET_COMMITMENT.remove(deps.storage, (user_wallet, bucket_id));
+        bucket_offers().remove(deps.storage, (user_wallet, bucket_id))?;
+        withdrawn_count += 1;
+    }
+    res = res.add_attribute("withdrawn_count", withdrawn_count.to_string());
+
+    if has_more {
+        if let Some((last_id, _)) = buckets.last() {
+            res = res.add_attribute("next_start_after", last_id);
+        }
+    }
+
+    Ok(res)
+}
+
+pub fn execute_set_bucket_target(
+    deps: DepsMut,
+    user_wallet: &Addr,
+    bucket_id: &str,
+    target_listing: Option<String>,
+) -> Result<Response, ContractError> {
+    let the_bucket = get_bucket_if_sender_is_owner(&deps, user_wallet, bucket_id)?;
+
+    // Target must exist when it's set, it can be removed later without affecting the bucket
+    if let Some(listing_id) = &target_listing {
+        if listingz().idx.id.item(deps.storage, listing_id.clone())?.is_none() {
+            return Err(ContractError::ListingNotFound {
+                id: listing_id.clone(),
+            });
+        }
+        record_activity(deps.storage, user_wallet, listing_id, ActivityRole::Bidder)?;
+    }
+
+    BUCKETS.save(
+        deps.storage,
+        (user_wallet.clone(), bucket_id),
+        &Bucket {
+            target_listing: target_listing.clone(),
+            ..the_bucket
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_bucket_target")
+        .add_attribute("bucket_id", bucket_id)
+        .add_attribute("target_listing", target_listing.unwrap_or_else(|| "None".to_string())))
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Listings
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+// Lowers the floor of each collection in the bundle for the day, a new day starts a new snapshot
+fn record_floor(
+    storage: &mut dyn Storage,
+    env: &Env,
+    nfts: &[Nft],
+    ask: &GenericBalance,
+) -> StdResult<()> {
+    let day = env.block.time.seconds() / 86_400;
+    let prices = ask
+        .native
+        .iter()
+        .map(|coin| (coin.denom.as_str(), coin.amount))
+        .chain(ask.cw20.iter().map(|coin| (coin.address.as_str(), coin.amount)));
+
+    let mut collections: Vec<&Addr> = nfts.iter().map(|nft| &nft.contract_address).collect();
+    collections.sort();
+    collections.dedup();
+
+    for collection in collections {
+        for (denom, price) in prices.clone() {
+            let mut history =
+                FLOOR_HISTORY.may_load(storage, (collection, denom))?.unwrap_or_default();
+            match history.last_mut() {
+                Some(snapshot) if snapshot.day == day => snapshot.price = snapshot.price.min(price),
+                _ => history.push(FloorSnapshot {
+                    day,
+                    price,
+                }),
+            }
+            if history.len() > MAX_FLOOR_HISTORY {
+                history.drain(..history.len() - MAX_FLOOR_HISTORY);
+            }
+            FLOOR_HISTORY.save(storage, (collection, denom), &history)?;
+        }
+    }
+    Ok(())
+}
+
+// Counts the address towards the unique users the first time it creates a listing or bucket
+fn mark_user_seen(storage: &mut dyn Storage, address: &Addr) -> StdResult<()> {
+    if SEEN_USERS.has(storage, address) {
+        return Ok(());
+    }
+    SEEN_USERS.save(storage, address, &true)?;
+    let unique_users = UNIQUE_USERS.may_load(storage)?.unwrap_or_default();
+    UNIQUE_USERS.save(storage, &(unique_users + 1))
+}
+
+// Adds the role to the address' activity in the listing, each role is only kept once
+fn record_activity(
+    storage: &mut dyn Storage,
+    address: &Addr,
+    listing_id: &str,
+    role: ActivityRole,
+) -> StdResult<()> {
+    ADDRESS_ACTIVITY.update(storage, (address, listing_id), |roles| -> StdResult<_> {
+        let mut roles = roles.unwrap_or_default();
+        if !roles.contains(&role) {
+            roles.push(role);
+        }
+        Ok(roles)
+    })?;
+    Ok(())
+}
+
+// Listings are moved to the archive instead of being deleted
+fn archive_listing(storage: &mut dyn Storage, listing: &Listing) -> StdResult<()> {
+    // Bought listings already released their collections when they closed
+    if listing.status != Status::Closed {
+        release_collections(storage, &listing.for_sale.nfts)?;
+    }
+    listingz().remove(storage, (&listing.creator, listing.id.clone()))?;
+    LISTING_CREATED.remove(storage, &listing.id);
+    ARCHIVED_LISTINGS.save(storage, &listing.id, listing)
+}
+
+// Takes a listing's bond out of storage, paid back to whoever posted it unless it's forfeited
+fn bond_msg(
+    storage: &mut dyn Storage,
+    listing_id: &str,
+    forfeit_to: Option<&Addr>,
+) -> StdResult<Option<BankMsg>> {
+    let Some((poster, bond)) = LISTING_BOND.may_load(storage, listing_id)? else {
+        return Ok(None);
+    };
+    LISTING_BOND.remove(storage, listing_id);
+    Ok(Some(BankMsg::Send {
+        to_address: forfeit_to.unwrap_or(&poster).to_string(),
+        amount: vec![bond],
+    }))
+}
+
+// Counts an NFT put up for sale towards its collection being active & indexes its listing
+fn track_collection(storage: &mut dyn Storage, nft: &Nft, listing_id: &str) -> StdResult<()> {
+    NFT_LISTING.save(storage, (&nft.contract_address, &nft.token_id), &listing_id.to_string())?;
+    COLLECTION_LISTINGS.update(
+        storage,
+        (&nft.contract_address, listing_id),
+        |count| -> StdResult<_> { Ok(count.unwrap_or_default() + 1) },
+    )?;
+    ACTIVE_COLLECTIONS.update(storage, &nft.contract_address, |count| -> StdResult<_> {
+        Ok(count.unwrap_or_default() + 1)
+    })?;
+    Ok(())
+}
+
+// Stops counting NFTs of a closed listing, a collection drops off with its last NFT
+fn release_collections(storage: &mut dyn Storage, nfts: &[Nft]) -> StdResult<()> {
+    for nft in nfts {
+        let key = (&nft.contract_address, nft.token_id.as_str());
+        if let Some(listing_id) = NFT_LISTING.may_load(storage, key)? {
+            let key = (&nft.contract_address, listing_id.as_str());
+            match COLLECTION_LISTINGS.may_load(storage, key)?.unwrap_or_default() {
+                0 | 1 => COLLECTION_LISTINGS.remove(storage, key),
+                count => COLLECTION_LISTINGS.save(storage, key, &(count - 1))?,
+            }
+        }
+        NFT_LISTING.remove(storage, key);
+        let count =
+            ACTIVE_COLLECTIONS.may_load(storage, &nft.contract_address)?.unwrap_or_default();
+        if count <= 1 {
+            ACTIVE_COLLECTIONS.remove(storage, &nft.contract_address);
+        } else {
+            ACTIVE_COLLECTIONS.save(storage, &nft.contract_address, &(count - 1))?;
+        }
+    }
+    Ok(())
+}
+
+// Listing IDs are assigned by the contract and never reused, even after a removal
+fn take_next_listing_id(storage: &mut dyn Storage) -> StdResult<String> {
+    let listing_id = NEXT_LISTING_ID.load(storage)?;
+    NEXT_LISTING_ID.save(storage, &(listing_id + 1))?;
+    Ok(listing_id.to_string())
+}
+
 fn validate_basic_new_listing(
     deps: &DepsMut,
-    listing_id: &str,
     bal: GenericBalance,
     whitelisted_buyer: Option<String>,
-) -> Result<(GenericBalance, Option<Addr>), ContractError> {
-    // Check ID isn't taken
-    if (listingz().idx.id.item(deps.storage, listing_id.to_string())?).is_some() {
-        return Err(ContractError::IdAlreadyExists {});
-    }
-
+    payout_address: Option<String>,
+) -> Result<(GenericBalance, Vec<Addr>, Option<Addr>), ContractError> {
     // normalize the tokens sent in
     let ask_tokens = normalize_ask_error_on_dup(bal)?;
+    validate_cw20_ask(deps, &ask_tokens)?;
+
+    let whitelist: Vec<Addr> =
+        whitelisted_buyer.map(|w| deps.api.addr_validate(&w)).transpose()?.into_iter().collect();
+    let payout: Option<Addr> = payout_address.map(|p| deps.api.addr_validate(&p)).transpose()?;
+    Ok((ask_tokens, whitelist, payout))
+}
+
+fn validate_ask_options(
+    deps: &DepsMut,
+    ask_options: Option<Vec<GenericBalance>>,
+) -> Result<Vec<GenericBalance>, ContractError> {
+    let ask_options = ask_options.unwrap_or_default();
+    if ask_options.len() > MAX_ASK_OPTIONS {
+        return Err(ContractError::TooManyAskOptions {
+            max: MAX_ASK_OPTIONS,
+        });
+    }
+    ask_options
+        .into_iter()
+        .map(|ask| {
+            let ask = normalize_ask_error_on_dup(ask)?;
+            validate_cw20_ask(deps, &ask)?;
+            Ok(ask)
+        })
+        .collect()
+}
+
+// Unit listings hand each buyer their share right away, so they can't vest
+fn validate_units(units: Option<u32>, vesting_seconds: Option<u64>) -> Result<(), ContractError> {
+    let vesting = vesting_seconds.unwrap_or_default() > 0;
+    match units {
+        Some(units) if units == 0 || vesting => Err(ContractError::InvalidUnits {}),
+        _ => Ok(()),
+    }
+}
+
+fn validate_sale_callback(
+    deps: &DepsMut,
+    callback: Option<SaleCallbackMsg>,
+) -> StdResult<Option<SaleCallback>> {
+    callback
+        .map(|callback| {
+            Ok(SaleCallback {
+                contract_addr: deps.api.addr_validate(&callback.contract_addr)?,
+                msg: callback.msg,
+            })
+        })
+        .transpose()
+}
+
+// A unit listing's for_sale must be fungible only & split evenly between its units
+fn ensure_units_split(listing: &Listing) -> Result<(), ContractError> {
+    let Some(units) = listing.units_available else {
+        return Ok(());
+    };
+    let units = Uint128::from(units);
+    let amounts = listing.for_sale.native.iter().map(|coin| coin.amount);
+    let mut amounts = amounts.chain(listing.for_sale.cw20.iter().map(|coin| coin.amount));
+    if !listing.for_sale.nfts.is_empty() || amounts.any(|amount| !(amount % units).is_zero()) {
+        return Err(ContractError::InvalidUnits {});
+    }
+    Ok(())
+}
+
+// First of the ask & its alternatives the bucket pays exactly, along with its maker & taker fees
+pub fn matching_ask(
+    listing: &Listing,
+    bucket_funds: &GenericBalance,
+    config: &Config,
+    now: Timestamp,
+) -> Result<Option<(GenericBalance, Uint128, Uint128)>, ContractError> {
+    for ask in std::iter::once(&listing.ask).chain(&listing.ask_options) {
+        let (maker_fee, taker_fee) = maker_taker_fees(ask, config, now)?;
+        if bucket_satisfies_ask(bucket_funds, &add_juno(ask, taker_fee)?) {
+            return Ok(Some((ask.clone(), maker_fee, taker_fee)));
+        }
+    }
+    Ok(None)
+}
 
-    let whitelist: Option<Addr> =
-        whitelisted_buyer.map(|w| deps.api.addr_validate(&w)).transpose()?;
-    Ok((ask_tokens, whitelist))
+// Optional since it costs a query per cw20, an ask for a non token could never be paid
+fn validate_cw20_ask(deps: &DepsMut, ask: &GenericBalance) -> Result<(), ContractError> {
+    if !CONFIG.load(deps.storage)?.validate_cw20_asks {
+        return Ok(());
+    }
+    for cw20_coin in &ask.cw20 {
+        let token_info: StdResult<TokenInfoResponse> =
+            deps.querier.query_wasm_smart(&cw20_coin.address, &Cw20QueryMsg::TokenInfo {});
+        if token_info.is_err() {
+            return Err(ContractError::InvalidCw20Ask {
+                address: cw20_coin.address.to_string(),
+            });
+        }
+    }
+    Ok(())
 }
 
 pub fn execute_create_listing(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: &Env,
     user_address: &Addr,
     funds_sent: &Balance,
     createlistingmsg: CreateListingMsg,
 ) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    ensure_creator_allowed(deps.storage, user_address)?;
+
     // Check that some tokens were sent with message
     if funds_sent.is_empty() {
         return Err(ContractError::NoTokens {});
     }
 
-    let (ask_tokens, whitelisted_buyer) = validate_basic_new_listing(
+    let (ask_tokens, whitelisted_buyers, payout_address) = validate_basic_new_listing(
         &deps,
-        &createlistingmsg.id,
         createlistingmsg.ask,
         createlistingmsg.whitelisted_buyer,
+        createlistingmsg.payout_address,
     )?;
+    let ask_options = validate_ask_options(&deps, createlistingmsg.ask_options)?;
+    validate_units(createlistingmsg.units, createlistingmsg.vesting_seconds)?;
+    let on_sale_callback =
+        validate_sale_callback(&deps, createlistingmsg.on_sale_callback.clone())?;
+
+    let min_buyer_purchases = createlistingmsg.min_buyer_purchases.unwrap_or_default();
+    mark_user_seen(deps.storage, user_address)?;
+
+    // Restricted, vesting & unit listings wait for a regular buy instead of auto matching
+    if whitelisted_buyers.is_empty()
+        && min_buyer_purchases == 0
+        && createlistingmsg.vesting_seconds.unwrap_or_default() == 0
+        && createlistingmsg.units.is_none()
+    {
+        if let Some(res) = try_auto_match(
+            deps.branch(),
+            env,
+            user_address,
+            &funds_sent.to_generic(),
+            &ask_tokens,
+            payout_address.as_ref(),
+        )? {
+            return Ok(res);
+        }
+    }
+
+    let listing_id = take_next_listing_id(deps.storage)?;
+    LISTING_CREATED.save(deps.storage, &listing_id, &env.block.time)?;
+    record_activity(deps.storage, user_address, &listing_id, ActivityRole::Creator)?;
 
     // Save listing
     listingz().save(
         deps.storage,
-        (user_address, createlistingmsg.id.clone()),
+        (user_address, listing_id.clone()),
         &Listing {
             creator: user_address.clone(),
-            id: createlistingmsg.id.clone(),
+            id: listing_id.clone(),
             finalized_time: None,
             expiration_time: None,
+            expiration_height: None,
             status: Status::BeingPrepared,
             for_sale: funds_sent.to_generic(),
             ask: ask_tokens,
+            ask_options,
             claimant: None,
-            whitelisted_buyer,
+            whitelisted_buyers,
+            whitelist_until: createlistingmsg.whitelist_until,
+            payout_address,
+            auto_swap_to: createlistingmsg.auto_swap_to.clone(),
+            units_available: createlistingmsg.units,
+            on_sale_callback,
+            min_net_proceeds: createlistingmsg.min_net_proceeds.clone().unwrap_or_default(),
+            max_offers: createlistingmsg.max_offers,
+            min_buyer_purchases,
+            vesting: createlistingmsg.vesting_seconds.filter(|s| *s > 0).map(Vesting::new),
+            on_expire: createlistingmsg.on_expire.clone().unwrap_or_default(),
+            private: createlistingmsg.private.unwrap_or_default(),
+            frozen: false,
         },
     )?;
 
     Ok(Response::new()
         .add_attribute("action", "create_native_listing")
-        .add_attribute("listing_id", &createlistingmsg.id))
+        .add_attribute("listing_id", &listing_id))
+}
+
+// Fills a new fungible only listing against an opposing finalized listing if their prices cross
+// The new listing's assets buy the matched listing like a bucket, through the same sale as a
+// BuyListing, & the creator gets the matched assets right away. The new listing is never saved
+fn try_auto_match(
+    mut deps: DepsMut,
+    env: &Env,
+    creator: &Addr,
+    for_sale: &GenericBalance,
+    ask: &GenericBalance,
+    payout_address: Option<&Addr>,
+) -> Result<Option<Response>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.auto_match || !for_sale.nfts.is_empty() || !ask.nfts.is_empty() {
+        return Ok(None);
+    }
+
+    let candidates: Vec<Listing> = listingz()
+        .idx
+        .status
+        .prefix(Status::FinalizedReady as u8)
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(MAX_AUTO_MATCH_SCAN)
+        .map(|entry| entry.map(|(_pk, listing)| listing))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Same checks a BuyListing of the opposing listing would go through
+    let storage = &*deps.storage;
+    let matched = candidates.into_iter().find(|listing| {
+        &listing.creator != creator
+            && listing.for_sale.nfts.is_empty()
+            && listing.ask.nfts.is_empty()
+            && listing.vesting.is_none()
+            && listing.units_available.is_none()
+            && ensure_can_buy(storage, env, &config, listing, creator).is_ok()
+            && live_commitment(storage, env, &listing.id).is_ok_and(|c| c.is_none())
+            && listing.for_sale.contains(ask)
+            && maker_taker_fees(&listing.ask, &config, env.block.time).is_ok_and(
+                |(_maker_fee, taker_fee)| {
+                    add_juno(&listing.ask, taker_fee)
+                        .is_ok_and(|ask_with_fee| for_sale.contains(&ask_with_fee))
+                },
+            )
+    });
+    let Some(matched) = matched else {
+        return Ok(None);
+    };
+    // The new listing's assets are the bucket that pays the matched listing's ask
+    // Its ID is only used for the payee's proceeds, one the creator already uses isn't matched
+    let bucket_id = format!("auto_match_{}", matched.id);
+    if BUCKETS.has(deps.storage, (creator.clone(), &bucket_id)) {
+        return Ok(None);
+    }
+    let bucket = Bucket {
+        funds: for_sale.clone(),
+        owner: creator.clone(),
+        target_listing: None,
+    };
+    let (maker_fee, taker_fee) = maker_taker_fees(&matched.ask, &config, env.block.time)?;
+    let paid = (matched.ask.clone(), maker_fee, taker_fee);
+    let sale = complete_sale(deps.branch(), env, matched.clone(), &bucket_id, bucket, paid, None);
+    // Too many assets to settle in one go, the new listing is saved instead
+    if matches!(sale, Err(ContractError::TooManyTransfers { .. })) {
+        return Ok(None);
+    }
+    let res = sale?;
+
+    // The creator withdraws the matched listing straight away, to their payout address if set
+    let (_claimant, bought) = load_purchased(deps.storage, creator, &matched.id)?;
+    archive_listing(deps.storage, &bought)?;
+    let recipient = payout_address.unwrap_or(creator);
+    let res = release_purchase(deps.storage, recipient, &bought.for_sale, res)?;
+
+    Ok(Some(
+        res.add_attribute("action", "auto_match").add_attribute("matched_listing_id", &matched.id),
+    ))
+}
+
+// New listing for new assets, with the ask, whitelist & other terms of one of the seller's listings
+// Sold or removed listings can be cloned while they're archived, only ever by their seller
+pub fn execute_clone_listing(
+    deps: DepsMut,
+    env: &Env,
+    user_address: &Addr,
+    source_listing_id: String,
+    new_assets: &Balance,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    ensure_creator_allowed(deps.storage, user_address)?;
+
+    // Check that some tokens were sent with message
+    if new_assets.is_empty() {
+        return Err(ContractError::NoTokens {});
+    }
+
+    let source = match listingz().idx.id.item(deps.storage, source_listing_id.clone())? {
+        Some((_pk, listing)) => listing,
+        None => ARCHIVED_LISTINGS.may_load(deps.storage, &source_listing_id)?.ok_or(
+            ContractError::ListingNotFound {
+                id: source_listing_id.clone(),
+            },
+        )?,
+    };
+    // A bought listing's creator is its buyer, who must not get the seller's payout & callback
+    let seller = LISTING_SELLER.may_load(deps.storage, &source_listing_id)?;
+    if user_address != seller.as_ref().unwrap_or(&source.creator) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let listing_id = take_next_listing_id(deps.storage)?;
+    LISTING_CREATED.save(deps.storage, &listing_id, &env.block.time)?;
+    record_activity(deps.storage, user_address, &listing_id, ActivityRole::Creator)?;
+
+    // Only the terms are copied, the new listing starts out being prepared
+    listingz().save(
+        deps.storage,
+        (user_address, listing_id.clone()),
+        &Listing {
+            creator: user_address.clone(),
+            id: listing_id.clone(),
+            finalized_time: None,
+            expiration_time: None,
+            expiration_height: None,
+            status: Status::BeingPrepared,
+            for_sale: new_assets.to_generic(),
+            ask: source.ask,
+            ask_options: source.ask_options,
+            claimant: None,
+            whitelisted_buyers: source.whitelisted_buyers,
+            whitelist_until: source.whitelist_until,
+            payout_address: source.payout_address,
+            auto_swap_to: source.auto_swap_to,
+            units_available: source.units_available,
+            on_sale_callback: source.on_sale_callback,
+            min_net_proceeds: source.min_net_proceeds,
+            max_offers: source.max_offers,
+            min_buyer_purchases: source.min_buyer_purchases,
+            vesting: source.vesting.map(|vesting| Vesting::new(vesting.duration_seconds)),
+            on_expire: source.on_expire,
+            private: source.private,
+            frozen: false,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "clone_listing")
+        .add_attribute("source_listing_id", source_listing_id)
+        .add_attribute("listing_id", &listing_id))
 }
 
 pub fn execute_create_listing_cw20(
     deps: DepsMut,
+    env: &Env,
     user_address: &Addr,
     _contract_address: &Addr,
     funds_sent: &Balance,
     createlistingmsg: CreateListingMsg,
 ) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    ensure_creator_allowed(deps.storage, user_address)?;
+
     // Check that some tokens were sent with message
     if funds_sent.is_empty() {
         return Err(ContractError::NoTokens {});
     }
 
-    let (ask_tokens, whitelisted_buyer) = validate_basic_new_listing(
+    let (ask_tokens, whitelisted_buyers, payout_address) = validate_basic_new_listing(
         &deps,
-        &createlistingmsg.id,
         createlistingmsg.ask,
         createlistingmsg.whitelisted_buyer,
+        createlistingmsg.payout_address,
     )?;
+    let ask_options = validate_ask_options(&deps, createlistingmsg.ask_options)?;
+    validate_units(createlistingmsg.units, createlistingmsg.vesting_seconds)?;
+    let on_sale_callback =
+        validate_sale_callback(&deps, createlistingmsg.on_sale_callback.clone())?;
+
+    let listing_id = take_next_listing_id(deps.storage)?;
+    LISTING_CREATED.save(deps.storage, &listing_id, &env.block.time)?;
+    mark_user_seen(deps.storage, user_address)?;
+    record_activity(deps.storage, user_address, &listing_id, ActivityRole::Creator)?;
 
     listingz().save(
         deps.storage,
-        (user_address, createlistingmsg.id.clone()),
+        (user_address, listing_id.clone()),
         &Listing {
             creator: user_address.clone(),
-            id: createlistingmsg.id.clone(),
+            id: listing_id.clone(),
             finalized_time: None,
             expiration_time: None,
+            expiration_height: None,
             status: Status::BeingPrepared,
             for_sale: funds_sent.to_generic(),
             ask: ask_tokens,
+            ask_options,
             claimant: None,
-            whitelisted_buyer,
+            whitelisted_buyers,
+            whitelist_until: createlistingmsg.whitelist_until,
+            payout_address,
+            auto_swap_to: createlistingmsg.auto_swap_to.clone(),
+            units_available: createlistingmsg.units,
+            on_sale_callback,
+            min_net_proceeds: createlistingmsg.min_net_proceeds.clone().unwrap_or_default(),
+            max_offers: createlistingmsg.max_offers,
+            min_buyer_purchases: createlistingmsg.min_buyer_purchases.unwrap_or_default(),
+            vesting: createlistingmsg.vesting_seconds.filter(|s| *s > 0).map(Vesting::new),
+            on_expire: createlistingmsg.on_expire.clone().unwrap_or_default(),
+            private: createlistingmsg.private.unwrap_or_default(),
+            frozen: false,
         },
     )?;
 
     Ok(Response::new()
         .add_attribute("action", "create_cw20_listing")
-        .add_attribute("listing_id", &createlistingmsg.id)
+        .add_attribute("listing_id", &listing_id)
         .add_attribute("creator", user_address.to_string()))
 }
 
+pub fn execute_create_listing_cw20_via_allowance(
+    deps: DepsMut,
+    env: &Env,
+    user_address: &Addr,
+    createlistingmsg: CreateListingMsg,
+    token: &str,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    ensure_creator_allowed(deps.storage, user_address)?;
+
+    // Check that some tokens will be pulled
+    if amount.is_zero() {
+        return Err(ContractError::NoTokens {});
+    }
+
+    let token = deps.api.addr_validate(token)?;
+
+    // Fail early instead of after the tokens are pulled
+    validate_basic_new_listing(
+        &deps,
+        createlistingmsg.ask.clone(),
+        createlistingmsg.whitelisted_buyer.clone(),
+        createlistingmsg.payout_address.clone(),
+    )?;
+    validate_ask_options(&deps, createlistingmsg.ask_options.clone())?;
+    validate_units(createlistingmsg.units, createlistingmsg.vesting_seconds)?;
+    validate_sale_callback(&deps, createlistingmsg.on_sale_callback.clone())?;
+
+    // Listing is saved in reply, only after the TransferFrom succeeds
+    PENDING_ALLOWANCE_LISTING.save(
+        deps.storage,
+        &PendingAllowanceListing {
+            creator: user_address.clone(),
+            token: token.clone(),
+            amount,
+            create_msg: createlistingmsg.clone(),
+        },
+    )?;
+
+    let transfer_from = WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+            owner: user_address.to_string(),
+            recipient: env.contract.address.to_string(),
+            amount,
+        })?,
+        funds: Vec::new(),
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "create_cw20_listing_via_allowance")
+        .add_submessage(SubMsg::reply_on_success(transfer_from, ALLOWANCE_LISTING_REPLY_ID)))
+}
+
 pub fn execute_create_listing_cw721(
     deps: DepsMut,
+    env: &Env,
     user_wallet: &Addr,
     nft: Nft,
     createlistingmsg: CreateListingMsg,
 ) -> Result<Response, ContractError> {
-    let (ask_tokens, whitelisted_buyer) = validate_basic_new_listing(
+    ensure_not_wound_down(deps.storage)?;
+    ensure_creator_allowed(deps.storage, user_wallet)?;
+
+    let (ask_tokens, whitelisted_buyers, payout_address) = validate_basic_new_listing(
         &deps,
-        &createlistingmsg.id,
         createlistingmsg.ask,
         createlistingmsg.whitelisted_buyer,
+        createlistingmsg.payout_address,
     )?;
+    let ask_options = validate_ask_options(&deps, createlistingmsg.ask_options)?;
+    validate_units(createlistingmsg.units, createlistingmsg.vesting_seconds)?;
+    let on_sale_callback =
+        validate_sale_callback(&deps, createlistingmsg.on_sale_callback.clone())?;
+    let for_sale = genbal_from_nft(nft.clone());
+    ensure_collection_limit(deps.storage, &for_sale, &nft.contract_address)?;
+
+    let listing_id = take_next_listing_id(deps.storage)?;
+    LISTING_CREATED.save(deps.storage, &listing_id, &env.block.time)?;
+    mark_user_seen(deps.storage, user_wallet)?;
+    record_activity(deps.storage, user_wallet, &listing_id, ActivityRole::Creator)?;
+    track_collection(deps.storage, &nft, &listing_id)?;
 
     listingz().save(
         deps.storage,
-        (user_wallet, createlistingmsg.id.clone()),
+        (user_wallet, listing_id.clone()),
         &Listing {
             creator: user_wallet.clone(),
-            id: createlistingmsg.id.clone(),
+            id: listing_id.clone(),
             finalized_time: None,
             expiration_time: None,
+            expiration_height: None,
             status: Status::BeingPrepared,
-            for_sale: genbal_from_nft(nft),
+            for_sale,
             ask: ask_tokens,
+            ask_options,
             claimant: None,
-            whitelisted_buyer,
+            whitelisted_buyers,
+            whitelist_until: createlistingmsg.whitelist_until,
+            payout_address,
+            auto_swap_to: createlistingmsg.auto_swap_to.clone(),
+            units_available: createlistingmsg.units,
+            on_sale_callback,
+            min_net_proceeds: createlistingmsg.min_net_proceeds.clone().unwrap_or_default(),
+            max_offers: createlistingmsg.max_offers,
+            min_buyer_purchases: createlistingmsg.min_buyer_purchases.unwrap_or_default(),
+            vesting: createlistingmsg.vesting_seconds.filter(|s| *s > 0).map(Vesting::new),
+            on_expire: createlistingmsg.on_expire.clone().unwrap_or_default(),
+            private: createlistingmsg.private.unwrap_or_default(),
+            frozen: false,
         },
     )?;
 
     Ok(Response::new()
         .add_attribute("action", "create_cw721_listing")
-        .add_attribute("listing_id", &createlistingmsg.id)
+        .add_attribute("listing_id", &listing_id)
         .add_attribute("creator", user_wallet.to_string()))
 }
 
 /// Validate basic listing info
-/// - Ensure listing exists, sender is owner, & get listing
+/// - Ensure listing exists & get listing
 /// - Ensure sender is owner
+/// - Ensure not frozen
 /// - Ensure no finalized time
 /// - Ensure being prepared
+///
 /// If all of these checks pass, return the listing.
 fn validate_basic_listings(
     deps: &DepsMut,
@@ -320,10 +1154,10 @@ fn validate_basic_listings(
     listing_id: &str,
     is_refund: bool, // only for execute_refund
 ) -> Result<Listing, ContractError> {
-    // Ensure listing exists, sender is owner, & get listing
-    let Some(listing) = listingz().may_load(deps.storage, (user_sender, listing_id.to_string()))? else {
-        return Err(ContractError::NotFound {
-            typ: "Listing".to_string(),
+    // Ensure listing exists & get listing
+    // Looked up by ID so a non-owner gets Unauthorized rather than ListingNotFound
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.to_string())? else {
+        return Err(ContractError::ListingNotFound {
             id: listing_id.to_string()
         });
     };
@@ -333,6 +1167,11 @@ fn validate_basic_listings(
         return Err(ContractError::Unauthorized {});
     }
 
+    // Ensure listing isn't frozen by the admin
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+
     // Ensure no finalized time (unless we want to refund, in which case ignore)
     if !is_refund && listing.finalized_time.is_some() {
         return Err(ContractError::AlreadyFinalized {});
@@ -353,6 +1192,7 @@ fn validate_basic_listings(
 
 pub fn execute_change_ask(
     deps: DepsMut,
+    env: &Env,
     user_sender: &Addr,
     listing_id: String,
     new_ask: GenericBalance,
@@ -360,53 +1200,112 @@ pub fn execute_change_ask(
     let listing = validate_basic_listings(&deps, user_sender, &listing_id, false)?;
 
     let new_ask_tokens = normalize_ask_error_on_dup(new_ask)?;
+    validate_cw20_ask(&deps, &new_ask_tokens)?;
 
     listingz().replace(
         deps.storage,
         (user_sender, listing_id.clone()),
         Some(&Listing {
-            ask: new_ask_tokens,
+            ask: new_ask_tokens.clone(),
             ..listing.clone()
         }),
         Some(&listing),
     )?;
 
-    Ok(Response::new()
+    let mut history = ASK_HISTORY.may_load(deps.storage, &listing_id)?.unwrap_or_default();
+    history.push(AskChange {
+        timestamp: env.block.time,
+        old_ask: listing.ask,
+        new_ask: new_ask_tokens,
+    });
+    if history.len() > MAX_ASK_HISTORY {
+        history.drain(..history.len() - MAX_ASK_HISTORY);
+    }
+    ASK_HISTORY.save(deps.storage, &listing_id, &history)?;
+
+    Ok(Response::new()
         .add_attribute("attribute", "change_listing_ask")
         .add_attribute("listing_id", &listing_id))
 }
 
-pub fn execute_modify_whitelisted_buyer(
+// Replaces the entire whitelist, an empty list opens the listing to anyone
+pub fn execute_set_whitelisted_buyers(
     deps: DepsMut,
     user_sender: &Addr,
     listing_id: String,
-    new_whitelisted_buyer: Option<String>,
+    addresses: Vec<String>,
 ) -> Result<Response, ContractError> {
     let listing = validate_basic_listings(&deps, user_sender, &listing_id, false)?;
 
-    let whitelisted_buyer: Option<Addr> = if let Some(new_whitelisted_buyer) = new_whitelisted_buyer
-    {
-        let addr = deps.api.addr_validate(&new_whitelisted_buyer)?;
-        Some(addr)
-    } else {
-        None
-    };
+    let mut whitelisted_buyers: Vec<Addr> = addresses
+        .iter()
+        .map(|addr| deps.api.addr_validate(addr))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    whitelisted_buyers.sort();
+    whitelisted_buyers.dedup();
+
+    if whitelisted_buyers.len() > MAX_WHITELISTED_BUYERS {
+        return Err(ContractError::WhitelistTooLarge {
+            max: MAX_WHITELISTED_BUYERS,
+        });
+    }
 
     listingz().replace(
         deps.storage,
         (user_sender, listing_id.clone()),
         Some(&Listing {
-            whitelisted_buyer,
+            whitelisted_buyers,
             ..listing.clone()
         }),
         Some(&listing),
     )?;
 
     Ok(Response::new()
-        .add_attribute("attribute", "execute_modify_whitelisted_buyer")
+        .add_attribute("attribute", "execute_set_whitelisted_buyers")
         .add_attribute("listing_id", &listing_id))
 }
 
+// Ownership moves without changing the terms, finalized listings included
+pub fn execute_transfer_listing(
+    deps: DepsMut,
+    current_owner: &Addr,
+    listing_id: String,
+    new_owner: &str,
+) -> Result<Response, ContractError> {
+    let new_owner = deps.api.addr_validate(new_owner)?;
+
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if current_owner != &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+    // Bought listings belong to the buyer until withdrawn
+    if listing.claimant.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Owner is part of the key, so the listing is saved again under the new owner
+    listingz().remove(deps.storage, (current_owner, listing_id.clone()))?;
+    listingz().save(
+        deps.storage,
+        (&new_owner, listing_id.clone()),
+        &Listing {
+            creator: new_owner.clone(),
+            ..listing
+        },
+    )?;
+    record_activity(deps.storage, &new_owner, &listing_id, ActivityRole::Creator)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_listing")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("new_owner", new_owner))
+}
+
 pub fn execute_add_funds_to_sale(
     deps: DepsMut,
     balance: Balance,
@@ -424,7 +1323,7 @@ pub fn execute_add_funds_to_sale(
     let new_listing = {
         let old_listing = listing.for_sale.clone();
         let mut x = listing.clone();
-        x.for_sale.add_tokens(balance);
+        x.for_sale.add_tokens(balance)?;
         if old_listing == x.for_sale {
             Err(ContractError::ErrorAdding("Tokens to Listing".to_string()))
         } else {
@@ -450,44 +1349,314 @@ pub fn execute_add_to_sale_cw721(
     nft: Nft,
     listing_id: String,
 ) -> Result<Response, ContractError> {
-    let old_listing = validate_basic_listings(&deps, user_wallet, &listing_id, false)?;
+    // Finalized contents are what the buyer is guaranteed, so no NFTs can be added after
+    let old_listing =
+        validate_basic_listings(&deps, user_wallet, &listing_id, false).map_err(|e| match e {
+            ContractError::AlreadyFinalized {} => ContractError::ListingFinalized {},
+            e => e,
+        })?;
 
     // Create updated listing
     let new_listing = {
         let old = old_listing.for_sale.clone();
         let mut x = old_listing.clone();
-        x.for_sale.add_nft(nft);
+        x.for_sale.add_nft(nft.clone());
         if old == x.for_sale {
             Err(ContractError::ToDo {})
         } else {
             Ok(x)
         }
     }?;
+    ensure_collection_limit(deps.storage, &new_listing.for_sale, &nft.contract_address)?;
 
     // Replace old listing with new listing
     listingz().replace(
         deps.storage,
-        (user_wallet, listing_id),
+        (user_wallet, listing_id.clone()),
         Some(&new_listing),
         Some(&old_listing),
     )?;
+    track_collection(deps.storage, &nft, &listing_id)?;
 
     Ok(Response::default())
 }
 
 pub fn execute_remove_listing(
     deps: DepsMut,
+    env: &Env,
     user_sender: &Addr,
     listing_id: String,
 ) -> Result<Response, ContractError> {
     let listing = validate_basic_listings(&deps, user_sender, &listing_id, false)?;
+    ensure_min_active(deps.storage, env, &listing_id)?;
+
+    let res = Response::new().add_attribute("action", "remove_listing");
+    Ok(archive_and_pay_back(deps.storage, &listing, None, res)?)
+}
+
+// A listing can hold at most the config's max_nfts_per_collection_per_listing from a collection
+fn ensure_collection_limit(
+    storage: &dyn Storage,
+    for_sale: &GenericBalance,
+    collection: &Addr,
+) -> Result<(), ContractError> {
+    let max = CONFIG.load(storage)?.max_nfts_per_collection_per_listing;
+    let count = for_sale.nfts.iter().filter(|nft| &nft.contract_address == collection).count();
+    if count > max as usize {
+        return Err(ContractError::TooManyNftsFromCollection {
+            collection: collection.to_string(),
+            max,
+        });
+    }
+    Ok(())
+}
+
+// Owners can't pull a listing until it's existed for the config's min_active_seconds
+fn ensure_min_active(
+    storage: &dyn Storage,
+    env: &Env,
+    listing_id: &str,
+) -> Result<(), ContractError> {
+    let min_active_seconds = CONFIG.load(storage)?.min_active_seconds;
+    let Some(created) = LISTING_CREATED.may_load(storage, listing_id)? else {
+        return Ok(());
+    };
+    let removable_at = created.plus_seconds(min_active_seconds);
+    if env.block.time < removable_at {
+        return Err(ContractError::ListingTooNew {
+            seconds: removable_at.seconds() - env.block.time.seconds(),
+        });
+    }
+    Ok(())
+}
+
+// Delete listing & send funds back to its creator, NFTs that fail to send can be claimed later
+// The bond goes back to its poster, unless it's forfeited
+fn archive_and_pay_back(
+    storage: &mut dyn Storage,
+    listing: &Listing,
+    forfeit_to: Option<&Addr>,
+    res: Response,
+) -> StdResult<Response> {
+    let fungibles = GenericBalance {
+        nfts: Vec::new(),
+        ..listing.for_sale.clone()
+    };
+    let msgs = payout_msgs(storage, &listing.creator, &fungibles)?;
+    let nft_submsgs = nft_payout_submsgs(storage, &listing.creator, &listing.for_sale.nfts)?;
+    let bond_msg = bond_msg(storage, &listing.id, forfeit_to)?;
+
+    archive_listing(storage, listing)?;
+
+    Ok(res.add_messages(msgs).add_submessages(nft_submsgs).add_messages(bond_msg))
+}
+
+// Removes the owner's listings that RemoveListing would accept, paginated for gas safety
+// Finalized, frozen, claimed & too new listings are skipped
+pub fn execute_remove_all_listings(
+    deps: DepsMut,
+    env: &Env,
+    owner: &Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(MAX_LISTINGS_PER_REMOVE).min(MAX_LISTINGS_PER_REMOVE) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let mut listings: Vec<(String, Listing)> = listingz()
+        .prefix(owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let has_more = listings.len() > limit;
+    listings.truncate(limit);
+
+    let mut res = Response::new().add_attribute("action", "remove_all_listings");
+    let mut removed_count = 0;
+
+    for (_listing_id, listing) in &listings {
+        if listing.frozen
+            || listing.finalized_time.is_some()
+            || listing.status != Status::BeingPrepared
+            || listing.claimant.is_some()
+            || ensure_min_active(deps.storage, env, &listing.id).is_err()
+        {
+            continue;
+        }
+        res = archive_and_pay_back(deps.storage, listing, None, res)?;
+        removed_count += 1;
+    }
+    res = res.add_attribute("removed_count", removed_count.to_string());
+
+    if has_more {
+        if let Some((last_id, _)) = listings.last() {
+            res = res.add_attribute("next_start_after", last_id);
+        }
+    }
+
+    Ok(res)
+}
+
+pub fn execute_set_conversion_rate(
+    deps: DepsMut,
+    sender: &Addr,
+    from: &str,
+    to: &str,
+    rate: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
 
-    // Delete listing & send funds back to user
-    let msgs = send_tokens_cosmos(&listing.creator, &listing.for_sale)?;
+    match rate {
+        Some(rate) => CONVERSION_RATES.save(deps.storage, (from, to), &rate)?,
+        None => CONVERSION_RATES.remove(deps.storage, (from, to)),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_conversion_rate")
+        .add_attribute("from", from)
+        .add_attribute("to", to))
+}
+
+// Called once per NFT payout, in the order they were sent
+pub fn execute_nft_payout_reply(deps: DepsMut, failed: bool) -> Result<Response, ContractError> {
+    let mut pending = PENDING_NFT_PAYOUTS.load(deps.storage)?;
+    let (owner, nft) = pending.remove(0);
+    if pending.is_empty() {
+        PENDING_NFT_PAYOUTS.remove(deps.storage);
+    } else {
+        PENDING_NFT_PAYOUTS.save(deps.storage, &pending)?;
+    }
+
+    if !failed {
+        return Ok(Response::new());
+    }
+    NFT_CLAIMS.save(deps.storage, (&nft.contract_address, &nft.token_id), &owner)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "nft_claim_created")
+        .add_attribute("contract", nft.contract_address)
+        .add_attribute("token_id", nft.token_id))
+}
+
+// Retries the payout of an NFT whose transfer failed
+pub fn execute_claim_nft(
+    deps: DepsMut,
+    sender: &Addr,
+    contract: &str,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    let contract = deps.api.addr_validate(contract)?;
+    let Some(owner) = NFT_CLAIMS.may_load(deps.storage, (&contract, token_id))? else {
+        return Err(ContractError::NotFound {
+            typ: "Claim".to_string(),
+            id: format!("{contract} {token_id}"),
+        });
+    };
+    if sender != &owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    NFT_CLAIMS.remove(deps.storage, (&contract, token_id));
+    let msgs = payout_msgs(
+        deps.storage,
+        &owner,
+        &genbal_from_nft(Nft {
+            contract_address: contract,
+            token_id: token_id.to_string(),
+        }),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_nft")
+        .add_attribute("token_id", token_id)
+        .add_messages(msgs))
+}
+
+pub fn execute_force_remove_listing(
+    deps: DepsMut,
+    sender: &Addr,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
+
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    // Sold listings belong to the buyer
+    if listing.claimant.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let fee_collector = CONFIG.load(deps.storage)?.fee_collector;
+    // Bids on an auction are refunded in full
+    let mut refund_msgs: Vec<BankMsg> = match SEALED_AUCTION.may_load(deps.storage, &listing_id)? {
+        Some(auction) => take_sealed_bids(deps.storage, &listing_id)?
+            .iter()
+            .map(|(bidder, bid)| escrow_msg(&auction.reserve, bidder, bid.escrow))
+            .collect(),
+        None => Vec::new(),
+    };
+    if let Some(auction) = AUCTIONS.may_load(deps.storage, &listing_id)? {
+        AUCTIONS.remove(deps.storage, &listing_id);
+        refund_msgs.extend(
+            auction.high_bid.map(|bid| escrow_msg(&auction.reserve, &bid.bidder, bid.escrow)),
+        );
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "force_remove_listing")
+        .add_attribute("listing_id", listing_id);
+    let res = archive_and_pay_back(deps.storage, &listing, Some(&fee_collector), res)?;
+
+    Ok(res.add_messages(refund_msgs))
+}
+
+pub fn execute_post_bond(
+    deps: DepsMut,
+    info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    validate_basic_listings(&deps, &info.sender, &listing_id, false)?;
+
+    let Some(bond) = CONFIG.load(deps.storage)?.listing_bond else {
+        return Err(ContractError::BondNotRequired {});
+    };
+    if LISTING_BOND.has(deps.storage, &listing_id) {
+        return Err(ContractError::BondNotRequired {});
+    }
+    if info.funds != vec![bond.clone()] {
+        return Err(ContractError::InvalidBond {
+            bond: bond.to_string(),
+        });
+    }
+
+    LISTING_BOND.save(deps.storage, &listing_id, &(info.sender.clone(), bond.clone()))?;
 
-    listingz().remove(deps.storage, (user_sender, listing_id))?;
+    Ok(Response::new()
+        .add_attribute("action", "post_bond")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("bond", bond.to_string()))
+}
+
+// A default has to be a duration Finalize would accept
+pub fn validate_default_listing_ttl(default_listing_ttl: u64) -> Result<(), ContractError> {
+    if default_listing_ttl != 0
+        && !(MIN_FINALIZE_SECONDS..=MAX_FINALIZE_SECONDS).contains(&default_listing_ttl)
+    {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    Ok(())
+}
 
-    Ok(Response::new().add_attribute("action", "remove_listing").add_messages(msgs))
+// A limit of 0 would reject every buy or return only empty pages
+pub fn validate_nonzero_limit(field: &str, limit: u32) -> Result<(), ContractError> {
+    if limit == 0 {
+        return Err(ContractError::InvalidConfig {
+            field: field.to_string(),
+        });
+    }
+    Ok(())
 }
 
 pub fn execute_finalize(
@@ -495,35 +1664,72 @@ pub fn execute_finalize(
     env: &Env,
     user_sender: &Addr,
     listing_id: String,
-    seconds: u64,
+    seconds: Option<u64>,
+    expiration_height: Option<u64>,
 ) -> Result<Response, ContractError> {
     let listing = validate_basic_listings(&deps, user_sender, &listing_id, false)?;
 
-    // max expiration is 1209600 seconds <14 days>
-    // min expiration is 600 seconds <10 minutes>
-    if !(600..=1_209_600).contains(&seconds) {
+    let config = CONFIG.load(deps.storage)?;
+    // Without seconds or a default, the 0 second expiry is rejected below
+    let seconds = seconds.unwrap_or(config.default_listing_ttl);
+    match expiration_height {
+        Some(height) if height <= env.block.height => {
+            return Err(ContractError::InvalidExpiration {});
+        }
+        Some(_) => {}
+        None if !(MIN_FINALIZE_SECONDS..=MAX_FINALIZE_SECONDS).contains(&seconds) => {
+            return Err(ContractError::InvalidExpiration {});
+        }
+        None => {}
+    }
+    // Relisting repeats the time the listing was up for, which a height expiry doesn't set
+    if expiration_height.is_some() && listing.on_expire == OnExpire::Relist {
         return Err(ContractError::InvalidExpiration {});
     }
+    ensure_units_split(&listing)?;
+    ensure_bond_posted(deps.storage, &config, &listing_id)?;
 
     let finalized_at = env.block.time;
-    let expiration = env.block.time.plus_seconds(seconds);
+    let expiration = expiration_height.is_none().then(|| env.block.time.plus_seconds(seconds));
 
     listingz().replace(
         deps.storage,
         (user_sender, listing_id.clone()),
         Some(&Listing {
             finalized_time: Some(finalized_at),
-            expiration_time: Some(expiration),
+            expiration_time: expiration,
+            expiration_height,
             status: Status::FinalizedReady,
             ..listing.clone()
         }),
         Some(&listing),
     )?;
+    record_floor(deps.storage, env, &listing.for_sale.nfts, &listing.ask)?;
 
-    Ok(Response::new()
+    let res = Response::new()
         .add_attribute("action", "finalize")
-        .add_attribute("listing_id", &listing_id)
-        .add_attribute("expiration_seconds", expiration.to_string()))
+        .add_attribute("listing_id", &listing_id);
+    Ok(match (expiration, expiration_height) {
+        (Some(expiration), _) => res.add_attribute("expiration_seconds", expiration.to_string()),
+        (None, Some(height)) => res.add_attribute("expiration_height", height.to_string()),
+        (None, None) => res,
+    })
+}
+
+// Listings can't be put up for sale until they've posted the config's bond, when one is set
+fn ensure_bond_posted(
+    storage: &dyn Storage,
+    config: &Config,
+    listing_id: &str,
+) -> Result<(), ContractError> {
+    if let Some(bond) = &config.listing_bond {
+        if !LISTING_BOND.has(storage, listing_id) {
+            return Err(ContractError::BondRequired {
+                bond: bond.to_string(),
+            });
+        }
+    }
+    Ok(())
 }
 
 pub fn execute_refund(
@@ -534,153 +1740,2251 @@ pub fn execute_refund(
 ) -> Result<Response, ContractError> {
     let listing = validate_basic_listings(&deps, user_sender, &listing_id, true)?;
 
-    // Check if listing is expired
-    match listing.expiration_time {
-        None => {
+    let refund_grace_seconds = CONFIG.load(deps.storage)?.refund_grace_seconds;
+
+    // Check if listing is expired, plus the grace period for a time expiry
+    match (listing.expiration_height, listing.expiration_time) {
+        (None, None) => {
             return Err(ContractError::Unauthorized {});
         }
-        Some(timestamp) => {
-            if env.block.time < timestamp {
+        (Some(height), _) => {
+            if env.block.height <= height {
+                return Err(ContractError::NotExpired {
+                    x: format!("block {height}"),
+                });
+            }
+        }
+        (None, Some(timestamp)) => {
+            let refundable_at = timestamp.plus_seconds(refund_grace_seconds);
+            if env.block.time < refundable_at {
                 return Err(ContractError::NotExpired {
-                    x: timestamp.seconds().to_string(),
+                    x: refundable_at.seconds().to_string(),
                 });
             }
         }
     };
 
-    // Checks pass, send refund & delete listing
-    let refundee = listing.creator;
-    let funds = listing.for_sale;
-    let send_msgs = send_tokens_cosmos(&refundee, &funds)?;
-
-    // Delete Listing
-    listingz().remove(deps.storage, (user_sender, listing_id))?;
-
-    Ok(Response::new().add_attribute("action", "refund").add_messages(send_msgs))
+    // Checks pass, send refund & archive listing
+    let res = Response::new().add_attribute("action", "refund");
+    Ok(archive_and_pay_back(deps.storage, &listing, None, res)?)
 }
 
-//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// Purchasing
-//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-pub fn execute_buy_listing(
+// Keeper entry point, refunds or relists an expired listing as its owner chose
+// A wound down contract always refunds
+pub fn execute_process_expiry(
     deps: DepsMut,
     env: &Env,
-    buyer: &Addr,
     listing_id: String,
-    bucket_id: &str,
 ) -> Result<Response, ContractError> {
-    // Get bucket (will error if no bucket found)
-    let the_bucket = match BUCKETS.load(deps.storage, (buyer.clone(), bucket_id)) {
-        Ok(buck) => buck,
-        Err(_) => return Err(ContractError::LoadBucketError {}),
-    };
-
-    // Check listing exists & get the_listing
-    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
-        return Err(ContractError::NotFound { typ: "Listing".to_string(), id: listing_id });
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
     };
 
-    // Check that sender is bucket owner (redundant check)
-    if buyer != &the_bucket.owner {
-        return Err(ContractError::Unauthorized {});
-    }
-    // Check that bucket contains required purchase price
-    if the_bucket.funds != the_listing.ask {
-        return Err(ContractError::FundsSentNotFundsAsked {
-            which: format!("Bucket ID: {bucket_id}"),
-        });
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
     }
-    // Check that listing is ready for purchase
-    if the_listing.status != Status::FinalizedReady {
+    if listing.status != Status::FinalizedReady || listing.claimant.is_some() {
         return Err(ContractError::NotPurchasable {});
     }
-    // Check that the user buying is whitelisted
-    if let Some(whitelist) = the_listing.whitelisted_buyer.clone() {
-        if whitelist != *buyer {
-            return Err(ContractError::NotWhitelisted {});
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // Same expiry as buying, listings stay purchasable during the grace period
+    let expires_at = match (listing.expiration_height, listing.expiration_time) {
+        (Some(height), _) => format!("block {height}"),
+        (None, Some(expiration)) => {
+            expiration.plus_seconds(config.refund_grace_seconds).seconds().to_string()
         }
+        (None, None) => return Err(ContractError::Unauthorized {}),
+    };
+    if !past_expiry(&listing, env, config.refund_grace_seconds) {
+        return Err(ContractError::NotExpired {
+            x: expires_at,
+        });
     }
 
-    // Check that there's no existing claimant on listing
-    if the_listing.claimant.is_some() {
-        return Err(ContractError::NotPurchasable {});
-    }
-    // Check that listing isn't expired
-    if let Some(exp) = the_listing.expiration_time {
-        if env.block.time > exp {
-            return Err(ContractError::Expired {});
+    let action = if config.wind_down {
+        OnExpire::Refund
+    } else {
+        listing.on_expire.clone()
+    };
+
+    match action {
+        OnExpire::Refund => {
+            let res = Response::new()
+                .add_attribute("action", "process_expiry_refund")
+                .add_attribute("listing_id", listing_id);
+            Ok(archive_and_pay_back(deps.storage, &listing, None, res)?)
+        }
+        OnExpire::Relist => {
+            // Finalize doesn't let a height expiry relist, it has no duration in seconds to repeat
+            let (Some(finalized_at), Some(expiration)) = (listing.finalized_time, listing.expiration_time) else {
+                return Err(ContractError::InvalidExpiration {});
+            };
+            let duration = expiration.seconds() - finalized_at.seconds();
+            let new_expiration = env.block.time.plus_seconds(duration);
+
+            listingz().replace(
+                deps.storage,
+                (&listing.creator, listing_id.clone()),
+                Some(&Listing {
+                    finalized_time: Some(env.block.time),
+                    expiration_time: Some(new_expiration),
+                    ..listing.clone()
+                }),
+                Some(&listing),
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("action", "process_expiry_relist")
+                .add_attribute("listing_id", listing_id)
+                .add_attribute("expiration_seconds", new_expiration.to_string()))
         }
     }
+}
 
-    // Delete Old Listing -> Save new listing with listing_buyer in key & creator
-    listingz().remove(deps.storage, (&the_listing.creator, listing_id.clone()))?;
-    listingz().save(
-        deps.storage,
-        (buyer, listing_id.clone()),
-        &Listing {
-            creator: buyer.clone(),
-            claimant: Some(buyer.clone()),
-            status: Status::Closed,
-            ..the_listing
-        },
-    )?;
+pub fn execute_express_interest(
+    deps: DepsMut,
+    user_sender: &Addr,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    // Check listing exists
+    if listingz().idx.id.item(deps.storage, listing_id.clone())?.is_none() {
+        return Err(ContractError::ListingNotFound {
+            id: listing_id,
+        });
+    }
 
-    // Delete Old Bucket -> Save new Bucket with listing_seller in key & owner
-    BUCKETS.remove(deps.storage, (buyer.clone(), bucket_id));
-    BUCKETS.save(
-        deps.storage,
-        (the_listing.creator.clone(), bucket_id),
-        &Bucket {
-            owner: the_listing.creator,
-            ..the_bucket
-        },
-    )?;
+    // Each address only counts once per listing
+    if LISTING_INTEREST.has(deps.storage, (&listing_id, user_sender)) {
+        return Ok(Response::new()
+            .add_attribute("action", "express_interest")
+            .add_attribute("listing_id", &listing_id)
+            .add_attribute("counted", "false"));
+    }
+
+    LISTING_INTEREST.save(deps.storage, (&listing_id, user_sender), &true)?;
+    record_activity(deps.storage, user_sender, &listing_id, ActivityRole::Interested)?;
+    INTEREST_COUNT.update(deps.storage, &listing_id, |count| -> Result<_, ContractError> {
+        Ok(count.unwrap_or_default() + 1)
+    })?;
 
     Ok(Response::new()
-        .add_attribute("action", "buy_listing")
-        .add_attribute("bucket_used", bucket_id)
-        .add_attribute("listing_purchased:", &listing_id))
+        .add_attribute("action", "express_interest")
+        .add_attribute("listing_id", &listing_id)
+        .add_attribute("counted", "true"))
 }
 
-// TODO: merge this in with buy_listing function above
-pub fn execute_withdraw_purchased(
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Admin
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+fn ensure_admin(deps: &DepsMut, sender: &Addr) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if sender != &config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+// Once wound down, only withdrawals & refunds are allowed
+fn ensure_not_wound_down(storage: &dyn Storage) -> Result<(), ContractError> {
+    if CONFIG.load(storage)?.wind_down {
+        return Err(ContractError::WoundDown {});
+    }
+    Ok(())
+}
+
+// Curated markets only take listings from allowlisted creators
+fn ensure_creator_allowed(storage: &dyn Storage, creator: &Addr) -> Result<(), ContractError> {
+    if CONFIG.load(storage)?.restrict_creators && !CREATOR_ALLOWLIST.has(storage, creator) {
+        return Err(ContractError::NotAllowedToCreate {});
+    }
+    Ok(())
+}
+
+pub fn execute_freeze_listing(
     deps: DepsMut,
-    withdrawer: &Addr,
+    sender: &Addr,
     listing_id: String,
+    frozen: bool,
 ) -> Result<Response, ContractError> {
-    // Get listing
-    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
-        return Err(ContractError::NotFound { typ: "Listing".to_string(), id: listing_id });
+    ensure_admin(&deps, sender)?;
+
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
     };
 
-    // Check and pull out claimant
-    let listing_claimer = the_listing.claimant.ok_or(ContractError::Unauthorized {})?;
+    listingz().replace(
+        deps.storage,
+        (&listing.creator, listing_id.clone()),
+        Some(&Listing {
+            frozen,
+            ..listing.clone()
+        }),
+        Some(&listing),
+    )?;
 
-    // Check that withdrawer is the claimant
-    if withdrawer != &listing_claimer {
-        return Err(ContractError::Unauthorized {});
-    };
+    Ok(Response::new()
+        .add_attribute("action", "freeze_listing")
+        .add_attribute("listing_id", &listing_id)
+        .add_attribute("frozen", frozen.to_string()))
+}
 
-    // Check that status is Closed
-    if the_listing.status != Status::Closed {
-        return Err(ContractError::Unauthorized {});
-    };
+pub fn execute_update_config(
+    deps: DepsMut,
+    sender: &Addr,
+    config_msg: UpdateConfigMsg,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
 
-    // Delete Listing
-    listingz().remove(deps.storage, (&listing_claimer, listing_id.clone()))?;
+    if CONFIG.load(deps.storage)?.admin_timelock_seconds > 0 {
+        return Err(ContractError::TimelockActive {});
+    }
+    apply_config_update(deps, config_msg)?;
 
-    // default listing response
-    let res: Response = Response::new()
-        .add_attribute("action", "withdraw_purchased")
-        .add_attribute("listing_id", listing_id);
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn apply_config_update(deps: DepsMut, config_msg: UpdateConfigMsg) -> Result<(), ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(refund_grace_seconds) = config_msg.refund_grace_seconds {
+        config.refund_grace_seconds = refund_grace_seconds;
+    }
+    if let Some(auto_match) = config_msg.auto_match {
+        config.auto_match = auto_match;
+    }
+    if let Some(rounding) = config_msg.rounding {
+        config.rounding = rounding;
+    }
+    if let Some(maker_fee_bps) = config_msg.maker_fee_bps {
+        config.maker_fee_bps = maker_fee_bps;
+    }
+    if let Some(taker_fee_bps) = config_msg.taker_fee_bps {
+        config.taker_fee_bps = taker_fee_bps;
+    }
+    if config.maker_fee_bps > MAX_FEE_BPS || config.taker_fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::FeeTooHigh {
+            max: MAX_FEE_BPS,
+        });
+    }
+    if let Some(fee_collector) = config_msg.fee_collector {
+        config.fee_collector = deps.api.addr_validate(&fee_collector)?;
+    }
+    if let Some(max_transfers_per_buy) = config_msg.max_transfers_per_buy {
+        validate_nonzero_limit("max_transfers_per_buy", max_transfers_per_buy)?;
+        config.max_transfers_per_buy = max_transfers_per_buy;
+    }
+    if let Some(validate_cw20_asks) = config_msg.validate_cw20_asks {
+        config.validate_cw20_asks = validate_cw20_asks;
+    }
+    if let Some(admin_timelock_seconds) = config_msg.admin_timelock_seconds {
+        config.admin_timelock_seconds = admin_timelock_seconds;
+    }
+    if let Some(listing_bond) = config_msg.listing_bond {
+        config.listing_bond = (!listing_bond.amount.is_zero()).then_some(listing_bond);
+    }
+    if let Some(min_market_ask_value) = config_msg.min_market_ask_value {
+        config.min_market_ask_value = min_market_ask_value;
+    }
+    if let Some(default_listing_ttl) = config_msg.default_listing_ttl {
+        validate_default_listing_ttl(default_listing_ttl)?;
+        config.default_listing_ttl = default_listing_ttl;
+    }
+    if let Some(referral_bps) = config_msg.referral_bps {
+        if referral_bps > MAX_REFERRAL_BPS {
+            return Err(ContractError::ReferralTooHigh {
+                max: MAX_REFERRAL_BPS,
+            });
+        }
+        config.referral_bps = referral_bps;
+    }
+    if let Some(max_page_size) = config_msg.max_page_size {
+        validate_nonzero_limit("max_page_size", max_page_size)?;
+        config.max_page_size = max_page_size;
+    }
+    if let Some(min_active_seconds) = config_msg.min_active_seconds {
+        config.min_active_seconds = min_active_seconds;
+    }
+    if let Some(swap_router) = config_msg.swap_router {
+        config.swap_router = if swap_router.is_empty() {
+            None
+        } else {
+            Some(deps.api.addr_validate(&swap_router)?)
+        };
+    }
+    if let Some(max_nfts) = config_msg.max_nfts_per_collection_per_listing {
+        config.max_nfts_per_collection_per_listing = max_nfts;
+    }
+    if let Some(restrict_creators) = config_msg.restrict_creators {
+        config.restrict_creators = restrict_creators;
+    }
+    if let Some(fee_holiday_until) = config_msg.fee_holiday_until {
+        config.fee_holiday_until = Some(fee_holiday_until);
+    }
+    if let Some(reveal_deposit_slash_bps) = config_msg.reveal_deposit_slash_bps {
+        if reveal_deposit_slash_bps > MAX_REVEAL_SLASH_BPS {
+            return Err(ContractError::SlashTooHigh {
+                max: MAX_REVEAL_SLASH_BPS,
+            });
+        }
+        config.reveal_deposit_slash_bps = reveal_deposit_slash_bps;
+    }
+    if let Some(buy_cooldown_seconds) = config_msg.buy_cooldown_seconds {
+        config.buy_cooldown_seconds = buy_cooldown_seconds;
+    }
+    if let Some(min_offer_value) = config_msg.min_offer_value {
+        config.min_offer_value = min_offer_value;
+    }
+    if let Some(max_offers) = config_msg.max_offers {
+        config.max_offers = max_offers;
+    }
+    if let Some(dust_threshold) = config_msg.dust_threshold {
+        config.dust_threshold = dust_threshold;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(())
+}
+
+pub fn execute_queue_admin_action(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    action: AdminAction,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
+
+    let eta = env.block.time.plus_seconds(CONFIG.load(deps.storage)?.admin_timelock_seconds);
+    let action_id = NEXT_ACTION_ID.may_load(deps.storage)?.unwrap_or(1);
+    NEXT_ACTION_ID.save(deps.storage, &(action_id + 1))?;
+    QUEUED_ACTIONS.save(
+        deps.storage,
+        action_id,
+        &QueuedAction {
+            action,
+            eta,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "queue_admin_action")
+        .add_attribute("action_id", action_id.to_string())
+        .add_attribute("eta", eta.seconds().to_string()))
+}
+
+pub fn execute_queued_action(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    action_id: u64,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
+
+    let Some(queued) = QUEUED_ACTIONS.may_load(deps.storage, action_id)? else {
+        return Err(ContractError::NotFound { typ: "Queued Action".to_string(), id: action_id.to_string() });
+    };
+    if env.block.time < queued.eta {
+        return Err(ContractError::ActionNotReady {
+            eta: queued.eta.seconds(),
+        });
+    }
+    QUEUED_ACTIONS.remove(deps.storage, action_id);
+
+    match queued.action {
+        AdminAction::UpdateConfig {
+            config_msg,
+        } => apply_config_update(deps, config_msg)?,
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_queued_action")
+        .add_attribute("action_id", action_id.to_string()))
+}
+
+// One way, there is no message to undo it
+pub fn execute_wind_down(deps: DepsMut, sender: &Addr) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.wind_down = true;
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "wind_down"))
+}
+
+pub fn execute_prune_archive(
+    deps: DepsMut,
+    sender: &Addr,
+    listing_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
+
+    for listing_id in &listing_ids {
+        ARCHIVED_LISTINGS.remove(deps.storage, listing_id);
+        LISTING_SELLER.remove(deps.storage, listing_id);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "prune_archive")
+        .add_attribute("pruned_count", listing_ids.len().to_string()))
+}
+
+pub fn execute_set_nft_receiver(
+    deps: DepsMut,
+    sender: &Addr,
+    address: &str,
+    known: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
+
+    let address = deps.api.addr_validate(address)?;
+
+    if known {
+        NFT_RECEIVERS.save(deps.storage, &address, &true)?;
+    } else {
+        NFT_RECEIVERS.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_nft_receiver")
+        .add_attribute("address", address)
+        .add_attribute("known", known.to_string()))
+}
+
+pub fn execute_set_creator_allowed(
+    deps: DepsMut,
+    sender: &Addr,
+    address: &str,
+    allowed: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, sender)?;
+
+    let address = deps.api.addr_validate(address)?;
+
+    if allowed {
+        CREATOR_ALLOWLIST.save(deps.storage, &address, &true)?;
+    } else {
+        CREATOR_ALLOWLIST.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_creator_allowed")
+        .add_attribute("address", address)
+        .add_attribute("allowed", allowed.to_string()))
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Purchasing
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Checks on the listing & the buyer every buy must pass, whatever bucket pays
+pub fn ensure_can_buy(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    listing: &Listing,
+    buyer: &Addr,
+) -> Result<(), ContractError> {
+    // Check that listing isn't frozen by the admin
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+    // Check that listing is sold at its ask, not by auction
+    if SEALED_AUCTION.has(storage, &listing.id) || AUCTIONS.has(storage, &listing.id) {
+        return Err(ContractError::SealedAuction {});
+    }
+    // Check that listing is ready for purchase
+    if listing.status != Status::FinalizedReady {
+        return Err(ContractError::NotPurchasable {});
+    }
+    // Check that the user buying is whitelisted, while the presale lasts
+    if !whitelist_allows(listing, env, buyer) {
+        return Err(ContractError::NotWhitelisted {});
+    }
+
+    // Check that the buyer has enough completed purchases for the seller
+    let buyer_purchases = PURCHASE_COUNT.may_load(storage, buyer)?.unwrap_or_default();
+    if buyer_purchases < listing.min_buyer_purchases {
+        return Err(ContractError::BuyerReputationTooLow {});
+    }
+    ensure_buy_cooldown(storage, env, config, buyer)?;
+
+    // Check that there's no existing claimant on listing
+    if listing.claimant.is_some() {
+        return Err(ContractError::NotPurchasable {});
+    }
+    // Check that listing isn't expired, it can still be bought during the refund grace period
+    if past_expiry(listing, env, config.refund_grace_seconds) {
+        return Err(ContractError::Expired {});
+    }
+    Ok(())
+}
+
+// Buyers can't buy again until the config's buy_cooldown_seconds have passed since their last buy
+fn ensure_buy_cooldown(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    buyer: &Addr,
+) -> Result<(), ContractError> {
+    let Some(last_buy) = LAST_BUY.may_load(storage, buyer)? else {
+        return Ok(());
+    };
+    let can_buy_at = last_buy.plus_seconds(config.buy_cooldown_seconds);
+    if env.block.time < can_buy_at {
+        return Err(ContractError::BuyCooldown {
+            seconds: can_buy_at.seconds() - env.block.time.seconds(),
+        });
+    }
+    Ok(())
+}
+
+pub fn execute_buy_listing(
+    deps: DepsMut,
+    env: &Env,
+    buyer: &Addr,
+    listing_id: String,
+    bucket_id: &str,
+    max_price: Option<GenericBalance>,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+
+    // Get bucket (will error if no bucket found)
+    let the_bucket = match BUCKETS.load(deps.storage, (buyer.clone(), bucket_id)) {
+        Ok(buck) => buck,
+        Err(_) => {
+            return Err(ContractError::BucketNotFound {
+                id: bucket_id.to_string(),
+            })
+        }
+    };
+
+    // Check listing exists & get the_listing
+    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+
+    // Check that sender is bucket owner (redundant check)
+    if buyer != &the_bucket.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // Check that a committed listing is only bought with the committed bucket
+    // A bucket offered on this listing can still buy it at the ask
+    let offered = bucket_offers()
+        .may_load(deps.storage, (buyer, bucket_id))?
+        .is_some_and(|offer| offer.listing_id == listing_id);
+    if let Some(commitment) = live_commitment(deps.storage, env, &listing_id)? {
+        if commitment != (buyer.clone(), bucket_id.to_string()) {
+            return Err(ContractError::ListingCommitted {});
+        }
+    } else if !offered {
+        ensure_bucket_not_committed(deps.storage, env, buyer, bucket_id)?;
+    }
+    // Maker & taker fees are a cut of the JUNO in the ask, the taker's is paid on top of it
+    // The bucket pays the ask or one of its alternatives
+    let config = CONFIG.load(deps.storage)?;
+    let paid = matching_ask(&the_listing, &the_bucket.funds, &config, env.block.time)?;
+    // Check that the ask wasn't raised past what the buyer agreed to pay
+    if let Some(max_price) = max_price {
+        let ask = paid.as_ref().map_or(&the_listing.ask, |(ask, _maker_fee, _taker_fee)| ask);
+        if !max_price.contains(ask) {
+            return Err(ContractError::PriceSlippage {});
+        }
+    }
+    // Check that bucket contains required purchase price, plus the taker fee
+    let Some(paid) = paid else {
+        return Err(ContractError::FundsSentNotFundsAsked {
+            which: format!("Bucket ID: {bucket_id}"),
+        });
+    };
+
+    let res = complete_sale(deps, env, the_listing, bucket_id, the_bucket, paid, referrer)?;
+    Ok(res.add_attribute("action", "buy_listing"))
+}
+
+// Adds a sale's proceeds to the seller's lifetime earnings, even when paid to a payout address
+fn record_earnings(
+    storage: &mut dyn Storage,
+    seller: &Addr,
+    proceeds: &GenericBalance,
+) -> Result<(), ContractError> {
+    let amounts = proceeds.native.iter().map(|coin| (coin.denom.as_str(), coin.amount)).chain(
+        proceeds.cw20.iter().map(|cw20_coin| (cw20_coin.address.as_str(), cw20_coin.amount)),
+    );
+    for (denom, amount) in amounts {
+        SELLER_EARNINGS.update(storage, (seller, denom), |earned| {
+            checked_add(earned.unwrap_or_default(), amount)
+        })?;
+    }
+    Ok(())
+}
+
+// Adds sale proceeds to the payee's bucket, merged into one they already hold with the same ID
+// Like AddToBucket, a bucket the payee has committed or offered can't be added to
+fn deposit_proceeds(
+    storage: &mut dyn Storage,
+    env: &Env,
+    payee: &Addr,
+    bucket_id: &str,
+    proceeds: &GenericBalance,
+) -> Result<(), ContractError> {
+    if proceeds.native.is_empty() && proceeds.cw20.is_empty() && proceeds.nfts.is_empty() {
+        return Ok(());
+    }
+    ensure_bucket_not_committed(storage, env, payee, bucket_id)?;
+    let mut bucket = BUCKETS.may_load(storage, (payee.clone(), bucket_id))?.unwrap_or(Bucket {
+        funds: GenericBalance {
+            native: Vec::new(),
+            cw20: Vec::new(),
+            nfts: Vec::new(),
+        },
+        owner: payee.clone(),
+        target_listing: None,
+    });
+    bucket.funds.merge(proceeds)?;
+    BUCKETS.save(storage, (payee.clone(), bucket_id), &bucket)?;
+    Ok(())
+}
+
+// Pays the bucket to the seller for the listing, once the bucket is known to pay `paid`'s ask
+// `paid` is the ask with the maker & taker fees taken out of the bucket
+fn complete_sale(
+    deps: DepsMut,
+    env: &Env,
+    the_listing: Listing,
+    bucket_id: &str,
+    the_bucket: Bucket,
+    paid: (GenericBalance, Uint128, Uint128),
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    let (paid_ask, maker_fee, taker_fee) = paid;
+    let buyer = &the_bucket.owner;
+    let listing_id = the_listing.id.clone();
+    let config = CONFIG.load(deps.storage)?;
+    ensure_can_buy(deps.storage, env, &config, &the_listing, buyer)?;
+    // Sale proceeds go to the payout address if one is set, otherwise the seller
+    let payee = the_listing.payout_address.clone().unwrap_or_else(|| the_listing.creator.clone());
+
+    // Check that the referrer isn't a side of the trade, it takes its cut of the maker & taker fees
+    let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+    if referrer.as_ref().is_some_and(|referrer| {
+        referrer == buyer || referrer == &the_listing.creator || referrer == &payee
+    }) {
+        return Err(ContractError::InvalidReferrer {});
+    }
+    let total_fee = checked_add(maker_fee, taker_fee)?;
+    let referral_fee = match &referrer {
+        Some(_) => total_fee.multiply_ratio(config.referral_bps, 10_000_u128),
+        None => Uint128::zero(),
+    };
+
+    // Check that paying out both sides & the fees stays under the transfer limit
+    let fee =
+        calc_fee(&the_listing.for_sale, &config.rounding).map_err(|_foo| ContractError::FeeCalc)?;
+    let referral_fee_msg =
+        referrer.and_then(|referrer| maker_taker_fee_msg(&referrer, referral_fee));
+    let maker_taker_fee_msg =
+        maker_taker_fee_msg(&config.fee_collector, checked_sub(total_fee, referral_fee)?);
+    let transfers = transfer_count(&the_listing.for_sale)
+        + transfer_count(&the_bucket.funds)
+        + usize::from(fee.is_some())
+        + usize::from(maker_taker_fee_msg.is_some())
+        + usize::from(referral_fee_msg.is_some());
+    if transfers > config.max_transfers_per_buy as usize {
+        return Err(ContractError::TooManyTransfers {
+            count: transfers,
+            max: config.max_transfers_per_buy,
+        });
+    }
+
+    let proceeds = sub_juno(&the_bucket.funds, total_fee)?;
+    ensure_net_proceeds(&the_listing, &proceeds)?;
+
+    record_sale(deps.storage, env, &the_listing, buyer, paid_ask)?;
+    LISTING_COMMITMENT.remove(deps.storage, &listing_id);
+    BUCKET_COMMITMENT.remove(deps.storage, (buyer, bucket_id));
+    bucket_offers().remove(deps.storage, (buyer, bucket_id))?;
+    // Unit listings hand the buyer their unit now & return the bond with the last one
+    let bond_msg = match the_listing.units_available {
+        Some(units) if units > 1 => None,
+        _ => bond_msg(deps.storage, &listing_id, None)?,
+    };
+    let unit_msgs = match the_listing.units_available {
+        Some(units) => sell_unit(deps.storage, &the_listing, units, buyer)?,
+        None => {
+            close_sold_listing(deps.storage, env, &the_listing, buyer)?;
+            Vec::new()
+        }
+    };
+
+    // Delete Old Bucket -> Add to the payee's bucket of the same ID, minus the maker & taker fees
+    BUCKETS.remove(deps.storage, (buyer.clone(), bucket_id));
+    let swap_msg = pay_proceeds(deps.storage, env, &config, &the_listing, bucket_id, proceeds)?;
+    let callback_msg = sale_callback_msg(&the_listing);
+
+    Ok(Response::new()
+        .add_attribute("bucket_used", bucket_id)
+        .add_attribute("listing_purchased:", &listing_id)
+        .add_messages(maker_taker_fee_msg)
+        .add_messages(referral_fee_msg)
+        .add_messages(bond_msg)
+        .add_submessages(unit_msgs)
+        .add_submessages(swap_msg)
+        .add_submessages(callback_msg))
+}
+
+// Every denom of the listing's floor must be netted in full, so an unpaid denom fails too
+fn ensure_net_proceeds(
+    the_listing: &Listing,
+    proceeds: &GenericBalance,
+) -> Result<(), ContractError> {
+    let too_low = the_listing.min_net_proceeds.iter().any(|min| {
+        let net = proceeds.native.iter().find(|coin| coin.denom == min.denom);
+        net.map_or(Uint128::zero(), |coin| coin.amount) < min.amount
+    });
+    if too_low {
+        return Err(ContractError::NetProceedsTooLow {});
+    }
+    Ok(())
+}
+
+// Pays a sale's proceeds into the payee's bucket & counts them as the seller's earnings
+// Native proceeds are swapped first if the listing asks for it
+fn pay_proceeds(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    the_listing: &Listing,
+    bucket_id: &str,
+    proceeds: GenericBalance,
+) -> Result<Option<SubMsg>, ContractError> {
+    let payee = the_listing.payout_address.clone().unwrap_or_else(|| the_listing.creator.clone());
+    record_earnings(storage, &the_listing.creator, &proceeds)?;
+    let (proceeds, swap_msg) = swap_proceeds(
+        storage,
+        config,
+        the_listing.auto_swap_to.clone(),
+        &payee,
+        bucket_id,
+        proceeds,
+    )?;
+    deposit_proceeds(storage, env, &payee, bucket_id, &proceeds)?;
+    Ok(swap_msg)
+}
+
+// The seller's callback runs last & a failure is ignored in the reply, so the sale stands
+fn sale_callback_msg(the_listing: &Listing) -> Option<SubMsg> {
+    the_listing.on_sale_callback.clone().map(|callback| {
+        SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: callback.contract_addr.to_string(),
+                msg: callback.msg,
+                funds: Vec::new(),
+            },
+            SALE_CALLBACK_REPLY_ID,
+        )
+    })
+}
+
+// Records a sale in the price history & recent sales, and counts it for the buyer
+fn record_sale(
+    storage: &mut dyn Storage,
+    env: &Env,
+    the_listing: &Listing,
+    buyer: &Addr,
+    paid_ask: GenericBalance,
+) -> StdResult<()> {
+    // Record the paid ask as the last sale price of every collection in the listing
+    for nft in &the_listing.for_sale.nfts {
+        for coin in &paid_ask.native {
+            LAST_SALE_PRICE.save(storage, (&nft.contract_address, &coin.denom), &coin.amount)?;
+        }
+        for cw20_coin in &paid_ask.cw20 {
+            LAST_SALE_PRICE.save(
+                storage,
+                (&nft.contract_address, cw20_coin.address.as_str()),
+                &cw20_coin.amount,
+            )?;
+        }
+    }
+
+    record_floor(storage, env, &the_listing.for_sale.nfts, &paid_ask)?;
+
+    let mut recent_sales = RECENT_SALES.may_load(storage)?.unwrap_or_default();
+    recent_sales.push(Sale {
+        listing_id: the_listing.id.clone(),
+        price: paid_ask,
+        buyer: buyer.clone(),
+        seller: the_listing.creator.clone(),
+        timestamp: env.block.time,
+    });
+    if recent_sales.len() > MAX_RECENT_SALES {
+        recent_sales.drain(..recent_sales.len() - MAX_RECENT_SALES);
+    }
+    RECENT_SALES.save(storage, &recent_sales)?;
+
+    PURCHASE_COUNT
+        .update(storage, buyer, |count| -> StdResult<_> { Ok(count.unwrap_or_default() + 1) })?;
+    LAST_BUY.save(storage, buyer, &env.block.time)?;
+    Ok(())
+}
+
+// Sends the buyer an equal share of a unit listing's for_sale, less the withdrawal fee
+// The listing stays up until its last unit, which closes & archives it
+fn sell_unit(
+    storage: &mut dyn Storage,
+    the_listing: &Listing,
+    units: u32,
+    buyer: &Addr,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let mut unit = the_listing.for_sale.clone();
+    unit.native.iter_mut().for_each(|c| c.amount = c.amount.multiply_ratio(1_u128, units));
+    unit.cw20.iter_mut().for_each(|c| c.amount = c.amount.multiply_ratio(1_u128, units));
+    let listing = Listing {
+        for_sale: the_listing.for_sale.subtract(&unit)?,
+        units_available: Some(units - 1),
+        ..the_listing.clone()
+    };
+    let dust_threshold = CONFIG.load(storage)?.dust_threshold;
+    let dust = !dust_threshold.is_zero()
+        && listing.for_sale.native.iter().all(|c| c.amount < dust_threshold)
+        && listing.for_sale.cw20.iter().all(|c| c.amount < dust_threshold);
+
+    let mut res = Response::new().add_attribute("units_available", (units - 1).to_string());
+    record_activity(storage, buyer, &the_listing.id, ActivityRole::Buyer)?;
+    if units == 1 {
+        archive_listing(
+            storage,
+            &Listing {
+                status: Status::Closed,
+                claimant: Some(buyer.clone()),
+                ..listing
+            },
+        )?;
+    } else if dust {
+        // Too little is left to be worth selling, it goes back to the seller with the bond
+        res = archive_and_pay_back(storage, &listing, None, res)?;
+    } else {
+        listingz().replace(
+            storage,
+            (&the_listing.creator, the_listing.id.clone()),
+            Some(&listing),
+            Some(the_listing),
+        )?;
+    }
+
+    Ok(release_purchase(storage, buyer, &unit, res)?.messages)
+}
+
+// Hands a sold listing to the buyer, closed with them as claimant & its vesting started
+fn close_sold_listing(
+    storage: &mut dyn Storage,
+    env: &Env,
+    the_listing: &Listing,
+    buyer: &Addr,
+) -> StdResult<()> {
+    release_collections(storage, &the_listing.for_sale.nfts)?;
+    record_activity(storage, buyer, &the_listing.id, ActivityRole::Buyer)?;
+
+    // Vesting starts once the listing is bought
+    let vesting = the_listing.vesting.clone().map(|vesting| Vesting {
+        start: Some(env.block.time),
+        ..vesting
+    });
+
+    // Delete Old Listing -> Save new listing with listing_buyer in key & creator
+    // The whole for_sale bundle moves with the listing, it's never split
+    LISTING_SELLER.save(storage, &the_listing.id, &the_listing.creator)?;
+    listingz().remove(storage, (&the_listing.creator, the_listing.id.clone()))?;
+    listingz().save(
+        storage,
+        (buyer, the_listing.id.clone()),
+        &Listing {
+            creator: buyer.clone(),
+            claimant: Some(buyer.clone()),
+            status: Status::Closed,
+            vesting,
+            ..the_listing.clone()
+        },
+    )
+}
+
+// Sends the native proceeds not already in the listing's auto_swap_to denom to the swap router
+// Returns what's left for the payee's bucket, the swapped proceeds go straight to the payee
+fn swap_proceeds(
+    storage: &mut dyn Storage,
+    config: &Config,
+    auto_swap_to: Option<String>,
+    payee: &Addr,
+    bucket_id: &str,
+    proceeds: GenericBalance,
+) -> Result<(GenericBalance, Option<SubMsg>), ContractError> {
+    let (Some(router), Some(ask_denom)) = (&config.swap_router, auto_swap_to) else {
+        return Ok((proceeds, None));
+    };
+    let (kept, offer): (Vec<Coin>, Vec<Coin>) =
+        proceeds.native.iter().cloned().partition(|coin| coin.denom == ask_denom);
+    if offer.is_empty() {
+        return Ok((proceeds, None));
+    }
+
+    PENDING_SWAP.save(
+        storage,
+        &PendingSwap {
+            payee: payee.clone(),
+            bucket_id: bucket_id.to_string(),
+            offer: offer.clone(),
+        },
+    )?;
+    let swap = WasmMsg::Execute {
+        contract_addr: router.to_string(),
+        msg: to_binary(&SwapRouterExecuteMsg::Swap {
+            ask_denom,
+            recipient: payee.to_string(),
+        })?,
+        funds: offer,
+    };
+
+    Ok((
+        GenericBalance {
+            native: kept,
+            ..proceeds
+        },
+        Some(SubMsg::reply_always(swap, SWAP_REPLY_ID)),
+    ))
+}
+
+pub fn execute_swap_reply(deps: DepsMut, failed: bool) -> Result<Response, ContractError> {
+    let pending = PENDING_SWAP.load(deps.storage)?;
+    PENDING_SWAP.remove(deps.storage);
+
+    if !failed {
+        return Ok(Response::new());
+    }
+    // The router's state & the funds sent to it were reverted, so the proceeds are still held
+    let key = (pending.payee.clone(), pending.bucket_id.as_str());
+    let mut bucket = BUCKETS.may_load(deps.storage, key.clone())?.unwrap_or(Bucket {
+        funds: GenericBalance {
+            native: Vec::new(),
+            cw20: Vec::new(),
+            nfts: Vec::new(),
+        },
+        owner: pending.payee.clone(),
+        target_listing: None,
+    });
+    bucket.funds.add_tokens(Balance::from(pending.offer))?;
+    BUCKETS.save(deps.storage, key, &bucket)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "swap_failed")
+        .add_attribute("bucket_id", pending.bucket_id))
+}
+
+// Locks a bucket that pays the listing's ask until the trade is settled
+pub fn execute_commit_to_listing(
+    deps: DepsMut,
+    env: &Env,
+    buyer: &Addr,
+    listing_id: String,
+    bucket_id: &str,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+
+    let the_bucket = get_bucket_if_sender_is_owner(&deps, buyer, bucket_id)?;
+    ensure_bucket_not_committed(deps.storage, env, buyer, bucket_id)?;
+
+    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    // Only a buyer who could buy now can hold the listing, settling runs every buy check again
+    let config = CONFIG.load(deps.storage)?;
+    ensure_can_buy(deps.storage, env, &config, &the_listing, buyer)?;
+    if live_commitment(deps.storage, env, &listing_id)?.is_some() {
+        return Err(ContractError::ListingCommitted {});
+    }
+
+    if matching_ask(&the_listing, &the_bucket.funds, &config, env.block.time)?.is_none() {
+        return Err(ContractError::FundsSentNotFundsAsked {
+            which: format!("Bucket ID: {bucket_id}"),
+        });
+    }
+
+    LISTING_COMMITMENT.save(deps.storage, &listing_id, &(buyer.clone(), bucket_id.to_string()))?;
+    BUCKET_COMMITMENT.save(deps.storage, (buyer, bucket_id), &listing_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "commit_to_listing")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("bucket_id", bucket_id))
+}
+
+// Anyone can settle a commitment, the listing is bought with the committed bucket
+pub fn execute_settle_commitment(
+    deps: DepsMut,
+    env: &Env,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let Some((buyer, bucket_id)) = live_commitment(deps.storage, env, &listing_id)? else {
+        return Err(ContractError::NotFound { typ: "Commitment".to_string(), id: listing_id });
+    };
+
+    execute_buy_listing(deps, env, &buyer, listing_id, &bucket_id, None, None)
+}
+
+// Locks a bucket as a standing offer on a listing, whatever its ask, for the seller to accept
+// The bucket unlocks once the offer expires, or the listing is removed or bought
+pub fn execute_lock_bucket_to_listing(
+    deps: DepsMut,
+    env: &Env,
+    buyer: &Addr,
+    bucket_id: &str,
+    listing_id: String,
+    expiration_seconds: Option<u64>,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+
+    let the_bucket = get_bucket_if_sender_is_owner(&deps, buyer, bucket_id)?;
+    ensure_bucket_not_committed(deps.storage, env, buyer, bucket_id)?;
+    let config = CONFIG.load(deps.storage)?;
+    if !offer_meets_minimum(&the_bucket.funds, &config.min_offer_value) {
+        return Err(ContractError::OfferTooLow {});
+    }
+
+    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if the_listing.claimant.is_some() {
+        return Err(ContractError::NotPurchasable {});
+    }
+
+    // At the cap, the new offer takes the place of the lowest one, freeing its bucket
+    let mut res = Response::new();
+    let max_offers = the_listing.max_offers.unwrap_or(config.max_offers);
+    if max_offers > 0 {
+        let evicted = offer_to_evict(
+            deps.storage,
+            env,
+            &the_listing,
+            (buyer, bucket_id),
+            &the_bucket.funds,
+            max_offers,
+        )?;
+        if let Some((evicted_buyer, evicted_bucket_id)) = evicted {
+            bucket_offers().remove(deps.storage, (&evicted_buyer, &evicted_bucket_id))?;
+            res = res
+                .add_attribute("evicted_buyer", evicted_buyer)
+                .add_attribute("evicted_bucket_id", evicted_bucket_id);
+        }
+    }
+
+    let expiration = expiration_seconds.map(|seconds| env.block.time.plus_seconds(seconds));
+    bucket_offers().save(
+        deps.storage,
+        (buyer, bucket_id),
+        &Offer {
+            listing_id: listing_id.clone(),
+            expiration,
+        },
+    )?;
+
+    Ok(res
+        .add_attribute("action", "lock_bucket_to_listing")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("bucket_id", bucket_id))
+}
+
+// The lowest live offer on a listing holding max_offers of them, which funds must beat
+// Offers rank by their amount of the ask's first denom, the offering bucket itself isn't counted
+fn offer_to_evict(
+    storage: &dyn Storage,
+    env: &Env,
+    the_listing: &Listing,
+    offering: (&Addr, &str),
+    funds: &GenericBalance,
+    max_offers: u32,
+) -> Result<Option<(Addr, String)>, ContractError> {
+    let denom = the_listing
+        .ask
+        .native
+        .first()
+        .map(|coin| coin.denom.clone())
+        .or_else(|| the_listing.ask.cw20.first().map(|coin| coin.address.to_string()))
+        .unwrap_or_default();
+
+    let mut offers = Vec::new();
+    for entry in bucket_offers().idx.listing.prefix(the_listing.id.clone()).range_raw(
+        storage,
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let (pk, offer) = entry?;
+        let (buyer, bucket_id) = <(&Addr, &str)>::from_vec(pk)?;
+        if (&buyer, bucket_id.as_str()) == offering || !live_offer(storage, env, &offer)? {
+            continue;
+        }
+        let bucket = BUCKETS.may_load(storage, (buyer.clone(), &bucket_id))?;
+        let amount = bucket.and_then(|bucket| amount_of(&bucket.funds, &denom));
+        offers.push((amount, buyer, bucket_id));
+    }
+    if offers.len() < max_offers as usize {
+        return Ok(None);
+    }
+
+    // Of tied offers, the one with the lowest (buyer, bucket_id) is evicted
+    let Some((lowest, buyer, bucket_id)) = offers.into_iter().min_by_key(|offer| offer.0) else {
+        return Ok(None);
+    };
+    if amount_of(funds, &denom) <= lowest {
+        return Err(ContractError::TooManyOffers {
+            max: max_offers,
+        });
+    }
+    Ok(Some((buyer, bucket_id)))
+}
+
+// The seller sells the listing for the whole offered bucket, the maker & taker fees come out of it
+pub fn execute_accept_offer(
+    deps: DepsMut,
+    env: &Env,
+    seller: &Addr,
+    listing_id: String,
+    buyer: &str,
+    bucket_id: &str,
+) -> Result<Response, ContractError> {
+    let (the_listing, the_bucket) = load_offer(&deps, env, seller, listing_id, buyer, bucket_id)?;
+    let config = CONFIG.load(deps.storage)?;
+    let (maker_fee, taker_fee) = maker_taker_fees(&the_bucket.funds, &config, env.block.time)?;
+    let paid = (the_bucket.funds.clone(), maker_fee, taker_fee);
+
+    let res = complete_sale(deps, env, the_listing, bucket_id, the_bucket, paid, None)?;
+    Ok(res.add_attribute("action", "accept_offer"))
+}
+
+// The seller sells the listing for only the accepted assets of the offered bucket
+// The fees come out of the accepted assets & the rest is left in the buyer's bucket, unlocked
+pub fn execute_accept_partial_offer(
+    mut deps: DepsMut,
+    env: &Env,
+    seller: &Addr,
+    listing_id: String,
+    buyer: &str,
+    bucket_id: &str,
+    accepted_assets: GenericBalance,
+) -> Result<Response, ContractError> {
+    if accepted_assets.native.is_empty()
+        && accepted_assets.cw20.is_empty()
+        && accepted_assets.nfts.is_empty()
+    {
+        return Err(ContractError::NoTokens {});
+    }
+    let (the_listing, mut the_bucket) =
+        load_offer(&deps, env, seller, listing_id, buyer, bucket_id)?;
+    if !the_bucket.funds.contains(&accepted_assets) {
+        return Err(ContractError::NotInOffer {});
+    }
+    let rest = the_bucket.funds.subtract(&accepted_assets)?;
+    the_bucket.funds = accepted_assets;
+    let buyer = the_bucket.owner.clone();
+
+    let config = CONFIG.load(deps.storage)?;
+    let (maker_fee, taker_fee) = maker_taker_fees(&the_bucket.funds, &config, env.block.time)?;
+    let paid = (the_bucket.funds.clone(), maker_fee, taker_fee);
+
+    let res = complete_sale(deps.branch(), env, the_listing, bucket_id, the_bucket, paid, None)?;
+    deposit_proceeds(deps.storage, env, &buyer, bucket_id, &rest)?;
+    Ok(res.add_attribute("action", "accept_partial_offer"))
+}
+
+// Loads a live offer on the seller's listing, with the bucket it locks
+fn load_offer(
+    deps: &DepsMut,
+    env: &Env,
+    seller: &Addr,
+    listing_id: String,
+    buyer: &str,
+    bucket_id: &str,
+) -> Result<(Listing, Bucket), ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let buyer = deps.api.addr_validate(buyer)?;
+
+    let offer = bucket_offers()
+        .may_load(deps.storage, (&buyer, bucket_id))?
+        .filter(|offer| offer.listing_id == listing_id);
+    let Some(offer) = offer else {
+        return Err(ContractError::NotFound {
+            typ: "Offer".to_string(),
+            id: format!("{buyer}/{bucket_id}"),
+        });
+    };
+    if offer.expiration.is_some_and(|expiration| env.block.time >= expiration) {
+        return Err(ContractError::OfferExpired {});
+    }
+
+    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if seller != &the_listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if live_commitment(deps.storage, env, &listing_id)?.is_some() {
+        return Err(ContractError::ListingCommitted {});
+    }
+
+    let the_bucket = get_bucket_if_sender_is_owner(deps, &buyer, bucket_id)?;
+    Ok((the_listing, the_bucket))
+}
+
+// Sells a listing that isn't finalized by sealed-bid auction instead of at its ask
+// Bids are committed for commit_seconds, then revealed for reveal_seconds
+pub fn execute_start_sealed_auction(
+    deps: DepsMut,
+    env: &Env,
+    user_sender: &Addr,
+    listing_id: String,
+    commit_seconds: u64,
+    reveal_seconds: u64,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let listing = validate_basic_listings(&deps, user_sender, &listing_id, false)?;
+
+    let phase_seconds = MIN_FINALIZE_SECONDS..=MAX_FINALIZE_SECONDS;
+    if !phase_seconds.contains(&commit_seconds) || !phase_seconds.contains(&reveal_seconds) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    // The ask is the reserve, so bids can be compared by amount alone
+    let ([reserve], true) =
+        (listing.ask.native.as_slice(), listing.ask.cw20.is_empty() && listing.ask.nfts.is_empty())
+    else {
+        return Err(ContractError::InvalidAuctionAsk {});
+    };
+    if listing.units_available.is_some() {
+        return Err(ContractError::InvalidUnits {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    ensure_bond_posted(deps.storage, &config, &listing_id)?;
+
+    let commit_end = env.block.time.plus_seconds(commit_seconds);
+    let auction = SealedAuction {
+        reserve: reserve.clone(),
+        commit_end,
+        reveal_end: commit_end.plus_seconds(reveal_seconds),
+    };
+    SEALED_AUCTION.save(deps.storage, &listing_id, &auction)?;
+
+    // Finalized without an expiry, settling the auction ends it
+    listingz().replace(
+        deps.storage,
+        (user_sender, listing_id.clone()),
+        Some(&Listing {
+            finalized_time: Some(env.block.time),
+            expiration_time: None,
+            expiration_height: None,
+            status: Status::FinalizedReady,
+            ..listing.clone()
+        }),
+        Some(&listing),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "start_sealed_auction")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("commit_end", auction.commit_end.seconds().to_string())
+        .add_attribute("reveal_end", auction.reveal_end.seconds().to_string()))
+}
+
+fn load_sealed_auction(
+    storage: &dyn Storage,
+    listing_id: &str,
+) -> Result<SealedAuction, ContractError> {
+    SEALED_AUCTION.may_load(storage, listing_id)?.ok_or(ContractError::NotSealedAuction {})
+}
+
+// Removes an auction & returns its bids, for settlement or when its listing is removed
+fn take_sealed_bids(
+    storage: &mut dyn Storage,
+    listing_id: &str,
+) -> StdResult<Vec<(Addr, SealedBid)>> {
+    let bids = SEALED_BIDS
+        .prefix(listing_id)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (bidder, _bid) in &bids {
+        SEALED_BIDS.remove(storage, (listing_id, bidder));
+    }
+    SEALED_AUCTION.remove(storage, listing_id);
+    Ok(bids)
+}
+
+fn escrow_msg(reserve: &Coin, to: &Addr, amount: Uint128) -> BankMsg {
+    BankMsg::Send {
+        to_address: to.to_string(),
+        amount: vec![Coin {
+            denom: reserve.denom.clone(),
+            amount,
+        }],
+    }
+}
+
+// Escrows the most a bidder would pay, behind the hash of the bid they'll reveal
+pub fn execute_commit_bid(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    listing_id: String,
+    commitment: HexBinary,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let bidder = &info.sender;
+
+    let auction = load_sealed_auction(deps.storage, &listing_id)?;
+    if env.block.time >= auction.commit_end {
+        return Err(ContractError::AuctionPhase {
+            phase: "commit".to_string(),
+        });
+    }
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+    if bidder == &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !whitelist_allows(&listing, env, bidder) {
+        return Err(ContractError::NotWhitelisted {});
+    }
+    if SEALED_BIDS.has(deps.storage, (&listing_id, bidder)) {
+        return Err(ContractError::AlreadyBid {});
+    }
+    let [escrow] = info.funds.as_slice() else {
+        return Err(ContractError::InvalidEscrow {
+            denom: auction.reserve.denom,
+        });
+    };
+    if escrow.denom != auction.reserve.denom {
+        return Err(ContractError::InvalidEscrow {
+            denom: auction.reserve.denom,
+        });
+    }
+
+    SEALED_BIDS.save(
+        deps.storage,
+        (&listing_id, bidder),
+        &SealedBid {
+            commitment,
+            escrow: escrow.amount,
+            revealed: None,
+            committed_height: env.block.height,
+        },
+    )?;
+    record_activity(deps.storage, bidder, &listing_id, ActivityRole::Bidder)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "commit_bid")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("escrow", escrow.to_string()))
+}
+
+// Opens a committed bid, it must match the commitment & be payable from the escrow
+// A bid that's never revealed can't win & is refunded at settlement
+pub fn execute_reveal_bid(
+    deps: DepsMut,
+    env: &Env,
+    bidder: &Addr,
+    listing_id: String,
+    amount: Uint128,
+    nonce: &str,
+) -> Result<Response, ContractError> {
+    let auction = load_sealed_auction(deps.storage, &listing_id)?;
+    if env.block.time < auction.commit_end || env.block.time >= auction.reveal_end {
+        return Err(ContractError::AuctionPhase {
+            phase: "reveal".to_string(),
+        });
+    }
+    let Some(bid) = SEALED_BIDS.may_load(deps.storage, (&listing_id, bidder))? else {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // The bidder is hashed in so a commitment can't be copied by another address
+    let hash = Sha256::digest(format!("{bidder}:{amount}:{nonce}").as_bytes());
+    if bid.revealed.is_some() || hash.as_slice() != bid.commitment.as_slice() {
+        return Err(ContractError::InvalidReveal {});
+    }
+    let price = GenericBalance {
+        native: vec![Coin {
+            denom: auction.reserve.denom.clone(),
+            amount,
+        }],
+        cw20: Vec::new(),
+        nfts: Vec::new(),
+    };
+    let (_maker_fee, taker_fee) =
+        maker_taker_fees(&price, &CONFIG.load(deps.storage)?, env.block.time)?;
+    if amount < auction.reserve.amount || checked_add(amount, taker_fee)? > bid.escrow {
+        return Err(ContractError::BidOutOfRange {});
+    }
+
+    SEALED_BIDS.save(
+        deps.storage,
+        (&listing_id, bidder),
+        &SealedBid {
+            revealed: Some(amount),
+            ..bid
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reveal_bid")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("amount", amount))
+}
+
+// Sells the listing to the highest revealed bid, the earliest committed one on a tie
+// Other bids & the winner's unused escrow are refunded, with no winner it's unfinalized
+// Unrevealed bids are slashed by the config's reveal_deposit_slash_bps
+pub fn execute_settle_auction(
+    deps: DepsMut,
+    env: &Env,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    if let Some(auction) = AUCTIONS.may_load(deps.storage, &listing_id)? {
+        return settle_open_auction(deps, env, listing_id, auction);
+    }
+    let auction = load_sealed_auction(deps.storage, &listing_id)?;
+    if env.block.time < auction.reveal_end {
+        return Err(ContractError::AuctionPhase {
+            phase: "settlement".to_string(),
+        });
+    }
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+    let bids = take_sealed_bids(deps.storage, &listing_id)?;
+
+    let winner = bids
+        .iter()
+        .filter_map(|(bidder, bid)| bid.revealed.map(|amount| (bidder, bid, amount)))
+        .max_by(|(_, a, a_amount), (_, b, b_amount)| {
+            a_amount.cmp(b_amount).then(b.committed_height.cmp(&a.committed_height))
+        });
+    let config = CONFIG.load(deps.storage)?;
+    // Sale proceeds go to the payout address if one is set, otherwise the seller
+    let payee = listing.payout_address.clone().unwrap_or_else(|| listing.creator.clone());
+
+    // Bids never revealed forfeit reveal_deposit_slash_bps of their escrow to the payee
+    let mut msgs: Vec<BankMsg> = Vec::new();
+    let mut slashed = Uint128::zero();
+    for (bidder, bid) in &bids {
+        if winner.is_some_and(|(winner, _, _)| bidder == winner) {
+            continue;
+        }
+        let slash = match bid.revealed {
+            Some(_) => Uint128::zero(),
+            None => bid.escrow.multiply_ratio(config.reveal_deposit_slash_bps, 10_000_u128),
+        };
+        slashed = checked_add(slashed, slash)?;
+        if slash < bid.escrow {
+            msgs.push(escrow_msg(&auction.reserve, bidder, bid.escrow - slash));
+        }
+    }
+    if !slashed.is_zero() {
+        msgs.push(escrow_msg(&auction.reserve, &payee, slashed));
+    }
+    let res = Response::new()
+        .add_attribute("action", "settle_auction")
+        .add_attribute("listing_id", &listing_id)
+        .add_attribute("slashed", slashed);
+
+    let Some((winner, bid, amount)) = winner else {
+        reopen_unsold(deps.storage, &listing)?;
+        return Ok(res.add_attribute("winner", "none").add_messages(msgs));
+    };
+    let winning_bid = AuctionBid {
+        bidder: winner.clone(),
+        amount,
+        escrow: bid.escrow,
+    };
+    let payout_msgs =
+        pay_winning_bid(deps.storage, env, &config, &listing, &auction.reserve, &winning_bid)?;
+
+    Ok(res
+        .add_attribute("winner", winner)
+        .add_attribute("amount", amount)
+        .add_messages(msgs)
+        .add_submessages(payout_msgs))
+}
+
+// No winner, the owner can finalize or auction it again, or remove it
+fn reopen_unsold(storage: &mut dyn Storage, listing: &Listing) -> StdResult<()> {
+    listingz().replace(
+        storage,
+        (&listing.creator, listing.id.clone()),
+        Some(&Listing {
+            finalized_time: None,
+            status: Status::BeingPrepared,
+            ..listing.clone()
+        }),
+        Some(listing),
+    )
+}
+
+fn bid_price(reserve: &Coin, amount: Uint128) -> GenericBalance {
+    GenericBalance {
+        native: vec![Coin {
+            denom: reserve.denom.clone(),
+            amount,
+        }],
+        cw20: Vec::new(),
+        nfts: Vec::new(),
+    }
+}
+
+// Sells an auctioned listing to the winning bid, paying the payee & the fees from its escrow
+// Fees are taken like a buy's, a taker fee raised since the bid is capped by the escrow
+// The seller's callback fires last, like after a buy
+fn pay_winning_bid(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    listing: &Listing,
+    reserve: &Coin,
+    bid: &AuctionBid,
+) -> Result<Vec<SubMsg>, ContractError> {
+    // Sale proceeds go to the payout address if one is set, otherwise the seller
+    let payee = listing.payout_address.clone().unwrap_or_else(|| listing.creator.clone());
+    let price = bid_price(reserve, bid.amount);
+    let (maker_fee, taker_fee) = maker_taker_fees(&price, config, env.block.time)?;
+    let unused = checked_sub(bid.escrow, bid.amount)?;
+    let taker_fee = taker_fee.min(unused);
+    let change = checked_sub(unused, taker_fee)?;
+
+    let mut msgs: Vec<CosmosMsg> = Vec::new();
+    if !change.is_zero() {
+        msgs.push(escrow_msg(reserve, &bid.bidder, change).into());
+    }
+    let proceeds = sub_juno(&price, maker_fee)?;
+    record_earnings(storage, &listing.creator, &proceeds)?;
+    if !proceeds.native.is_empty() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: payee.to_string(),
+                amount: proceeds.native,
+            }
+            .into(),
+        );
+    }
+    msgs.extend(bond_msg(storage, &listing.id, None)?.map(CosmosMsg::from));
+    msgs.extend(maker_taker_fee_msg(&config.fee_collector, checked_add(maker_fee, taker_fee)?));
+
+    record_sale(storage, env, listing, &bid.bidder, price)?;
+    close_sold_listing(storage, env, listing, &bid.bidder)?;
+    Ok(msgs.into_iter().map(SubMsg::new).chain(sale_callback_msg(listing)).collect())
+}
+
+// Sells a finalized listing by open auction instead of at its ask, its for_sale carries over
+// Bids must reach the reserve, then beat the highest by min_increment, until end_time
+pub fn execute_convert_to_auction(
+    deps: DepsMut,
+    env: &Env,
+    user_sender: &Addr,
+    listing_id: String,
+    reserve: Coin,
+    min_increment: Uint128,
+    end_time: Timestamp,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if user_sender != &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Only listings that can still be bought at their ask, with no buyer committed to them
+    let config = CONFIG.load(deps.storage)?;
+    if listing.status != Status::FinalizedReady
+        || listing.frozen
+        || listing.claimant.is_some()
+        || listing.units_available.is_some()
+        || SEALED_AUCTION.has(deps.storage, &listing_id)
+        || AUCTIONS.has(deps.storage, &listing_id)
+        || past_expiry(&listing, env, config.refund_grace_seconds)
+        || live_commitment(deps.storage, env, &listing_id)?.is_some()
+    {
+        return Err(ContractError::NotConvertible {});
+    }
+    if end_time <= env.block.time || end_time > env.block.time.plus_seconds(MAX_FINALIZE_SECONDS) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+
+    AUCTIONS.save(
+        deps.storage,
+        &listing_id,
+        &Auction {
+            reserve,
+            min_increment,
+            end_time,
+            high_bid: None,
+        },
+    )?;
+
+    // Without an expiry, settling the auction ends it
+    listingz().replace(
+        deps.storage,
+        (user_sender, listing_id.clone()),
+        Some(&Listing {
+            expiration_time: None,
+            expiration_height: None,
+            ..listing.clone()
+        }),
+        Some(&listing),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "convert_to_auction")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("end_time", end_time.seconds().to_string()))
+}
+
+// Bidders bid against the params they saw, so they're fixed once the first bid is placed
+pub fn execute_update_auction_params(
+    deps: DepsMut,
+    env: &Env,
+    user_sender: &Addr,
+    listing_id: String,
+    reserve: Coin,
+    min_increment: Uint128,
+    end_time: Timestamp,
+) -> Result<Response, ContractError> {
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if user_sender != &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    let Some(auction) = AUCTIONS.may_load(deps.storage, &listing_id)? else {
+        return Err(ContractError::NotAuction {});
+    };
+    if auction.high_bid.is_some() {
+        return Err(ContractError::AuctionHasBids {});
+    }
+    if end_time <= env.block.time || end_time > env.block.time.plus_seconds(MAX_FINALIZE_SECONDS) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+
+    AUCTIONS.save(
+        deps.storage,
+        &listing_id,
+        &Auction {
+            reserve,
+            min_increment,
+            end_time,
+            high_bid: None,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_auction_params")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("end_time", end_time.seconds().to_string()))
+}
+
+// Escrows a bid that beats the auction's highest, refunding the bid it beats
+pub fn execute_place_bid(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    listing_id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let bidder = &info.sender;
+
+    let Some(mut auction) = AUCTIONS.may_load(deps.storage, &listing_id)? else {
+        return Err(ContractError::NotAuction {});
+    };
+    if env.block.time >= auction.end_time {
+        return Err(ContractError::AuctionPhase {
+            phase: "bidding".to_string(),
+        });
+    }
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+    if bidder == &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !whitelist_allows(&listing, env, bidder) {
+        return Err(ContractError::NotWhitelisted {});
+    }
+    let [escrow] = info.funds.as_slice() else {
+        return Err(ContractError::InvalidEscrow {
+            denom: auction.reserve.denom,
+        });
+    };
+    if escrow.denom != auction.reserve.denom {
+        return Err(ContractError::InvalidEscrow {
+            denom: auction.reserve.denom,
+        });
+    }
+
+    // The first bid must reach the reserve, later ones must raise the highest by min_increment
+    let min_bid = match &auction.high_bid {
+        Some(high_bid) => checked_add(high_bid.amount, auction.min_increment.max(Uint128::one()))?,
+        None => auction.reserve.amount,
+    };
+    let price = bid_price(&auction.reserve, amount);
+    let (_maker_fee, taker_fee) =
+        maker_taker_fees(&price, &CONFIG.load(deps.storage)?, env.block.time)?;
+    if amount < min_bid || checked_add(amount, taker_fee)? > escrow.amount {
+        return Err(ContractError::BidOutOfRange {});
+    }
+
+    let outbid = auction.high_bid.replace(AuctionBid {
+        bidder: bidder.clone(),
+        amount,
+        escrow: escrow.amount,
+    });
+    let refund_msg =
+        outbid.map(|outbid| escrow_msg(&auction.reserve, &outbid.bidder, outbid.escrow));
+    AUCTIONS.save(deps.storage, &listing_id, &auction)?;
+    record_activity(deps.storage, bidder, &listing_id, ActivityRole::Bidder)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "place_bid")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("amount", amount)
+        .add_messages(refund_msg))
+}
+
+// Sells an open auction's listing to its highest bid once it's ended
+fn settle_open_auction(
+    deps: DepsMut,
+    env: &Env,
+    listing_id: String,
+    auction: Auction,
+) -> Result<Response, ContractError> {
+    if env.block.time < auction.end_time {
+        return Err(ContractError::AuctionPhase {
+            phase: "settlement".to_string(),
+        });
+    }
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+    AUCTIONS.remove(deps.storage, &listing_id);
+    let res = Response::new()
+        .add_attribute("action", "settle_auction")
+        .add_attribute("listing_id", &listing_id);
+
+    let Some(high_bid) = auction.high_bid else {
+        reopen_unsold(deps.storage, &listing)?;
+        return Ok(res.add_attribute("winner", "none"));
+    };
+    let config = CONFIG.load(deps.storage)?;
+    let payout_msgs =
+        pay_winning_bid(deps.storage, env, &config, &listing, &auction.reserve, &high_bid)?;
+
+    Ok(res
+        .add_attribute("winner", &high_bid.bidder)
+        .add_attribute("amount", high_bid.amount)
+        .add_submessages(payout_msgs))
+}
+
+// Owner only, ends an open auction before its end_time by selling to the highest bid
+pub fn execute_accept_highest_bid(
+    deps: DepsMut,
+    env: &Env,
+    user_sender: &Addr,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let Some(auction) = AUCTIONS.may_load(deps.storage, &listing_id)? else {
+        return Err(ContractError::NotAuction {});
+    };
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    if user_sender != &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+    // PlaceBid holds every bid to the reserve, so any high bid meets it
+    let reserve = auction.reserve;
+    let Some(high_bid) = auction.high_bid else {
+        return Err(ContractError::ReserveNotMet {});
+    };
+    AUCTIONS.remove(deps.storage, &listing_id);
+
+    let config = CONFIG.load(deps.storage)?;
+    let payout_msgs = pay_winning_bid(deps.storage, env, &config, &listing, &reserve, &high_bid)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_highest_bid")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("winner", &high_bid.bidder)
+        .add_attribute("amount", high_bid.amount)
+        .add_submessages(payout_msgs))
+}
+
+// Offers the proposer's listing for another owner's, both keep selling until it's accepted
+pub fn execute_propose_swap(
+    deps: DepsMut,
+    env: &Env,
+    proposer: &Addr,
+    listing_id: String,
+    counter_listing_id: String,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let listing = load_swappable(deps.storage, env, &listing_id)?;
+    let counter_listing = load_swappable(deps.storage, env, &counter_listing_id)?;
+    if proposer != &listing.creator || proposer == &counter_listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    SWAP_PROPOSALS.save(deps.storage, (&listing_id, &counter_listing_id), proposer)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_swap")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("counter_listing_id", counter_listing_id))
+}
+
+// Finalized listings that are still their owner's to trade away, their contents can't change
+fn load_swappable(
+    storage: &dyn Storage,
+    env: &Env,
+    listing_id: &str,
+) -> Result<Listing, ContractError> {
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(storage, listing_id.to_string())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id.to_string() });
+    };
+    if listing.frozen {
+        return Err(ContractError::ListingFrozen {});
+    }
+    if live_commitment(storage, env, listing_id)?.is_some() {
+        return Err(ContractError::ListingCommitted {});
+    }
+    if listing.status != Status::FinalizedReady
+        || listing.claimant.is_some()
+        || listing.vesting.is_some()
+        || listing.units_available.is_some()
+        || SEALED_AUCTION.has(storage, listing_id)
+        || AUCTIONS.has(storage, listing_id)
+    {
+        return Err(ContractError::NotSwappable {});
+    }
+    Ok(listing)
+}
+
+// Accepts a proposed swap, each owner is sent the other listing's assets less the withdrawal fee
+// Both listings are archived as bought by the other owner & their bonds are returned
+pub fn execute_swap_listings(
+    deps: DepsMut,
+    env: &Env,
+    user_sender: &Addr,
+    proposer_listing_id: String,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let Some(proposer) = SWAP_PROPOSALS.may_load(deps.storage, (&proposer_listing_id, &listing_id))? else {
+        return Err(ContractError::NoSwapProposal {});
+    };
+    let proposed = load_swappable(deps.storage, env, &proposer_listing_id)?;
+    let listing = load_swappable(deps.storage, env, &listing_id)?;
+    if proposed.creator != proposer {
+        return Err(ContractError::NoSwapProposal {});
+    }
+    if user_sender != &listing.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    SWAP_PROPOSALS.remove(deps.storage, (&proposer_listing_id, &listing_id));
+
+    let mut res = Response::new()
+        .add_attribute("action", "swap_listings")
+        .add_attribute("proposer_listing_id", &proposer_listing_id)
+        .add_attribute("listing_id", &listing_id);
+    for (the_listing, receiver) in [(&proposed, &listing.creator), (&listing, &proposed.creator)] {
+        release_collections(deps.storage, &the_listing.for_sale.nfts)?;
+        archive_listing(
+            deps.storage,
+            &Listing {
+                claimant: Some(receiver.clone()),
+                status: Status::Closed,
+                ..the_listing.clone()
+            },
+        )?;
+        record_activity(deps.storage, receiver, &the_listing.id, ActivityRole::Buyer)?;
+        res = res.add_messages(bond_msg(deps.storage, &the_listing.id, None)?);
+        res = release_purchase(deps.storage, receiver, &the_listing.for_sale, res)?;
+    }
+    Ok(res)
+}
+
+pub fn execute_reject_swap(
+    deps: DepsMut,
+    user_sender: &Addr,
+    proposer_listing_id: String,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let Some(proposer) = SWAP_PROPOSALS.may_load(deps.storage, (&proposer_listing_id, &listing_id))? else {
+        return Err(ContractError::NoSwapProposal {});
+    };
+    let owner = listingz().idx.id.item(deps.storage, listing_id.clone())?.map(|(_pk, l)| l.creator);
+    if user_sender != &proposer && owner.as_ref() != Some(user_sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    SWAP_PROPOSALS.remove(deps.storage, (&proposer_listing_id, &listing_id));
+
+    Ok(Response::new()
+        .add_attribute("action", "reject_swap")
+        .add_attribute("proposer_listing_id", proposer_listing_id)
+        .add_attribute("listing_id", listing_id))
+}
+
+// Offers listings of one owner together for a single price, each can still be bought alone
+pub fn execute_create_bundle(
+    deps: DepsMut,
+    user_sender: &Addr,
+    listing_ids: Vec<String>,
+    price: GenericBalance,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let invalid = || ContractError::InvalidBundle {
+        max: MAX_BUNDLE_LISTINGS,
+    };
+    let mut unique_ids = listing_ids.clone();
+    unique_ids.sort();
+    unique_ids.dedup();
+    if unique_ids.len() != listing_ids.len()
+        || !(2..=MAX_BUNDLE_LISTINGS).contains(&listing_ids.len())
+    {
+        return Err(invalid());
+    }
+
+    // The bundle's proceeds are paid out in one bucket, so the listings must share a payee & swap
+    let mut payouts = Vec::with_capacity(listing_ids.len());
+    for listing_id in &listing_ids {
+        let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+            return Err(ContractError::ListingNotFound { id: listing_id.clone() });
+        };
+        if user_sender != &listing.creator {
+            return Err(ContractError::Unauthorized {});
+        }
+        if listing.units_available.is_some() {
+            return Err(invalid());
+        }
+        payouts.push((listing.payout_address, listing.auto_swap_to));
+    }
+    if payouts.windows(2).any(|pair| pair[0] != pair[1]) {
+        return Err(invalid());
+    }
+
+    let bundle_id = NEXT_BUNDLE_ID.may_load(deps.storage)?.unwrap_or(1);
+    NEXT_BUNDLE_ID.save(deps.storage, &(bundle_id + 1))?;
+    BUNDLES.save(
+        deps.storage,
+        bundle_id,
+        &Bundle {
+            creator: user_sender.clone(),
+            listing_ids,
+            price: normalize_ask_error_on_dup(price)?,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_bundle")
+        .add_attribute("bundle_id", bundle_id.to_string()))
+}
+
+// Buys every listing in a bundle for its price, each must pass the checks of a BuyListing of it
+// Fails if any listing was sold, removed or changed owner since the bundle was created
+pub fn execute_buy_bundle(
+    deps: DepsMut,
+    env: &Env,
+    buyer: &Addr,
+    bundle_id: u64,
+    bucket_id: &str,
+) -> Result<Response, ContractError> {
+    ensure_not_wound_down(deps.storage)?;
+    let Some(bundle) = BUNDLES.may_load(deps.storage, bundle_id)? else {
+        return Err(ContractError::NotFound {
+            typ: "Bundle".to_string(),
+            id: bundle_id.to_string(),
+        });
+    };
+    let the_bucket = get_bucket_if_sender_is_owner(&deps, buyer, bucket_id)?;
+    ensure_bucket_not_committed(deps.storage, env, buyer, bucket_id)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut listings = Vec::with_capacity(bundle.listing_ids.len());
+    for listing_id in &bundle.listing_ids {
+        let listing = listingz()
+            .idx
+            .id
+            .item(deps.storage, listing_id.clone())?
+            .map(|(_pk, listing)| listing)
+            .filter(|listing| listing.creator == bundle.creator);
+        let Some(listing) = listing else {
+            return Err(ContractError::BundleUnavailable {
+                id: listing_id.clone(),
+            });
+        };
+        if live_commitment(deps.storage, env, listing_id)?.is_some() {
+            return Err(ContractError::ListingCommitted {});
+        }
+        ensure_can_buy(deps.storage, env, &config, &listing, buyer)?;
+        listings.push(listing);
+    }
+
+    // Check that bucket contains the bundle's price, plus the taker fee
+    let (maker_fee, taker_fee) = maker_taker_fees(&bundle.price, &config, env.block.time)?;
+    if !bucket_satisfies_ask(&the_bucket.funds, &add_juno(&bundle.price, taker_fee)?) {
+        return Err(ContractError::FundsSentNotFundsAsked {
+            which: format!("Bucket ID: {bucket_id}"),
+        });
+    }
+
+    // Check that paying out every listing, the bucket & the fees stays under the transfer limit
+    let total_fee = checked_add(maker_fee, taker_fee)?;
+    let fee_msg = maker_taker_fee_msg(&config.fee_collector, total_fee);
+    let mut transfers = transfer_count(&the_bucket.funds) + usize::from(fee_msg.is_some());
+    for listing in &listings {
+        let fee =
+            calc_fee(&listing.for_sale, &config.rounding).map_err(|_foo| ContractError::FeeCalc)?;
+        transfers += transfer_count(&listing.for_sale) + usize::from(fee.is_some());
+    }
+    if transfers > config.max_transfers_per_buy as usize {
+        return Err(ContractError::TooManyTransfers {
+            count: transfers,
+            max: config.max_transfers_per_buy,
+        });
+    }
+
+    // Each listing is recorded as sold for an even share of the price, the shares are cut at
+    // cumulative boundaries so they add up to the price exactly
+    // Each listing's floor is checked against the same share of the proceeds
+    let proceeds = sub_juno(&the_bucket.funds, total_fee)?;
+    let count = listings.len() as u128;
+    let mut bond_msgs = Vec::new();
+    let mut callback_msgs = Vec::new();
+    for (i, listing) in (0_u128..).zip(&listings) {
+        let share_of =
+            |amount: Uint128| amount.multiply_ratio(i + 1, count) - amount.multiply_ratio(i, count);
+        let mut share = bundle.price.clone();
+        share.native.iter_mut().for_each(|c| c.amount = share_of(c.amount));
+        share.cw20.iter_mut().for_each(|c| c.amount = share_of(c.amount));
+        let mut net = proceeds.clone();
+        net.native.iter_mut().for_each(|c| c.amount = share_of(c.amount));
+        ensure_net_proceeds(listing, &net)?;
+        record_sale(deps.storage, env, listing, buyer, share)?;
+        bond_msgs.extend(bond_msg(deps.storage, &listing.id, None)?);
+        close_sold_listing(deps.storage, env, listing, buyer)?;
+        callback_msgs.extend(sale_callback_msg(listing));
+    }
+    BUNDLES.remove(deps.storage, bundle_id);
+    BUCKET_COMMITMENT.remove(deps.storage, (buyer, bucket_id));
+    bucket_offers().remove(deps.storage, (buyer, bucket_id))?;
+
+    // Delete Old Bucket -> Add to the payee's bucket of the same ID, minus the maker & taker fees
+    // The listings share a payee & swap, so the first one pays out the whole bundle
+    BUCKETS.remove(deps.storage, (buyer.clone(), bucket_id));
+    let swap_msg = pay_proceeds(deps.storage, env, &config, &listings[0], bucket_id, proceeds)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "buy_bundle")
+        .add_attribute("bundle_id", bundle_id.to_string())
+        .add_attribute("bucket_used", bucket_id)
+        .add_messages(fee_msg)
+        .add_messages(bond_msgs)
+        .add_submessages(swap_msg)
+        .add_submessages(callback_msgs))
+}
+
+// Loads a bought listing, only its claimant can withdraw it
+fn load_purchased(
+    storage: &dyn Storage,
+    withdrawer: &Addr,
+    listing_id: &str,
+) -> Result<(Addr, Listing), ContractError> {
+    // Get listing
+    let Some((_pk, the_listing)): Option<(_, Listing)> = listingz().idx.id.item(storage, listing_id.to_string())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id.to_string() });
+    };
+
+    // Check and pull out claimant
+    let listing_claimer = the_listing.claimant.clone().ok_or(ContractError::Unauthorized {})?;
+
+    // Check that withdrawer is the claimant
+    if withdrawer != &listing_claimer {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that status is Closed
+    if the_listing.status != Status::Closed {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    Ok((listing_claimer, the_listing))
+}
+
+// TODO: merge this in with buy_listing function above
+pub fn execute_withdraw_purchased(
+    deps: DepsMut,
+    env: &Env,
+    withdrawer: &Addr,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let (listing_claimer, the_listing) = load_purchased(deps.storage, withdrawer, &listing_id)?;
+
+    // Vesting listings only release what has unlocked so far
+    let (release, fully_withdrawn) = match &the_listing.vesting {
+        Some(vesting) => {
+            let (release, fully_vested) = vested_release(&the_listing.for_sale, vesting, env)?;
+            if release.native.is_empty() && release.cw20.is_empty() && release.nfts.is_empty() {
+                return Err(ContractError::NothingVested {});
+            }
+            if !fully_vested {
+                let mut withdrawn = vesting.withdrawn.clone();
+                withdrawn.merge(&release)?;
+                listingz().replace(
+                    deps.storage,
+                    (&listing_claimer, listing_id.clone()),
+                    Some(&Listing {
+                        vesting: Some(Vesting {
+                            withdrawn,
+                            ..vesting.clone()
+                        }),
+                        ..the_listing.clone()
+                    }),
+                    Some(&the_listing),
+                )?;
+            }
+            (release, fully_vested)
+        }
+        None => (the_listing.for_sale.clone(), true),
+    };
+
+    // Archive Listing
+    if fully_withdrawn {
+        archive_listing(deps.storage, &the_listing)?;
+    }
+
+    // default listing response
+    let res: Response = Response::new()
+        .add_attribute("action", "withdraw_purchased")
+        .add_attribute("listing_id", listing_id);
+
+    release_purchase(deps.storage, &listing_claimer, &release, res)
+}
+
+// Releases only the given assets of a bought listing, the rest stay in it for later
+pub fn execute_withdraw_purchased_partial(
+    deps: DepsMut,
+    withdrawer: &Addr,
+    listing_id: String,
+    assets: GenericBalance,
+) -> Result<Response, ContractError> {
+    let (listing_claimer, the_listing) = load_purchased(deps.storage, withdrawer, &listing_id)?;
+
+    // Vesting listings track what's withdrawn against what's unlocked instead
+    if the_listing.vesting.is_some() {
+        return Err(ContractError::PartialWithdrawVesting {});
+    }
+
+    // Merge duplicates so each coin is only checked & taken once
+    let mut release = GenericBalance {
+        native: Vec::new(),
+        cw20: Vec::new(),
+        nfts: Vec::new(),
+    };
+    release.merge(&assets)?;
+    release.native.retain(|coin| !coin.amount.is_zero());
+    release.cw20.retain(|coin| !coin.amount.is_zero());
+    if release.native.is_empty() && release.cw20.is_empty() && release.nfts.is_empty() {
+        return Err(ContractError::NoTokens {});
+    }
+    if !the_listing.for_sale.contains(&release) {
+        return Err(ContractError::NotInListing {});
+    }
+
+    // Archive Listing once nothing is left, otherwise keep what's left
+    let left = the_listing.for_sale.subtract(&release)?;
+    if left.native.is_empty() && left.cw20.is_empty() && left.nfts.is_empty() {
+        archive_listing(deps.storage, &the_listing)?;
+    } else {
+        listingz().replace(
+            deps.storage,
+            (&listing_claimer, listing_id.clone()),
+            Some(&Listing {
+                for_sale: left,
+                ..the_listing.clone()
+            }),
+            Some(&the_listing),
+        )?;
+    }
+
+    let res: Response = Response::new()
+        .add_attribute("action", "withdraw_purchased_partial")
+        .add_attribute("listing_id", listing_id);
+
+    release_purchase(deps.storage, &listing_claimer, &release, res)
+}
+
+// Pays a withdrawal of purchased assets to the claimant, less the config's fee
+fn release_purchase(
+    storage: &dyn Storage,
+    listing_claimer: &Addr,
+    release: &GenericBalance,
+    res: Response,
+) -> Result<Response, ContractError> {
+    let rounding = CONFIG.load(storage)?.rounding;
 
     if let Some((fee_msg, gbal)) =
-        calc_fee(&the_listing.for_sale).map_err(|_foo| ContractError::FeeCalc)?
+        calc_fee(release, &rounding).map_err(|_foo| ContractError::FeeCalc)?
     {
-        let user_msgs = send_tokens_cosmos(&listing_claimer, &gbal)?;
+        let user_msgs = payout_msgs(storage, listing_claimer, &gbal)?;
         Ok(res.add_message(fee_msg).add_messages(user_msgs))
     } else {
-        let user_msgs = send_tokens_cosmos(&listing_claimer, &the_listing.for_sale)?;
+        let user_msgs = payout_msgs(storage, listing_claimer, release)?;
         Ok(res.add_messages(user_msgs))
     }
 }
+
+// Part of a vesting listing unlocked by now & not withdrawn yet, true once fully vested
+// Fungible assets unlock linearly, NFTs only once fully vested
+fn vested_release(
+    for_sale: &GenericBalance,
+    vesting: &Vesting,
+    env: &Env,
+) -> Result<(GenericBalance, bool), ContractError> {
+    let duration = vesting.duration_seconds;
+    let start = vesting.start.unwrap_or(env.block.time).seconds();
+    let elapsed = env.block.time.seconds().saturating_sub(start).min(duration);
+    let fully_vested = elapsed == duration;
+
+    let mut vested = for_sale.clone();
+    vested.native.iter_mut().for_each(|c| c.amount = c.amount.multiply_ratio(elapsed, duration));
+    vested.cw20.iter_mut().for_each(|c| c.amount = c.amount.multiply_ratio(elapsed, duration));
+    if !fully_vested {
+        vested.nfts.clear();
+    }
+
+    Ok((vested.subtract(&vesting.withdrawn)?, fully_vested))
+}
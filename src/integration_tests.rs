@@ -2,7 +2,7 @@
 use anyhow::ensure;
 use core::fmt::Display;
 
-use cosmwasm_std::{coins, to_binary, Addr, Coin, Empty, Uint128}; //BlockInfo};
+use cosmwasm_std::{coin, coins, to_binary, Addr, Coin, Empty, Uint128}; //BlockInfo};
 use cw20::{Cw20Coin, Cw20CoinVerified, Cw20Contract};
 use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 
@@ -49,10 +49,119 @@ pub mod create_contract {
             crate::contract::execute,
             crate::contract::instantiate,
             crate::contract::query,
-        );
+        )
+        .with_reply(crate::contract::reply);
 
         Box::new(contract)
     }
+
+    #[cosmwasm_schema::cw_serde]
+    pub enum NftReceiverExecuteMsg {
+        ReceiveNft(cw721::Cw721ReceiveMsg),
+    }
+
+    // Stand in for a contract implementing the cw721 ReceiveNft hook, accepts any NFT
+    pub fn nft_receiver_contract() -> Box<dyn Contract<Empty>> {
+        use cosmwasm_std::{
+            to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+        };
+
+        fn execute(
+            _: DepsMut,
+            _: Env,
+            _: MessageInfo,
+            _: NftReceiverExecuteMsg,
+        ) -> StdResult<Response> {
+            Ok(Response::new().add_attribute("action", "receive_nft_hook"))
+        }
+        fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+            Ok(Response::new())
+        }
+        fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+            to_binary(&Empty {})
+        }
+
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    // Stand in for a DEX router, pays the recipient 1:1 in ask_denom out of its own balance
+    pub fn swap_router_contract() -> Box<dyn Contract<Empty>> {
+        use cosmwasm_std::{
+            coins, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+            StdResult, Uint128,
+        };
+
+        fn execute(
+            _: DepsMut,
+            _: Env,
+            info: MessageInfo,
+            msg: crate::msg::SwapRouterExecuteMsg,
+        ) -> StdResult<Response> {
+            let crate::msg::SwapRouterExecuteMsg::Swap {
+                ask_denom,
+                recipient,
+            } = msg;
+            let amount: Uint128 = info.funds.iter().map(|coin| coin.amount).sum();
+
+            Ok(Response::new().add_message(BankMsg::Send {
+                to_address: recipient,
+                amount: coins(amount.u128(), ask_denom),
+            }))
+        }
+        fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+            Ok(Response::new())
+        }
+        fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+            to_binary(&Empty {})
+        }
+
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    #[cosmwasm_schema::cw_serde]
+    pub enum SaleCallbackExecuteMsg {
+        ListingSold {
+            listing_id: String,
+        },
+        Fail {},
+    }
+
+    // Stand in for a seller's contract, keeps the IDs of the listings it's told were sold
+    pub fn sale_callback_contract() -> Box<dyn Contract<Empty>> {
+        use cosmwasm_std::{
+            to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+        };
+        use cw_storage_plus::Item;
+
+        const SOLD: Item<Vec<String>> = Item::new("sold");
+
+        fn execute(
+            deps: DepsMut,
+            _: Env,
+            _: MessageInfo,
+            msg: SaleCallbackExecuteMsg,
+        ) -> StdResult<Response> {
+            match msg {
+                SaleCallbackExecuteMsg::ListingSold {
+                    listing_id,
+                } => {
+                    let mut sold = SOLD.may_load(deps.storage)?.unwrap_or_default();
+                    sold.push(listing_id);
+                    SOLD.save(deps.storage, &sold)?;
+                    Ok(Response::new())
+                }
+                SaleCallbackExecuteMsg::Fail {} => Err(StdError::generic_err("callback failed")),
+            }
+        }
+        fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+            Ok(Response::new())
+        }
+        fn query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+            to_binary(&SOLD.may_load(deps.storage)?.unwrap_or_default())
+        }
+
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
 }
 
 pub mod create_users {
@@ -156,6 +265,30 @@ pub mod init_contracts {
         let jv_id = router.store_code(junovaults_contract());
         let msg = InstantiateMsg {
             admin: None,
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
         };
 
         let addr =
@@ -166,6 +299,7 @@ pub mod init_contracts {
         addr
     }
 
+    #[allow(clippy::type_complexity)]
     pub fn init_all_contracts(
         router: &mut App,
         contract_admin: &User,
@@ -379,9 +513,20 @@ pub mod create_valid_listing {
         };
 
         let cm = CreateListingMsg {
-            id: "valid_ask".to_string(),
             ask: valid_ask_price,
             whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
         };
 
         crate::msg::ExecuteMsg::CreateListing {
@@ -390,9 +535,8 @@ pub mod create_valid_listing {
     }
 
     #[must_use]
+    #[allow(clippy::too_many_arguments)]
     pub fn create_valid_ask(
-        listing_id: String,
-
         juno_amt: Option<u128>,
 
         jvone_addr: Option<Addr>,
@@ -410,7 +554,7 @@ pub mod create_valid_listing {
 
         whitelisted_buyer: Option<String>,
     ) -> ExecuteMsg {
-        let native_ask = juno_amt.map_or_else(|| Vec::new(), |a| vec![coin(a, VALID_NATIVE)]);
+        let native_ask = juno_amt.map_or_else(Vec::new, |a| vec![coin(a, VALID_NATIVE)]);
 
         let mut cw20_ask: Vec<Cw20CoinVerified> = Vec::new();
 
@@ -458,9 +602,20 @@ pub mod create_valid_listing {
         };
 
         let cm = CreateListingMsg {
-            id: listing_id,
             ask: valid_ask_price,
             whitelisted_buyer,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
         };
 
         crate::msg::ExecuteMsg::CreateListing {
@@ -470,7 +625,6 @@ pub mod create_valid_listing {
 
     #[must_use]
     pub fn create_listing_msg(
-        listing_id: String,
         jvone_addr: Addr,
         np_addr: Addr,
         whitelisted_buyer_addr: Option<Addr>,
@@ -496,11 +650,118 @@ pub mod create_valid_listing {
         let whitelisted_buyer = whitelisted_buyer_addr.map(|addr| addr.to_string());
 
         CreateListingMsg {
-            id: listing_id,
             ask: ask_price,
             whitelisted_buyer,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        }
+    }
+}
+
+pub mod native_actions {
+    use super::VALID_NATIVE;
+    use crate::msg::{CreateListingMsg, ExecuteMsg};
+    use crate::state::GenericBalance;
+    use cosmwasm_std::{coins, Addr, Coin};
+    use cw_multi_test::{App, AppResponse, Executor};
+
+    // Ask of only VALID_NATIVE
+    #[must_use]
+    pub fn native_ask(amount: u128) -> GenericBalance {
+        GenericBalance {
+            native: coins(amount, VALID_NATIVE),
+            cw20: Vec::new(),
+            nfts: Vec::new(),
         }
     }
+
+    pub fn create_listing(
+        router: &mut App,
+        junovaults: &Addr,
+        creator: &Addr,
+        for_sale: &[Coin],
+        ask: GenericBalance,
+    ) -> anyhow::Result<AppResponse> {
+        let msg = ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask,
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        };
+        router.execute_contract(creator.clone(), junovaults.clone(), &msg, for_sale)
+    }
+
+    pub fn finalize(
+        router: &mut App,
+        junovaults: &Addr,
+        creator: &Addr,
+        listing_id: &str,
+        seconds: u64,
+    ) -> anyhow::Result<AppResponse> {
+        let msg = ExecuteMsg::Finalize {
+            listing_id: listing_id.to_string(),
+            seconds: Some(seconds),
+            expiration_height: None,
+        };
+        router.execute_contract(creator.clone(), junovaults.clone(), &msg, &[])
+    }
+
+    pub fn create_bucket(
+        router: &mut App,
+        junovaults: &Addr,
+        owner: &Addr,
+        bucket_id: &str,
+        funds: &[Coin],
+    ) -> anyhow::Result<AppResponse> {
+        let msg = ExecuteMsg::CreateBucket {
+            bucket_id: bucket_id.to_string(),
+        };
+        router.execute_contract(owner.clone(), junovaults.clone(), &msg, funds)
+    }
+
+    pub fn buy(
+        router: &mut App,
+        junovaults: &Addr,
+        buyer: &Addr,
+        listing_id: &str,
+        bucket_id: &str,
+    ) -> anyhow::Result<AppResponse> {
+        let msg = ExecuteMsg::BuyListing {
+            listing_id: listing_id.to_string(),
+            bucket_id: bucket_id.to_string(),
+            max_price: None,
+            referrer: None,
+        };
+        router.execute_contract(buyer.clone(), junovaults.clone(), &msg, &[])
+    }
+
+    // Root cause of a failed execute, to compare against a ContractError
+    #[must_use]
+    pub fn err_string(res: anyhow::Result<AppResponse>) -> String {
+        res.map_or_else(|e| e.root_cause().to_string(), |_| "Ok".to_string())
+    }
 }
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -536,11 +797,10 @@ fn create_listing_should_fail() -> Result<(), anyhow::Error> {
 
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-    // Can't create with same ID
+    // Can't create without funds
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let ask_price_valid = create_valid_listing::create_valid_ask(
-        "john_1".to_string(),
         Some(10),
         Some(jvone.addr()),
         Some(Uint128::from(25u32)),
@@ -562,21 +822,20 @@ fn create_listing_should_fail() -> Result<(), anyhow::Error> {
         &one_juno,
     );
     ensure!(res.is_ok(), here("'Testing Ask Creation' failure", line!(), column!()));
-    // John can't create another listing with same ID
-    let res: Result<AppResponse> = router.execute_contract(
-        john.address.clone(),
-        junovaults.clone(),
-        &ask_price_valid,
-        &one_juno,
-    );
+    // John can't create a listing without sending anything for sale
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &ask_price_valid, &[]);
 
-    ensure!(res.is_err(), here("Cant create with same ID failure", line!(), column!()));
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NoTokens {}.to_string(),
+        here("Cant create without funds failure", line!(), column!())
+    );
 
-    // Sam can't create another listing with same ID
+    // Sam can create the same ask, it just gets the next listing ID
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), junovaults, &ask_price_valid, &one_juno);
 
-    ensure!(res.is_err(), here("Cant create with same ID failure", line!(), column!()));
+    ensure!(res.is_ok(), here("Same ask different ID failure", line!(), column!()));
 
     Ok(())
 }
@@ -615,7 +874,6 @@ fn create_listing_should_pass() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let ask_price_valid = create_valid_listing::create_valid_ask(
-        "john_1".to_string(),
         Some(10),
         Some(jvone.addr()),
         Some(Uint128::from(25u32)),
@@ -650,12 +908,7 @@ fn create_listing_should_pass() -> Result<(), anyhow::Error> {
     // Create with cw20
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-    let cm = create_valid_listing::create_listing_msg(
-        "john_2".to_string(),
-        jvone.addr(),
-        neonpeepz.addr(),
-        None,
-    );
+    let cm = create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None);
     let cmsg = to_binary(&crate::msg::ReceiveMsg::CreateListingCw20 {
         create_msg: cm,
     })?;
@@ -673,12 +926,7 @@ fn create_listing_should_pass() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Create with NFT
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-    let cm = create_valid_listing::create_listing_msg(
-        "john_3".to_string(),
-        jvone.addr(),
-        neonpeepz.addr(),
-        None,
-    );
+    let cm = create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None);
     let cmsg_nft = to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
         create_msg: cm,
     })?;
@@ -735,7 +983,6 @@ fn add_to_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let ask_price_valid = create_valid_listing::create_valid_ask(
-        "john_1".to_string(),
         Some(10),
         Some(jvone.addr()),
         Some(Uint128::from(25u32)),
@@ -772,7 +1019,7 @@ fn add_to_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let add_native_msg = crate::msg::ExecuteMsg::AddFundsToSaleNative {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     };
 
     // Sam cannot add
@@ -824,7 +1071,7 @@ fn add_to_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let add_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
 
     let add_cw20_msg = cw20_base::msg::ExecuteMsg::Send {
@@ -887,7 +1134,7 @@ fn add_to_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
 
     let john_add_nft_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
@@ -967,8 +1214,6 @@ fn remove_a_listing() -> Result<(), anyhow::Error> {
     // Listing one, created by John
     //~~~~~~~~~~
     let ask_price_valid = create_valid_listing::create_valid_ask(
-        // Listing ID
-        "john_1".to_string(),
         // ujunox in ask
         Some(10),
         // jvone in ask
@@ -1011,8 +1256,6 @@ fn remove_a_listing() -> Result<(), anyhow::Error> {
     // Listing two, created by Sam
     //~~~~~~~~~~
     let ask_price_valid = create_valid_listing::create_valid_ask(
-        // Listing ID
-        "sam_1".to_string(),
         // ujunox in ask
         Some(10),
         // jvone in ask
@@ -1057,10 +1300,10 @@ fn remove_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let sam_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "sam_1".to_string(),
+        listing_id: "2".to_string(),
     })?;
     let john_add_ten_msg = cw20_base::msg::ExecuteMsg::Send {
         contract: junovaults.to_string(),
@@ -1105,7 +1348,7 @@ fn remove_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let john_add_nft_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
         cw721_base::msg::ExecuteMsg::SendNft {
@@ -1132,7 +1375,7 @@ fn remove_a_listing() -> Result<(), anyhow::Error> {
     // let owner = shittykittyz.owner_of(&router.wrap(), "3".to_string(), false).unwrap().owner;
     // assert_eq!(owner, sam.address.clone().to_string());
     let sam_add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "sam_1".to_string(),
+        listing_id: "2".to_string(),
     })?;
     let sam_add_nft_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
         cw721_base::msg::ExecuteMsg::SendNft {
@@ -1160,7 +1403,7 @@ fn remove_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let remove_john_1 = crate::msg::ExecuteMsg::RemoveListing {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     };
 
     let res: Result<AppResponse> =
@@ -1270,8 +1513,6 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     // Listing one, created by John
     //~~~~~~~~~~
     let ask_price_valid = create_valid_listing::create_valid_ask(
-        // Listing ID
-        "john_1".to_string(),
         // ujunox in ask
         Some(10),
         // jvone in ask
@@ -1314,8 +1555,6 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     // Listing two, created by Sam
     //~~~~~~~~~~
     let ask_price_valid = create_valid_listing::create_valid_ask(
-        // Listing ID
-        "sam_1".to_string(),
         // ujunox in ask
         Some(10),
         // jvone in ask
@@ -1360,10 +1599,10 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let sam_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "sam_1".to_string(),
+        listing_id: "2".to_string(),
     })?;
     let john_add_ten_msg = cw20_base::msg::ExecuteMsg::Send {
         contract: junovaults.to_string(),
@@ -1408,7 +1647,7 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let john_add_nft_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
         cw721_base::msg::ExecuteMsg::SendNft {
@@ -1436,7 +1675,7 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let sam_add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "sam_1".to_string(),
+        listing_id: "2".to_string(),
     })?;
     let sam_add_nft_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
         cw721_base::msg::ExecuteMsg::SendNft {
@@ -1464,13 +1703,26 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let finalize_john_1 = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_1".to_string(),
-        seconds: 259_200,
+        listing_id: "1".to_string(),
+        seconds: Some(259_200),
+        expiration_height: None,
     };
 
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), junovaults.clone(), &finalize_john_1, &[]);
     ensure!(res.is_err(), here("sam fail finalize", line!(), column!()));
+    let err = res.unwrap_err().root_cause().to_string();
+    ensure!(
+        err == crate::ContractError::Unauthorized {}.to_string(),
+        here(format!("sam finalize error: {err}"), line!(), column!())
+    );
+    // John's listing is untouched
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.status == "Being Prepared", here("john_1 still prepared", line!(), column!()));
 
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -1480,16 +1732,18 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let too_early = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_1".to_string(),
-        seconds: 599,
+        listing_id: "1".to_string(),
+        seconds: Some(599),
+        expiration_height: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &too_early, &[]);
     ensure!(res.is_err(), here("Expiration too early", line!(), column!()));
 
     let too_late = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_1".to_string(),
-        seconds: 1_209_601,
+        listing_id: "1".to_string(),
+        seconds: Some(1_209_601),
+        expiration_height: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &too_late, &[]);
@@ -1501,8 +1755,9 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let just_right = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_1".to_string(),
-        seconds: 20000,
+        listing_id: "1".to_string(),
+        seconds: Some(20000),
+        expiration_height: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &just_right, &[]);
@@ -1513,8 +1768,9 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     // Can't finalize again
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let finalize_again = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_1".to_string(),
-        seconds: 20000,
+        listing_id: "1".to_string(),
+        seconds: Some(20000),
+        expiration_height: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &finalize_again, &[]);
@@ -1525,7 +1781,7 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     // Can't remove after finalize
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let cant_remove = crate::msg::ExecuteMsg::RemoveListing {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &cant_remove, &[]);
@@ -1536,7 +1792,7 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     // Can't refund after finalize if not expired
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let cant_refund = crate::msg::ExecuteMsg::RefundExpired {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &cant_refund, &[]);
@@ -1548,7 +1804,7 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // cant add native
     let cant_add = crate::msg::ExecuteMsg::AddFundsToSaleNative {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> = router.execute_contract(
         john.address.clone(),
@@ -1560,7 +1816,7 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
 
     // cant add cw20
     let john_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let john_add_ten_msg = cw20_base::msg::ExecuteMsg::Send {
         contract: junovaults.to_string(),
@@ -1573,7 +1829,7 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
 
     // cant add NFT
     let john_add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let john_add_nft_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
         cw721_base::msg::ExecuteMsg::SendNft {
@@ -1583,7 +1839,13 @@ fn finalize_a_listing() -> Result<(), anyhow::Error> {
         };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), neonpeepz.addr(), &john_add_nft_msg, &[]);
-    ensure!(res.is_err(), here("add NFT after finalize", line!(), column!()));
+    ensure!(
+        native_actions::err_string(res)
+            == crate::error::ContractError::ListingFinalized {}.to_string(),
+        here("add NFT after finalize", line!(), column!())
+    );
+    let owner = neonpeepz.owner_of(&router.wrap(), "2".to_string(), false)?.owner;
+    ensure!(owner == john.address, here("John keeps NFT", line!(), column!()));
 
     Ok(())
 }
@@ -1630,8 +1892,6 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     // Listing one, created by John
     //~~~~~~~~~~
     let ask_price_valid = create_valid_listing::create_valid_ask(
-        // Listing ID
-        "john_1".to_string(),
         // ujunox in ask
         Some(10),
         // jvone in ask
@@ -1676,7 +1936,7 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let john_add_ten_msg = cw20_base::msg::ExecuteMsg::Send {
         contract: junovaults.to_string(),
@@ -1703,7 +1963,7 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let john_add_nft_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
         cw721_base::msg::ExecuteMsg::SendNft {
@@ -1734,8 +1994,9 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let just_right = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_1".to_string(),
-        seconds: 20000,
+        listing_id: "1".to_string(),
+        seconds: Some(20000),
+        expiration_height: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &just_right, &[]);
@@ -1752,7 +2013,7 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
         current_blockinfo.time = current_blockinfo.time.plus_seconds(19999);
     });
     let fail_refund = crate::msg::ExecuteMsg::RefundExpired {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &fail_refund, &[]);
@@ -1767,8 +2028,9 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     // ~~~~~~~~~~~~
     // Cant refinalize an expired listing
     let fail_refinalize_expired = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_1".to_string(),
-        seconds: 15000,
+        listing_id: "1".to_string(),
+        seconds: Some(15000),
+        expiration_height: None,
     };
     let res: Result<AppResponse> = router.execute_contract(
         john.address.clone(),
@@ -1781,7 +2043,7 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     // ~~~~~~~~~~
     // Can't remove an expired listing <must call refund instead>
     let fail_remove = crate::msg::ExecuteMsg::RemoveListing {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &fail_remove, &[]);
@@ -1790,7 +2052,7 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     // ~~~~~~~~~
     // Can't add to expired listing
     let john_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     })?;
     let john_add_ten_msg = cw20_base::msg::ExecuteMsg::Send {
         contract: junovaults.to_string(),
@@ -1806,7 +2068,7 @@ fn expiration_checks() -> Result<(), anyhow::Error> {
     // Refund success
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let success_refund = crate::msg::ExecuteMsg::RefundExpired {
-        listing_id: "john_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &success_refund, &[]);
@@ -2119,9 +2381,20 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
         nfts: nft_ask,
     };
     let cl = CreateListingMsg {
-        id: "john_listing_1".to_string(),
         ask: ask_price,
         whitelisted_buyer: None,
+        whitelist_until: None,
+        payout_address: None,
+        min_buyer_purchases: None,
+        vesting_seconds: None,
+        on_expire: None,
+        private: None,
+        ask_options: None,
+        auto_swap_to: None,
+        units: None,
+        on_sale_callback: None,
+        min_net_proceeds: None,
+        max_offers: None,
     };
     let clm = crate::msg::ExecuteMsg::CreateListing {
         create_msg: cl,
@@ -2145,7 +2418,7 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
     })
     .unwrap();
     let john_c_msg = cw20_base::msg::ExecuteMsg::Send {
@@ -2164,7 +2437,7 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_nft_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
     })
     .unwrap();
     let john_nft_c_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
@@ -2184,8 +2457,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let finalize = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_listing_1".to_string(),
-        seconds: 10000,
+        listing_id: "1".to_string(),
+        seconds: Some(10000),
+        expiration_height: None,
     };
 
     let res: Result<AppResponse> =
@@ -2194,7 +2468,7 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     //~~~~~~~~~~~~~~~~~~~~
     //
-    // Listing ID: "john_listing_1"
+    // Listing ID: "1"
     //
     // PRICE: JVTWO 20, ShittyKittyz #3
     //
@@ -2243,8 +2517,10 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
         bucket_id: "wrong".to_string(),
+        max_price: None,
+        referrer: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), junovaults.clone(), &buy_msg, &[]);
@@ -2295,8 +2571,10 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
         bucket_id: "wrong".to_string(),
+        max_price: None,
+        referrer: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), junovaults.clone(), &buy_msg, &[]);
@@ -2347,8 +2625,10 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
         bucket_id: "wrong".to_string(),
+        max_price: None,
+        referrer: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), junovaults.clone(), &buy_msg, &[]);
@@ -2398,8 +2678,10 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
         bucket_id: "wrong".to_string(),
+        max_price: None,
+        referrer: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), junovaults.clone(), &buy_msg, &[]);
@@ -2435,8 +2717,10 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     // Try to buy listing not whitelisted for, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
         bucket_id: "not_whitelist_1".to_string(),
+        max_price: None,
+        referrer: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(max.address.clone(), junovaults.clone(), &buy_msg, &[]);
@@ -2481,8 +2765,10 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     // Try to buy listing, should succeed
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
         bucket_id: "correct".to_string(),
+        max_price: None,
+        referrer: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), junovaults.clone(), &buy_msg, &[]);
@@ -2520,22 +2806,11 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // John can't do anything to listing
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-    // Create listing with same name before it's removed should fail
-    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-    let res: Result<AppResponse> = router.execute_contract(
-        john.address.clone(),
-        junovaults.clone(),
-        &clm,
-        &coins(5_000_000, VALID_NATIVE),
-    );
-    ensure!(res.is_err(), here("duplicate name should fail", line!(), column!()));
-
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // John can't add
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let john_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
     })
     .unwrap();
     let john_c_msg = cw20_base::msg::ExecuteMsg::Send {
@@ -2552,8 +2827,9 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // John can't finalize
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let finalize = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_listing_1".to_string(),
-        seconds: 10000,
+        listing_id: "1".to_string(),
+        seconds: Some(10000),
+        expiration_height: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &finalize, &[]);
@@ -2563,7 +2839,7 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // John can't Remove
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let remove = crate::msg::ExecuteMsg::RemoveListing {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
@@ -2582,7 +2858,7 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
         nfts: Vec::new(),
     };
     let edit_price = crate::msg::ExecuteMsg::ChangeAsk {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
         new_ask: ask_price,
     };
     let res: Result<AppResponse> =
@@ -2599,7 +2875,7 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
     // John can't Refund
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     let refund = crate::msg::ExecuteMsg::RefundExpired {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &refund, &[]);
@@ -2607,7 +2883,7 @@ fn marketplace_sale() -> Result<(), anyhow::Error> {
 
     // Edge case check
     let remove_edge = crate::msg::ExecuteMsg::WithdrawPurchased {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
     };
     let res: Result<AppResponse> =
         router.execute_contract(john.address.clone(), junovaults.clone(), &remove_edge, &[]);
@@ -2739,9 +3015,20 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
         nfts: nft_ask,
     };
     let cl = CreateListingMsg {
-        id: "john_listing_1".to_string(),
         ask: ask_price,
         whitelisted_buyer: None,
+        whitelist_until: None,
+        payout_address: None,
+        min_buyer_purchases: None,
+        vesting_seconds: None,
+        on_expire: None,
+        private: None,
+        ask_options: None,
+        auto_swap_to: None,
+        units: None,
+        on_sale_callback: None,
+        min_net_proceeds: None,
+        max_offers: None,
     };
     let clm = crate::msg::ExecuteMsg::CreateListing {
         create_msg: cl,
@@ -2765,7 +3052,7 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_msg = to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
     })
     .unwrap();
     let john_c_msg = cw20_base::msg::ExecuteMsg::Send {
@@ -2784,7 +3071,7 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let john_nft_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
     })
     .unwrap();
     let john_nft_c_msg: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
@@ -2804,8 +3091,9 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     let finalize = crate::msg::ExecuteMsg::Finalize {
-        listing_id: "john_listing_1".to_string(),
-        seconds: 10000,
+        listing_id: "1".to_string(),
+        seconds: Some(10000),
+        expiration_height: None,
     };
 
     let res: Result<AppResponse> =
@@ -2813,7 +3101,7 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
     ensure!(res.is_ok(), here("John finalize", line!(), column!()));
 
     //~~~~~~~~~~~~~~~~~~~~
-    // Listing ID: "john_listing_1"
+    // Listing ID: "1"
     // PRICE: JVTWO 20, ShittyKittyz #3
     // FOR_SALE: JUNO 5, JVONE 10, NeonPeepz #1
     //~~~~~~~~~~~~~~~~~~~~
@@ -2866,8 +3154,10 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
 
     // Try to buy listing, should fail
     let buy_msg = crate::msg::ExecuteMsg::BuyListing {
-        listing_id: "john_listing_1".to_string(),
+        listing_id: "1".to_string(),
         bucket_id: "correct".to_string(),
+        max_price: None,
+        referrer: None,
     };
     let res: Result<AppResponse> =
         router.execute_contract(sam.address.clone(), junovaults, &buy_msg, &[]);
@@ -2875,3 +3165,11543 @@ fn cant_buy_expired() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing Interest
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Expressing interest twice from the same address only counts once
+// <X> Can't express interest in a listing that doesn't exist
+#[test]
+fn express_interest() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    // John creates a listing
+    let cm = create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None);
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &crate::msg::ExecuteMsg::CreateListing {
+            create_msg: cm,
+        },
+        &coins(1, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+    let interest_msg = crate::msg::ExecuteMsg::ExpressInterest {
+        listing_id: "1".to_string(),
+    };
+
+    // Sam expresses interest twice, Max once
+    for user in [&sam, &sam, &max] {
+        let res: Result<AppResponse> =
+            router.execute_contract(user.address.clone(), junovaults.clone(), &interest_msg, &[]);
+        ensure!(res.is_ok(), here(format!("{} express interest", user.name), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.interest == 2, here(format!("Interest: {}", res.interest), line!(), column!()));
+
+    // Can't express interest in a listing that doesn't exist
+    let missing = crate::msg::ExecuteMsg::ExpressInterest {
+        listing_id: "not_a_listing".to_string(),
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), junovaults, &missing, &[]);
+    ensure!(res.is_err(), here("Interest in missing listing", line!(), column!()));
+
+    Ok(())
+}
+
+// <X> Bucket targeting an existing listing is not orphaned
+// <X> Bucket becomes orphaned once its target listing is removed
+// <X> Buckets without a target are never orphaned
+#[test]
+fn orphaned_buckets() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John creates a listing
+    let cm = create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None);
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &crate::msg::ExecuteMsg::CreateListing {
+            create_msg: cm,
+        },
+        &coins(1, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+    // Sam creates 2 buckets, only one targets John's listing
+    for bucket_id in ["targeted", "untargeted"] {
+        let res: Result<AppResponse> = router.execute_contract(
+            sam.address.clone(),
+            junovaults.clone(),
+            &crate::msg::ExecuteMsg::CreateBucket {
+                bucket_id: bucket_id.to_string(),
+            },
+            &coins(1, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+    }
+
+    // Can't target a listing that doesn't exist
+    let bad_target = crate::msg::ExecuteMsg::SetBucketTarget {
+        bucket_id: "targeted".to_string(),
+        target_listing: Some("not_a_listing".to_string()),
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &bad_target, &[]);
+    ensure!(res.is_err(), here("Target missing listing", line!(), column!()));
+
+    let set_target = crate::msg::ExecuteMsg::SetBucketTarget {
+        bucket_id: "targeted".to_string(),
+        target_listing: Some("1".to_string()),
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &set_target, &[]);
+    ensure!(res.is_ok(), here("Sam set bucket target", line!(), column!()));
+
+    // Nothing orphaned while the listing exists
+    let q = crate::msg::QueryMsg::GetOrphanedBuckets {
+        owner: sam.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.buckets.is_empty(), here("No orphaned buckets", line!(), column!()));
+
+    // John removes his listing
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &crate::msg::ExecuteMsg::RemoveListing {
+            listing_id: "1".to_string(),
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here("John remove listing", line!(), column!()));
+
+    // Targeted bucket is now orphaned
+    let res: crate::query::GetBucketsResponse = router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(res.buckets.len() == 1, here("One orphaned bucket", line!(), column!()));
+    ensure!(res.buckets[0].0 == "targeted", here("Orphaned bucket id", line!(), column!()));
+
+    Ok(())
+}
+
+// <X> Ask priced in an IBC denom can be bought with a bucket holding the same IBC denom
+// <X> Native asset ordering in the ask doesn't need to match the bucket
+#[test]
+fn ibc_denom_ask() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    let ibc_denom = "ibc/C4CFF46FD6DE35CA4CF4CE031E643C8FDC9BA4B99AE598E9B0ED98FE3A2319F9";
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John VALID_NATIVE, Sam VALID_NATIVE + the IBC denom
+    let router = give_natives(&john, &mut router);
+    router.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &sam.address,
+                vec![coin(100_000_000, ibc_denom), coin(100_000_000, VALID_NATIVE)],
+            )
+            .unwrap()
+    });
+
+    // PRICE: 10 VALID_NATIVE, 1_000 IBC <not sorted by denom>
+    // FOR_SALE: 5 VALID_NATIVE
+    let cl = CreateListingMsg {
+        ask: GenericBalance {
+            native: vec![coin(10, VALID_NATIVE), coin(1_000, ibc_denom)],
+            cw20: Vec::new(),
+            nfts: Vec::new(),
+        },
+        whitelisted_buyer: None,
+        whitelist_until: None,
+        payout_address: None,
+        min_buyer_purchases: None,
+        vesting_seconds: None,
+        on_expire: None,
+        private: None,
+        ask_options: None,
+        auto_swap_to: None,
+        units: None,
+        on_sale_callback: None,
+        min_net_proceeds: None,
+        max_offers: None,
+    };
+    let res: Result<AppResponse> = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &crate::msg::ExecuteMsg::CreateListing {
+            create_msg: cl,
+        },
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+    let finalize = crate::msg::ExecuteMsg::Finalize {
+        listing_id: "1".to_string(),
+        seconds: Some(10000),
+        expiration_height: None,
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &finalize, &[]);
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+
+    // Sam's bucket holds both denoms, sorted by denom as the bank module sends them
+    let res: Result<AppResponse> = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &crate::msg::ExecuteMsg::CreateBucket {
+            bucket_id: "sam_ibc".to_string(),
+        },
+        &[coin(1_000, ibc_denom), coin(10, VALID_NATIVE)],
+    );
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    let buy_msg = crate::msg::ExecuteMsg::BuyListing {
+        listing_id: "1".to_string(),
+        bucket_id: "sam_ibc".to_string(),
+        max_price: None,
+        referrer: None,
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &buy_msg, &[]);
+    ensure!(res.is_ok(), here(format!("Sam buy: {res:?}"), line!(), column!()));
+
+    // John withdraws the IBC proceeds
+    let rem = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "sam_ibc".to_string(),
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(john.address.clone(), junovaults, &rem, &[]);
+    ensure!(res.is_ok(), here("John remove bucket", line!(), column!()));
+
+    let john_ibc: Coin = router.wrap().query_balance(john.address.to_string(), ibc_denom)?;
+    ensure!(
+        john_ibc.amount == Uint128::from(1_000u32),
+        here(format!("John IBC balance: {}", john_ibc.amount), line!(), column!())
+    );
+
+    Ok(())
+}
+
+// <X> Only the admin can freeze a listing
+// <X> Frozen listing can't be bought or removed
+// <X> Unfrozen listing can be bought
+// <X> Purchased listing can still be withdrawn while frozen
+#[test]
+fn freeze_listing() -> Result<(), anyhow::Error> {
+    use crate::ContractError;
+    use native_actions::{buy, create_bucket, create_listing, err_string, finalize, native_ask};
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 5 VALID_NATIVE for 10 VALID_NATIVE, Sam has a matching bucket
+    let res =
+        create_listing(router, &junovaults, &john.address, &coins(5, VALID_NATIVE), native_ask(10));
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+    let res = finalize(router, &junovaults, &john.address, "1", 10000);
+    ensure!(res.is_ok(), here("John finalize", line!(), column!()));
+    let res = create_bucket(router, &junovaults, &sam.address, "sam_1", &coins(10, VALID_NATIVE));
+    ensure!(res.is_ok(), here("Sam create bucket", line!(), column!()));
+
+    let freeze = |frozen: bool| crate::msg::ExecuteMsg::FreezeListing {
+        listing_id: "1".to_string(),
+        frozen,
+    };
+
+    // John can't freeze his own listing
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &freeze(true), &[]);
+    ensure!(
+        err_string(res) == ContractError::Unauthorized {}.to_string(),
+        here("John freeze", line!(), column!())
+    );
+
+    // Admin freezes
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &freeze(true),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin freeze", line!(), column!()));
+
+    // Can't buy or refund while frozen
+    let res = buy(router, &junovaults, &sam.address, "1", "sam_1");
+    ensure!(
+        err_string(res) == ContractError::ListingFrozen {}.to_string(),
+        here("Buy frozen", line!(), column!())
+    );
+    let refund = crate::msg::ExecuteMsg::RefundExpired {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &refund, &[]);
+    ensure!(
+        err_string(res) == ContractError::ListingFrozen {}.to_string(),
+        here("Refund frozen", line!(), column!())
+    );
+
+    // Admin unfreezes, buy goes through
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &freeze(false),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin unfreeze", line!(), column!()));
+    let res = buy(router, &junovaults, &sam.address, "1", "sam_1");
+    ensure!(res.is_ok(), here("Buy unfrozen", line!(), column!()));
+
+    // Freezing after the sale doesn't block Sam withdrawing the purchase
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &freeze(true),
+        &[],
+    );
+    ensure!(res.is_ok(), here("Admin freeze sold", line!(), column!()));
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults, &withdraw, &[]);
+    ensure!(res.is_ok(), here("Sam withdraw purchased", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Allowance Listings
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Listing isn't created when there's no allowance
+// <X> Approved tokens are pulled and the listing is created in reply
+// <X> Balance checks after the pull
+#[test]
+fn create_listing_via_allowance() -> Result<(), anyhow::Error> {
+    use cw20::Cw20ExecuteMsg;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let create_msg = crate::msg::ExecuteMsg::CreateListingCw20ViaAllowance {
+        create_msg: crate::msg::CreateListingMsg {
+            ask: native_actions::native_ask(10),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+        token: jvone.addr().to_string(),
+        amount: Uint128::from(10u32),
+    };
+
+    // No allowance yet, TransferFrom fails and nothing is saved
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &create_msg, &[]);
+    ensure!(res.is_err(), here("Create without allowance", line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: Result<crate::query::ListingInfoResponse, _> =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q);
+    ensure!(res.is_err(), here("Listing saved without allowance", line!(), column!()));
+
+    // Sam approves the contract, then creates the listing
+    let approve = Cw20ExecuteMsg::IncreaseAllowance {
+        spender: junovaults.to_string(),
+        amount: Uint128::from(10u32),
+        expires: None,
+    };
+    let res = router.execute_contract(sam.address.clone(), jvone.addr(), &approve, &[]);
+    ensure!(res.is_ok(), here("Sam approve", line!(), column!()));
+
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &create_msg, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.creator == sam.address, here("Listing creator", line!(), column!()));
+    ensure!(
+        res.for_sale == vec![(jvone.addr().to_string(), 10)],
+        here("Listing for_sale", line!(), column!())
+    );
+
+    assert_eq!(jvone.balance(&router.wrap(), sam.address.clone()), Ok(Uint128::from(90u32)));
+    assert_eq!(jvone.balance(&router.wrap(), junovaults), Ok(Uint128::from(10u32)));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// NFT Discovery
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Listing appears when its ask includes the exact NFT
+// <X> Listing doesn't appear for a different token_id or collection
+// <X> Listing no longer appears once the NFT is removed from the ask
+#[test]
+fn listings_accepting_nft() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    // John creates a listing asking for NeonPeepz #5
+    let cm = create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None);
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &crate::msg::ExecuteMsg::CreateListing {
+            create_msg: cm,
+        },
+        &coins(1, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here("John create listing", line!(), column!()));
+
+    let accepting = |router: &App, contract: &Addr, token_id: &str| -> Vec<String> {
+        let q = crate::msg::QueryMsg::GetListingsAcceptingNft {
+            contract: contract.to_string(),
+            token_id: token_id.to_string(),
+        };
+        let res: crate::query::MultiListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q).unwrap();
+        res.listings.into_iter().map(|listing| listing.id).collect()
+    };
+
+    ensure!(
+        accepting(router, &neonpeepz.addr(), "5") == vec!["1".to_string()],
+        here("Listing accepts NeonPeepz #5", line!(), column!())
+    );
+    ensure!(
+        accepting(router, &neonpeepz.addr(), "6").is_empty(),
+        here("Listing doesn't accept NeonPeepz #6", line!(), column!())
+    );
+    ensure!(
+        accepting(router, &shittykittyz.addr(), "5").is_empty(),
+        here("Listing doesn't accept ShittyKittyz #5", line!(), column!())
+    );
+
+    // John changes the ask to natives only
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &crate::msg::ExecuteMsg::ChangeAsk {
+            listing_id: "1".to_string(),
+            new_ask: native_actions::native_ask(10),
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    ensure!(
+        accepting(router, &neonpeepz.addr(), "5").is_empty(),
+        here("NFT removed from ask", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Bundles
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Bundle contents query returns every NFT in the listing
+// <X> Buying a multi-NFT bundle transfers every NFT together
+#[test]
+fn buy_nft_bundle() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+
+    // John creates a listing with NeonPeepz #1, then adds NeonPeepz #2 & ShittyKittyz #1
+    let create_msg = to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(10),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    })?;
+    let add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
+        listing_id: "1".to_string(),
+    })?;
+
+    let sends = [
+        (neonpeepz.addr(), "1", create_msg),
+        (neonpeepz.addr(), "2", add_msg.clone()),
+        (shittykittyz.addr(), "1", add_msg),
+    ];
+    for (collection, token_id, msg) in sends {
+        let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+            cw721_base::msg::ExecuteMsg::SendNft {
+                contract: junovaults.to_string(),
+                token_id: token_id.to_string(),
+                msg,
+            };
+        let res = router.execute_contract(john.address.clone(), collection, &send_nft, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetBundleContents {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::BundleContentsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.for_sale.nfts.len() == 3, here("Bundle has 3 NFTs", line!(), column!()));
+
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam buys the bundle
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "sam_bucket");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &crate::msg::ExecuteMsg::WithdrawPurchased {
+            listing_id: "1".to_string(),
+        },
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Every NFT in the bundle went to Sam
+    for (collection, token_id) in [(&neonpeepz, "1"), (&neonpeepz, "2"), (&shittykittyz, "1")] {
+        let owner = collection.owner_of(&router.wrap(), token_id.to_string(), false)?.owner;
+        ensure!(owner == sam.address, here(format!("Owner of #{token_id}"), line!(), column!()));
+    }
+
+    // Bundle is gone once withdrawn
+    let res: Result<crate::query::BundleContentsResponse, _> =
+        router.wrap().query_wasm_smart(junovaults, &q);
+    ensure!(res.is_err(), here("Bundle removed", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Refund Grace Period
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Only the admin can update the config
+// <X> Refund within the grace period is rejected
+// <X> Listing can still be bought within the grace period
+// <X> Refund after the grace period succeeds
+#[test]
+fn refund_grace_period() -> Result<(), anyhow::Error> {
+    use crate::error::ContractError;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // Only the admin can set a 100 second grace period
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: Some(100),
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(
+        native_actions::err_string(res) == ContractError::Unauthorized {}.to_string(),
+        here("Non-admin update config", line!(), column!())
+    );
+    let res = router.execute_contract(contract_admin.address, junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John creates & finalizes 2 listings of 10 VALID_NATIVE, expiring in 1000 seconds
+    for listing_id in ["1", "2"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Expired, but within the grace period
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 100;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1050);
+    });
+
+    let refund = crate::msg::ExecuteMsg::RefundExpired {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &refund, &[]);
+    ensure!(
+        native_actions::err_string(res).starts_with("Listing not expired"),
+        here("Refund within grace period", line!(), column!())
+    );
+
+    // Sam can still buy within the grace period
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "2", "sam_bucket");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Grace period over
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 10;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(60);
+    });
+
+    let res = router.execute_contract(john.address.clone(), junovaults, &refund, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let john_balance = router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+    ensure!(
+        john_balance.amount == Uint128::from(99_999_990u32),
+        here(format!("John balance: {}", john_balance.amount), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Whitelists
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Only the owner can set the whitelist
+// <X> Setting the whitelist replaces the whole set
+// <X> Whitelist can't go over MAX_WHITELISTED_BUYERS
+// <X> Removed address can't buy, whitelisted address can
+#[test]
+fn set_whitelisted_buyers() -> Result<(), anyhow::Error> {
+    use crate::error::ContractError;
+    use crate::execute::MAX_WHITELISTED_BUYERS;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let set_whitelist = |addresses: Vec<&Addr>| crate::msg::ExecuteMsg::SetWhitelistedBuyers {
+        listing_id: "1".to_string(),
+        addresses: addresses.iter().map(ToString::to_string).collect(),
+    };
+    let whitelisted_ids = |router: &App, user: &User| -> Vec<String> {
+        let q = crate::msg::QueryMsg::GetWhitelistedListings {
+            address: user.address.to_string(),
+        };
+        let res: crate::query::MultiListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q).unwrap();
+        res.listings.into_iter().map(|listing| listing.id).collect()
+    };
+
+    // Only John can set the whitelist
+    let msg = set_whitelist(vec![&sam.address]);
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &msg, &[]);
+    ensure!(
+        native_actions::err_string(res) == ContractError::Unauthorized {}.to_string(),
+        here("Sam set whitelist", line!(), column!())
+    );
+
+    // John whitelists Sam & Max
+    let msg = set_whitelist(vec![&sam.address, &max.address]);
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &msg, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(whitelisted_ids(router, &sam) == vec!["1"], here("Sam", line!(), column!()));
+    ensure!(whitelisted_ids(router, &max) == vec!["1"], here("Max", line!(), column!()));
+
+    // Replacing with only Max drops Sam
+    let msg = set_whitelist(vec![&max.address]);
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &msg, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(whitelisted_ids(router, &sam).is_empty(), here("Sam removed", line!(), column!()));
+    ensure!(whitelisted_ids(router, &max) == vec!["1"], here("Max kept", line!(), column!()));
+
+    // Too many addresses
+    let too_many: Vec<Addr> =
+        (0..=MAX_WHITELISTED_BUYERS).map(|i| Addr::unchecked(format!("buyer{i}"))).collect();
+    let msg = set_whitelist(too_many.iter().collect());
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &msg, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == ContractError::WhitelistTooLarge {
+                max: MAX_WHITELISTED_BUYERS
+            }
+            .to_string(),
+        here("Whitelist over max", line!(), column!())
+    );
+
+    // Sam can't buy, Max can
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    for user in [&sam, &max] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &user.address,
+            "bucket",
+            &coins(5, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "bucket");
+    ensure!(
+        native_actions::err_string(res) == ContractError::NotWhitelisted {}.to_string(),
+        here("Sam buy", line!(), column!())
+    );
+    let res = native_actions::buy(router, &junovaults, &max.address, "1", "bucket");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Sale History
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> No last sale price before any sale
+// <X> Last sale price updates after each sale of the collection
+// <X> Other denoms & collections are unaffected
+#[test]
+fn last_sale_price() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+    let router = give_natives(&max, router);
+
+    let last_sale = |router: &App, contract: &Addr, denom: &str| -> Option<Uint128> {
+        let q = crate::msg::QueryMsg::GetLastSalePrice {
+            contract: contract.to_string(),
+            denom: denom.to_string(),
+        };
+        let res: crate::query::LastSalePriceResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q).unwrap();
+        res.price
+    };
+
+    ensure!(
+        last_sale(router, &neonpeepz.addr(), VALID_NATIVE).is_none(),
+        here("No sale yet", line!(), column!())
+    );
+
+    // John sells NeonPeepz #1 to Sam for 10, then NeonPeepz #2 to Max for 20
+    for (token_id, buyer, price) in [("1", &sam, 10), ("2", &max, 20)] {
+        // Listing IDs are assigned in order, so they match the token IDs here
+        let listing_id = token_id.to_string();
+        let create_msg = to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(price),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        })?;
+        let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+            cw721_base::msg::ExecuteMsg::SendNft {
+                contract: junovaults.to_string(),
+                token_id: token_id.to_string(),
+                msg: create_msg,
+            };
+        let res = router.execute_contract(john.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+        let res = native_actions::finalize(router, &junovaults, &john.address, &listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &buyer.address,
+            "bucket",
+            &coins(price, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+        let res = native_actions::buy(router, &junovaults, &buyer.address, &listing_id, "bucket");
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+        let last = last_sale(router, &neonpeepz.addr(), VALID_NATIVE);
+        ensure!(
+            last == Some(Uint128::from(price)),
+            here(format!("Last sale after {listing_id}: {last:?}"), line!(), column!())
+        );
+    }
+
+    ensure!(
+        last_sale(router, &neonpeepz.addr(), "uatom").is_none(),
+        here("Other denom", line!(), column!())
+    );
+    ensure!(
+        last_sale(router, &shittykittyz.addr(), VALID_NATIVE).is_none(),
+        here("Other collection", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Withdraw All Buckets
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Limited withdraw returns a cursor when buckets remain
+// <X> Continuing from the cursor refunds the rest, with no cursor
+// <X> Balance checks after all buckets are refunded
+#[test]
+fn withdraw_all_buckets() -> Result<(), anyhow::Error> {
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+    let starting_balance = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?;
+
+    // Sam creates 3 buckets
+    for bucket_id in ["bucket_1", "bucket_2", "bucket_3"] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            bucket_id,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let cursor = |res: &AppResponse| -> Option<String> {
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == "next_start_after")
+            .map(|attr| attr.value.clone())
+    };
+
+    // First page of 2
+    let msg = crate::msg::ExecuteMsg::WithdrawAllBuckets {
+        start_after: None,
+        limit: Some(2),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &msg, &[])?;
+    let next = cursor(&res);
+    ensure!(next == Some("bucket_2".to_string()), here(format!("{next:?}"), line!(), column!()));
+
+    // Rest from the cursor
+    let msg = crate::msg::ExecuteMsg::WithdrawAllBuckets {
+        start_after: next,
+        limit: Some(2),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &msg, &[])?;
+    ensure!(cursor(&res).is_none(), here("No more buckets", line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: sam.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse = router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(res.buckets.is_empty(), here("Buckets removed", line!(), column!()));
+
+    let balance = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?;
+    ensure!(balance == starting_balance, here("Sam refunded", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Payout Address
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Invalid payout address is rejected at creation
+// <X> Payout address can't manage the listing, the owner still can
+// <X> Sale proceeds bucket goes to the payout address instead of the owner
+// <X> Balance checks after the payout address withdraws
+#[test]
+fn payout_address() -> Result<(), anyhow::Error> {
+    use crate::error::ContractError;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let create = |payout_address: &str| crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(5),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: Some(payout_address.to_string()),
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    };
+
+    // Invalid payout address
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create("Not An Address"),
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("Invalid payout address", line!(), column!()));
+
+    // John lists with Max as the payout address
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create(max.address.as_str()),
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Max can't manage the listing
+    let res = native_actions::finalize(router, &junovaults, &max.address, "1", 1000);
+    ensure!(
+        native_actions::err_string(res) == ContractError::Unauthorized {}.to_string(),
+        here("Max finalize", line!(), column!())
+    );
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam buys
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "sam_bucket");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Proceeds bucket belongs to Max, not John
+    let remove = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "sam_bucket".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_err(), here("John remove proceeds", line!(), column!()));
+
+    let res = router.execute_contract(max.address.clone(), junovaults, &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let max_balance = router.wrap().query_balance(max.address.to_string(), VALID_NATIVE)?;
+    ensure!(
+        max_balance.amount == Uint128::from(5u32),
+        here(format!("Max balance: {}", max_balance.amount), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listings By Status
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Each status filter only returns listings in that status
+// <X> Pagination with start_after & limit
+#[test]
+fn listings_by_status() -> Result<(), anyhow::Error> {
+    use crate::state::Status;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // Listing 1 being prepared, 2 & 3 finalized, 4 sold
+    for _ in 0..4 {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    for listing_id in ["2", "3", "4"] {
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "4", "sam_bucket");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let by_status =
+        |router: &App, status: Status, start_after: Option<&str>, limit: Option<u32>| {
+            let q = crate::msg::QueryMsg::GetListingsByStatus {
+                status,
+                start_after: start_after.map(ToString::to_string),
+                limit,
+            };
+            let res: crate::query::MultiListingResponse =
+                router.wrap().query_wasm_smart(junovaults.clone(), &q).unwrap();
+            res.listings.into_iter().map(|listing| listing.id).collect::<Vec<String>>()
+        };
+
+    let prepared = by_status(router, Status::BeingPrepared, None, None);
+    ensure!(prepared == vec!["1"], here(format!("{prepared:?}"), line!(), column!()));
+
+    let finalized = by_status(router, Status::FinalizedReady, None, None);
+    ensure!(finalized == vec!["2", "3"], here(format!("{finalized:?}"), line!(), column!()));
+
+    let closed = by_status(router, Status::Closed, None, None);
+    ensure!(closed == vec!["4"], here(format!("{closed:?}"), line!(), column!()));
+
+    // Paginate finalized one at a time
+    let page = by_status(router, Status::FinalizedReady, None, Some(1));
+    ensure!(page == vec!["2"], here(format!("{page:?}"), line!(), column!()));
+    let page = by_status(router, Status::FinalizedReady, Some("2"), Some(1));
+    ensure!(page == vec!["3"], here(format!("{page:?}"), line!(), column!()));
+    let page = by_status(router, Status::FinalizedReady, Some("3"), Some(1));
+    ensure!(page.is_empty(), here(format!("{page:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing IDs
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Next listing ID starts at 1
+// <X> IDs increase across creators
+// <X> Removed listing IDs are not reused
+#[test]
+fn listing_ids_are_monotonic() -> Result<(), anyhow::Error> {
+    use anyhow::Result;
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let next_id = |router: &App| {
+        let res: crate::query::NextListingIdResponse = router
+            .wrap()
+            .query_wasm_smart(junovaults.clone(), &crate::msg::QueryMsg::GetNextListingId {})
+            .unwrap();
+        res.next_listing_id
+    };
+
+    ensure!(next_id(router) == 1, here("Next ID before any listing", line!(), column!()));
+
+    // John gets 1, Sam gets 2
+    for creator in [&john.address, &sam.address] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            creator,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let sams: crate::query::ListingInfoResponse = router.wrap().query_wasm_smart(
+        junovaults.clone(),
+        &crate::msg::QueryMsg::GetListingInfo {
+            listing_id: "2".to_string(),
+        },
+    )?;
+    ensure!(sams.creator == sam.address, here("Sam owns listing 2", line!(), column!()));
+    ensure!(next_id(router) == 3, here("Next ID after two listings", line!(), column!()));
+
+    // Removing listing 2 doesn't free its ID
+    let rem = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "2".to_string(),
+    };
+    let res: Result<AppResponse> =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &rem, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &sam.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "2".to_string(),
+    };
+    let res: cosmwasm_std::StdResult<crate::query::ListingInfoResponse> =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q);
+    ensure!(res.is_err(), here("Listing 2 stays removed", line!(), column!()));
+    ensure!(next_id(router) == 4, here("Next ID after removal", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Max Price
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Ask raised past max_price after Sam queried it is rejected
+// <X> Ask within max_price can be bought
+#[test]
+fn buy_with_max_price() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam sees an ask of 5, then John raises it to 8
+    let change = crate::msg::ExecuteMsg::ChangeAsk {
+        listing_id: "1".to_string(),
+        new_ask: native_actions::native_ask(8),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &change, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket",
+        &coins(8, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let buy = |max_price: u128| crate::msg::ExecuteMsg::BuyListing {
+        listing_id: "1".to_string(),
+        bucket_id: "sam_bucket".to_string(),
+        max_price: Some(native_actions::native_ask(max_price)),
+        referrer: None,
+    };
+
+    // Sam only agreed to pay 5
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &buy(5), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::PriceSlippage {}.to_string(),
+        here("Ask over max price", line!(), column!())
+    );
+
+    // Sam is willing to pay up to 10
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &buy(10), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing History
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> No history before the ask changes
+// <X> Each ask change is recorded with the old & new ask
+// <X> History is capped at MAX_ASK_HISTORY, dropping the oldest
+#[test]
+fn listing_ask_history() -> Result<(), anyhow::Error> {
+    use crate::state::MAX_ASK_HISTORY;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(1),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let history = |router: &App| {
+        let q = crate::msg::QueryMsg::GetListingHistory {
+            listing_id: "1".to_string(),
+        };
+        let res: crate::query::ListingHistoryResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q).unwrap();
+        res.changes
+    };
+    let change_ask = |router: &mut App, amount: u128| {
+        let msg = crate::msg::ExecuteMsg::ChangeAsk {
+            listing_id: "1".to_string(),
+            new_ask: native_actions::native_ask(amount),
+        };
+        router.execute_contract(john.address.clone(), junovaults.clone(), &msg, &[])
+    };
+
+    ensure!(history(router).is_empty(), here("No changes yet", line!(), column!()));
+
+    // 1 -> 2 -> 3
+    for amount in [2, 3] {
+        let res = change_ask(router, amount);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let changes = history(router);
+    ensure!(changes.len() == 2, here(format!("{changes:?}"), line!(), column!()));
+    ensure!(
+        changes[0].old_ask == native_actions::native_ask(1)
+            && changes[0].new_ask == native_actions::native_ask(2)
+            && changes[1].old_ask == native_actions::native_ask(2)
+            && changes[1].new_ask == native_actions::native_ask(3),
+        here(format!("{changes:?}"), line!(), column!())
+    );
+    ensure!(
+        changes[1].timestamp == router.block_info().time,
+        here("Change timestamp", line!(), column!())
+    );
+
+    // Fill past the cap, the oldest entries are dropped
+    for amount in 4..(MAX_ASK_HISTORY as u128 + 5) {
+        let res = change_ask(router, amount);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let changes = history(router);
+    ensure!(changes.len() == MAX_ASK_HISTORY, here(changes.len(), line!(), column!()));
+    ensure!(
+        changes.last().unwrap().new_ask == native_actions::native_ask(MAX_ASK_HISTORY as u128 + 4),
+        here(format!("{:?}", changes.last()), line!(), column!())
+    );
+    ensure!(
+        changes[0].old_ask == native_actions::native_ask(4),
+        here(format!("{:?}", changes[0]), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Buckets By Value
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Only buckets at or above the threshold are returned, across owners
+// <X> Other denoms don't count towards the threshold
+// <X> Limit caps the number of buckets returned
+#[test]
+fn buckets_with_min_value() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    for (owner, bucket_id, amount) in [
+        (&john.address, "john_small", 5),
+        (&john.address, "john_big", 50),
+        (&sam.address, "sam_exact", 20),
+    ] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            owner,
+            bucket_id,
+            &coins(amount, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let with_min = |router: &App, denom: &str, min: u128, limit: Option<u32>| {
+        let q = crate::msg::QueryMsg::GetBucketsWithMinValue {
+            denom: denom.to_string(),
+            min: Uint128::from(min),
+            limit,
+        };
+        let res: crate::query::GetBucketsResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q).unwrap();
+        let mut ids: Vec<String> = res.buckets.into_iter().map(|(id, _bucket)| id).collect();
+        ids.sort();
+        ids
+    };
+
+    let found = with_min(router, VALID_NATIVE, 20, None);
+    ensure!(found == vec!["john_big", "sam_exact"], here(format!("{found:?}"), line!(), column!()));
+
+    let found = with_min(router, "ucosm", 1, None);
+    ensure!(found.is_empty(), here(format!("{found:?}"), line!(), column!()));
+
+    let found = with_min(router, VALID_NATIVE, 1, Some(2));
+    ensure!(found.len() == 2, here(format!("{found:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Buyer Reputation
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Buyer below min_buyer_purchases can't buy
+// <X> Buyer at min_buyer_purchases can buy
+#[test]
+fn buyer_reputation_gate() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // Listing 1 needs 1 completed purchase, listing 2 is open to anyone
+    for min_buyer_purchases in [Some(1), None] {
+        let msg = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(5),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &msg,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    for (listing_id, bucket_id) in [("1", "sam_1"), ("2", "sam_2")] {
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            bucket_id,
+            &coins(5, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam has no purchases yet
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "sam_1");
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::BuyerReputationTooLow {}.to_string(),
+        here("Buyer below threshold", line!(), column!())
+    );
+
+    // After buying listing 2, Sam meets the threshold
+    let res = native_actions::buy(router, &junovaults, &sam.address, "2", "sam_2");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "sam_1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Auto Match
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Crossing listing isn't matched while auto_match is off
+// <X> Listing that doesn't cross is saved as normal
+// <X> Crossing listing is filled immediately & never saved
+// <X> Balance checks after the match, fee taken from the matched bundle
+// <X> Seller is paid into a bucket & the sale is recorded, like a BuyListing
+#[test]
+fn auto_match_crossing_listing() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE, Sam 1000 uatom
+    let router = give_natives(&john, &mut router);
+    router.init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &sam.address, coins(1_000, "uatom")).unwrap()
+    });
+
+    let sell = |router: &mut App, seller: &Addr, for_sale: Coin, ask: Coin| {
+        let msg = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: GenericBalance {
+                    native: vec![ask],
+                    cw20: Vec::new(),
+                    nfts: Vec::new(),
+                },
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        };
+        router.execute_contract(seller.clone(), junovaults.clone(), &msg, &[for_sale])
+    };
+    let listing_exists = |router: &App, listing_id: &str| {
+        let q = crate::msg::QueryMsg::GetListingInfo {
+            listing_id: listing_id.to_string(),
+        };
+        let res: cosmwasm_std::StdResult<crate::query::ListingInfoResponse> =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q);
+        res.is_ok()
+    };
+
+    // John sells 1000 VALID_NATIVE for 100 uatom
+    let res = sell(router, &john.address, coin(1_000, VALID_NATIVE), coin(100, "uatom"));
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // auto_match is off, Sam's crossing listing is saved as listing 2
+    let res = sell(router, &sam.address, coin(100, "uatom"), coin(1_000, VALID_NATIVE));
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(listing_exists(router, "2"), here("No match while off", line!(), column!()));
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: Some(true),
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res = router.execute_contract(contract_admin.address, junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // 50 uatom doesn't cover John's ask, saved as listing 3
+    let res = sell(router, &sam.address, coin(50, "uatom"), coin(1_000, VALID_NATIVE));
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(listing_exists(router, "3"), here("No match, prices don't cross", line!(), column!()));
+
+    // 100 uatom for at least 900 crosses John's listing
+    let res = sell(router, &sam.address, coin(100, "uatom"), coin(900, VALID_NATIVE));
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(!listing_exists(router, "1"), here("John's listing filled", line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetNextListingId {};
+    let res: crate::query::NextListingIdResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.next_listing_id == 4, here("Matched listing not saved", line!(), column!()));
+
+    // Balance checks, 0.1% fee taken from the 1000 Sam received
+    let sam_native = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?;
+    ensure!(sam_native.amount.u128() == 999, here(sam_native, line!(), column!()));
+    let sam_atom = router.wrap().query_balance(sam.address.to_string(), "uatom")?;
+    ensure!(sam_atom.amount.u128() == 750, here(sam_atom, line!(), column!()));
+    let john_native = router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+    ensure!(john_native.amount.u128() == 99_999_000, here(john_native, line!(), column!()));
+
+    // John's 100 uatom is paid into a bucket like a BuyListing's, named after his listing
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: john.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.buckets.len() == 1
+            && res.buckets[0].0 == "auto_match_1"
+            && res.buckets[0].1.funds.native == coins(100, "uatom"),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // The match is recorded like any other sale
+    let q = crate::msg::QueryMsg::GetRecentSales {
+        limit: None,
+    };
+    let res: crate::query::RecentSalesResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.sales.len() == 1
+            && res.sales[0].listing_id == "1"
+            && res.sales[0].buyer == sam.address
+            && res.sales[0].price.native == coins(100, "uatom"),
+        here(format!("{res:?}"), line!(), column!())
+    );
+    let q = crate::msg::QueryMsg::GetSellerEarnings {
+        seller: john.address.to_string(),
+    };
+    let res: crate::query::SellerEarningsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.earnings == vec![("uatom".to_string(), Uint128::new(100))],
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Fee Rounding
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Floor (default) rounds the fee on 1500 down to 1
+// <X> Ceil rounds the fee on 1500 up to 2
+#[test]
+fn fee_rounding_mode() -> Result<(), anyhow::Error> {
+    use crate::state::Rounding;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // Sam buys 1500 VALID_NATIVE for 5 & withdraws it, returns what Sam received
+    let sell_and_withdraw = |router: &mut App, listing_id: &str| -> Result<u128, anyhow::Error> {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(1_500, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            listing_id,
+            &coins(5, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::buy(router, &junovaults, &sam.address, listing_id, listing_id);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+        let before = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?;
+        let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+            listing_id: listing_id.to_string(),
+        };
+        let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let after = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?;
+
+        Ok((after.amount - before.amount).u128())
+    };
+
+    let received = sell_and_withdraw(router, "1")?;
+    ensure!(received == 1_499, here(received, line!(), column!()));
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: Some(Rounding::Ceil),
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res = router.execute_contract(contract_admin.address, junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let received = sell_and_withdraw(router, "2")?;
+    ensure!(received == 1_498, here(received, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// NFT Receivers
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Only the admin can register a receiver
+// <X> Known receiver contract is paid out with SendNft, its hook is called
+// <X> Wallet is paid out with TransferNft
+#[test]
+fn nft_payout_to_known_receiver() -> Result<(), anyhow::Error> {
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let receiver_id = router.store_code(create_contract::nft_receiver_contract());
+    let receiver = router.instantiate_contract(
+        receiver_id,
+        contract_admin.address.clone(),
+        &Empty {},
+        &[],
+        "receiver",
+        None,
+    )?;
+
+    // Give Sam & the receiver contract 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+    router.init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &receiver, coins(100, VALID_NATIVE)).unwrap()
+    });
+
+    let register = crate::msg::ExecuteMsg::SetNftReceiver {
+        address: receiver.to_string(),
+        known: true,
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &register, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non-admin register", line!(), column!())
+    );
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &register, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let has_action = |res: &AppResponse, action: &str| {
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .any(|attr| attr.key == "action" && attr.value == action)
+    };
+
+    // John sells NeonPeepz #1 to the receiver contract & #2 to Sam
+    for (token_id, buyer) in [("1", &receiver), ("2", &sam.address)] {
+        let create_msg = to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(10),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        })?;
+        let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+            cw721_base::msg::ExecuteMsg::SendNft {
+                contract: junovaults.to_string(),
+                token_id: token_id.to_string(),
+                msg: create_msg,
+            };
+        let res = router.execute_contract(john.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+        // Listing IDs are assigned in order, so they match the token IDs here
+        let res = native_actions::finalize(router, &junovaults, &john.address, token_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            buyer,
+            "bucket",
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::buy(router, &junovaults, buyer, token_id, "bucket");
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let withdraw = |listing_id: &str| crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: listing_id.to_string(),
+    };
+
+    let res = router.execute_contract(receiver.clone(), junovaults.clone(), &withdraw("1"), &[])?;
+    ensure!(has_action(&res, "send_nft"), here("SendNft to receiver", line!(), column!()));
+    ensure!(has_action(&res, "receive_nft_hook"), here("Hook called", line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    ensure!(owner == receiver, here(owner, line!(), column!()));
+
+    let res =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw("2"), &[])?;
+    ensure!(has_action(&res, "transfer_nft"), here("TransferNft to wallet", line!(), column!()));
+    ensure!(!has_action(&res, "send_nft"), here("No SendNft to wallet", line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "2".to_string(), false)?.owner;
+    ensure!(owner == sam.address, here(owner, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Bucket Shortfall
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Partially funded bucket is missing the rest of each asset
+// <X> Shortfall shrinks as the bucket is topped up
+// <X> Bucket that satisfies the ask has an empty shortfall
+#[test]
+fn bucket_shortfall() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // Listing 1 asks for 10 VALID_NATIVE + 5 JVONE + NeonPeepz #5, listing 2 only 10 VALID_NATIVE
+    let ask = GenericBalance {
+        native: coins(10, VALID_NATIVE),
+        cw20: vec![Cw20CoinVerified {
+            address: jvone.addr(),
+            amount: Uint128::from(5u32),
+        }],
+        nfts: vec![Nft {
+            contract_address: neonpeepz.addr(),
+            token_id: "5".to_string(),
+        }],
+    };
+    for ask in [ask.clone(), native_actions::native_ask(10)] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            ask,
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket",
+        &coins(4, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let shortfall = |router: &App, listing_id: &str| -> GenericBalance {
+        let q = crate::msg::QueryMsg::GetBucketShortfall {
+            bucket_owner: sam.address.to_string(),
+            bucket_id: "sam_bucket".to_string(),
+            listing_id: listing_id.to_string(),
+        };
+        let res: crate::query::BucketShortfallResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q).unwrap();
+        res.missing
+    };
+
+    let missing = shortfall(router, "1");
+    let expected = GenericBalance {
+        native: coins(6, VALID_NATIVE),
+        ..ask.clone()
+    };
+    ensure!(missing == expected, here(format!("{missing:?}"), line!(), column!()));
+
+    // Top up the rest of the VALID_NATIVE
+    let add = crate::msg::ExecuteMsg::AddToBucket {
+        bucket_id: "sam_bucket".to_string(),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &add,
+        &coins(6, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let missing = shortfall(router, "1");
+    let expected = GenericBalance {
+        native: Vec::new(),
+        ..ask
+    };
+    ensure!(missing == expected, here(format!("{missing:?}"), line!(), column!()));
+
+    let missing = shortfall(router, "2");
+    ensure!(
+        missing.native.is_empty() && missing.cw20.is_empty() && missing.nfts.is_empty(),
+        here(format!("{missing:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Vesting
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Nothing to withdraw at 0% vested
+// <X> Half the fungible assets at 50% vested, NFT stays locked
+// <X> The rest & the NFT at 100% vested, listing is then removed
+#[test]
+fn vesting_withdrawals() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John sells 800 VALID_NATIVE + NeonPeepz #1 for 5, vesting over 1000 seconds
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(5),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: Some(1000),
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create,
+        &coins(800, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: junovaults.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
+                listing_id: "1".to_string(),
+            })?,
+        };
+    let res = router.execute_contract(john.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "sam_bucket");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let sam_native = |router: &App| {
+        router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE).unwrap().amount.u128()
+    };
+    let start = sam_native(router);
+
+    // 0%
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NothingVested {}.to_string(),
+        here("Nothing vested at 0%", line!(), column!())
+    );
+
+    // 50%
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 100;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(500);
+    });
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let received = sam_native(router) - start;
+    ensure!(received == 400, here(received, line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    ensure!(owner == junovaults, here("NFT locked until fully vested", line!(), column!()));
+
+    // 100%, past the end only releases what's left
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1000);
+    });
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let received = sam_native(router) - start;
+    ensure!(received == 800, here(received, line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    ensure!(owner == sam.address, here(owner, line!(), column!()));
+
+    // Listing is gone once everything is withdrawn
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_err(), here("Listing removed", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Wind Down
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Only the admin can wind down
+// <X> New listings, buckets & buys are blocked, even after a later config update
+// <X> Bucket withdrawals & listing removals still work
+#[test]
+fn wind_down() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // Listing 1 finalized, listing 2 being prepared, Sam's bucket for listing 1
+    for _ in 0..2 {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let wind_down = crate::msg::ExecuteMsg::WindDown {};
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &wind_down, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non-admin wind down", line!(), column!())
+    );
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &wind_down,
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Updating the rest of the config doesn't undo it
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: Some(0),
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res = router.execute_contract(contract_admin.address, junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let wound_down = crate::ContractError::WoundDown {}.to_string();
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(
+        native_actions::err_string(res) == wound_down,
+        here("Create listing", line!(), column!())
+    );
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "sam_bucket_2",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == wound_down,
+        here("Create bucket", line!(), column!())
+    );
+
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "sam_bucket");
+    ensure!(native_actions::err_string(res) == wound_down, here("Buy", line!(), column!()));
+
+    // Sam gets the bucket back, John gets listing 2 back
+    let withdraw = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "sam_bucket".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let sam_native = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?;
+    ensure!(sam_native.amount.u128() == 100_000_000, here(sam_native, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Expiry Actions
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Can't process a listing before it expires
+// <X> Refund listing is sent back to its owner, by anyone
+// <X> Relist listing is finalized again for the same duration
+#[test]
+fn process_expiry_actions() -> Result<(), anyhow::Error> {
+    use crate::state::OnExpire;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    // Listing 1 refunds on expiry, listing 2 relists
+    for on_expire in [None, Some(OnExpire::Relist)] {
+        let create = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(5),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    for listing_id in ["1", "2"] {
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Max is the keeper
+    let process = |router: &mut App, listing_id: &str| {
+        let msg = crate::msg::ExecuteMsg::ProcessExpiry {
+            listing_id: listing_id.to_string(),
+        };
+        router.execute_contract(max.address.clone(), junovaults.clone(), &msg, &[])
+    };
+
+    let res = process(router, "1");
+    ensure!(res.is_err(), here("Not expired yet", line!(), column!()));
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1001);
+    });
+
+    // Refund
+    let res = process(router, "1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let john_native = router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+    ensure!(john_native.amount.u128() == 99_999_990, here(john_native, line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: cosmwasm_std::StdResult<crate::query::ListingInfoResponse> =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q);
+    ensure!(res.is_err(), here("Refunded listing removed", line!(), column!()));
+
+    // Relist
+    let res = process(router, "2");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "2".to_string(),
+    };
+    let info: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let expected = router.block_info().time.plus_seconds(1000).seconds().to_string();
+    ensure!(
+        info.expiration == expected && info.status == "Ready for purchase",
+        here(format!("{info:?}"), line!(), column!())
+    );
+
+    let res = process(router, "2");
+    ensure!(res.is_err(), here("Relisted listing not expired", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Cw20 Ask Validation
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Any cw20 address is accepted while the check is off
+// <X> Bogus cw20 ask is rejected once turned on
+// <X> Real cw20 ask still works
+// <X> Changing the ask to a bogus cw20 is rejected
+#[test]
+fn cw20_ask_validation() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    let cw20_ask = |address: &Addr| GenericBalance {
+        native: vec![],
+        cw20: vec![Cw20CoinVerified {
+            address: address.clone(),
+            amount: Uint128::from(100u32),
+        }],
+        nfts: vec![],
+    };
+    let bogus = Addr::unchecked("bogus");
+
+    // Off by default
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        cw20_ask(&bogus),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: Some(true),
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res = router.execute_contract(contract_admin.address, junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        cw20_ask(&bogus),
+    );
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::InvalidCw20Ask {
+                address: bogus.to_string()
+            }
+            .to_string(),
+        here("Bogus cw20 ask", line!(), column!())
+    );
+
+    // Listing 2
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        cw20_ask(&jvone.addr()),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let change = crate::msg::ExecuteMsg::ChangeAsk {
+        listing_id: "2".to_string(),
+        new_ask: cw20_ask(&bogus),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &change, &[]);
+    ensure!(res.is_err(), here("Change to bogus cw20 ask", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Limits
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Limits match the enforced constants
+// <X> Config driven limits follow a config update
+#[test]
+fn get_limits() -> Result<(), anyhow::Error> {
+    use crate::execute::{
+        DEFAULT_MAX_NFTS_PER_COLLECTION_PER_LISTING, DEFAULT_MAX_TRANSFERS_PER_BUY,
+        MAX_ASK_OPTIONS, MAX_BUCKETS_PER_WITHDRAW, MAX_FEE_BPS, MAX_FINALIZE_SECONDS,
+        MAX_LISTINGS_PER_REMOVE, MAX_WHITELISTED_BUYERS, MIN_FINALIZE_SECONDS,
+    };
+    use crate::query::LimitsResponse;
+    use crate::state::MAX_ASK_HISTORY;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let q = crate::msg::QueryMsg::GetLimits {};
+    let limits: LimitsResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let expected = LimitsResponse {
+        min_finalize_seconds: MIN_FINALIZE_SECONDS,
+        max_finalize_seconds: MAX_FINALIZE_SECONDS,
+        refund_grace_seconds: 0,
+        max_whitelisted_buyers: MAX_WHITELISTED_BUYERS as u64,
+        max_buckets_per_withdraw: MAX_BUCKETS_PER_WITHDRAW,
+        max_listings_per_remove: MAX_LISTINGS_PER_REMOVE,
+        max_ask_history: MAX_ASK_HISTORY as u64,
+        max_page_size: 30,
+        max_transfers_per_buy: DEFAULT_MAX_TRANSFERS_PER_BUY,
+        max_fee_bps: MAX_FEE_BPS,
+        max_ask_options: MAX_ASK_OPTIONS as u64,
+        max_nfts_per_collection_per_listing: DEFAULT_MAX_NFTS_PER_COLLECTION_PER_LISTING,
+    };
+    ensure!(limits == expected, here(format!("{limits:?}"), line!(), column!()));
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: Some(100),
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res = router.execute_contract(contract_admin.address, junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let limits: LimitsResponse = router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(
+        limits
+            == LimitsResponse {
+                refund_grace_seconds: 100,
+                ..expected
+            },
+        here(format!("{limits:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Address Activity
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Creator shows up on the listings they created
+// <X> Interested, bidder & buyer roles are all recorded for the same listing
+// <X> Results are paginated
+#[test]
+fn address_activity() -> Result<(), anyhow::Error> {
+    use crate::query::{AddressActivityResponse, ListingActivity};
+    use crate::state::ActivityRole;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let activity = |router: &App, address: &Addr, start_after: Option<&str>, limit: Option<u32>| {
+        let q = crate::msg::QueryMsg::GetAddressActivity {
+            address: address.to_string(),
+            start_after: start_after.map(str::to_string),
+            limit,
+        };
+        let res: AddressActivityResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res.activity)
+    };
+
+    // Listings 1 & 2
+    for _ in 0..2 {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam is interested, targets a bucket at listing 1 & buys it
+    let interest = crate::msg::ExecuteMsg::ExpressInterest {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &interest, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let target = crate::msg::ExecuteMsg::SetBucketTarget {
+        bucket_id: "b1".to_string(),
+        target_listing: Some("1".to_string()),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &target, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let sam_activity = activity(router, &sam.address, None, None)?;
+    ensure!(
+        sam_activity
+            == vec![ListingActivity {
+                listing_id: "1".to_string(),
+                roles: vec![ActivityRole::Interested, ActivityRole::Bidder, ActivityRole::Buyer],
+            }],
+        here(format!("{sam_activity:?}"), line!(), column!())
+    );
+
+    let john_activity = activity(router, &john.address, None, None)?;
+    ensure!(
+        john_activity.len() == 2
+            && john_activity.iter().all(|a| a.roles == vec![ActivityRole::Creator]),
+        here(format!("{john_activity:?}"), line!(), column!())
+    );
+
+    // Paginated
+    let page = activity(router, &john.address, None, Some(1))?;
+    ensure!(
+        page.len() == 1 && page[0].listing_id == "1",
+        here(format!("{page:?}"), line!(), column!())
+    );
+    let page = activity(router, &john.address, Some("1"), Some(1))?;
+    ensure!(
+        page.len() == 1 && page[0].listing_id == "2",
+        here(format!("{page:?}"), line!(), column!())
+    );
+
+    let max_activity = activity(router, &max.address, None, None)?;
+    ensure!(max_activity.is_empty(), here(format!("{max_activity:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Transfer Limit
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Buy is rejected when the bundle, bucket & fee transfers pass the limit
+// <X> Same buy works once the limit allows it
+#[test]
+fn max_transfers_per_buy() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let set_max = |router: &mut App, max_transfers_per_buy: u32| {
+        let update = crate::msg::ExecuteMsg::UpdateConfig {
+            config_msg: crate::msg::UpdateConfigMsg {
+                refund_grace_seconds: None,
+                auto_match: None,
+                rounding: None,
+                validate_cw20_asks: None,
+                max_transfers_per_buy: Some(max_transfers_per_buy),
+                maker_fee_bps: None,
+                taker_fee_bps: None,
+                fee_collector: None,
+                admin_timelock_seconds: None,
+                listing_bond: None,
+                min_market_ask_value: None,
+                default_listing_ttl: None,
+                referral_bps: None,
+                max_page_size: None,
+                min_active_seconds: None,
+                swap_router: None,
+                max_nfts_per_collection_per_listing: None,
+                restrict_creators: None,
+                fee_holiday_until: None,
+                reveal_deposit_slash_bps: None,
+                buy_cooldown_seconds: None,
+                min_offer_value: None,
+                max_offers: None,
+                dust_threshold: None,
+            },
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[])
+    };
+
+    // 1 transfer for the bundle, 1 for the fee & 1 for the bucket
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(1_000, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = set_max(router, 2);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::TooManyTransfers {
+                count: 3,
+                max: 2
+            }
+            .to_string(),
+        here("Over the transfer limit", line!(), column!())
+    );
+
+    let res = set_max(router, 3);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Private Listings
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Private listing is left out of the market query
+// <X> Private listing is still returned by GetListingInfo & GetListingsByOwner
+// <X> Private listing can still be bought
+#[test]
+fn private_listing() -> Result<(), anyhow::Error> {
+    use crate::query::MultiListingResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // Listing 1 is private, listing 2 is public
+    for private in [Some(true), None] {
+        let create = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(5),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire: None,
+                private,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    for listing_id in ["1", "2"] {
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetListingsForMarket {
+        page_num: 1,
+    };
+    let market: MultiListingResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let ids: Vec<&str> = market.listings.iter().map(|l| l.id.as_str()).collect();
+    ensure!(ids == vec!["2"], here(format!("{ids:?}"), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: cosmwasm_std::StdResult<crate::query::ListingInfoResponse> =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q);
+    ensure!(res.is_ok(), here("Private listing info", line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingsByOwner {
+        owner: john.address.to_string(),
+    };
+    let owned: MultiListingResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        owned.listings.len() == 2 && owned.listings.iter().any(|l| l.id == "1" && l.private),
+        here(format!("{owned:?}"), line!(), column!())
+    );
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing Archive
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Sold & withdrawn listing is moved to the archive
+// <X> Removed listing is moved to the archive
+// <X> Archived listings are gone from active queries
+// <X> Only the admin can prune the archive
+#[test]
+fn listing_archive() -> Result<(), anyhow::Error> {
+    use crate::query::ArchivedListingResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let archived = |router: &App, listing_id: &str| {
+        let q = crate::msg::QueryMsg::GetArchivedListing {
+            listing_id: listing_id.to_string(),
+        };
+        router.wrap().query_wasm_smart::<ArchivedListingResponse>(junovaults.clone(), &q)
+    };
+    let active = |router: &App, listing_id: &str| {
+        let q = crate::msg::QueryMsg::GetListingInfo {
+            listing_id: listing_id.to_string(),
+        };
+        router
+            .wrap()
+            .query_wasm_smart::<crate::query::ListingInfoResponse>(junovaults.clone(), &q)
+            .is_ok()
+    };
+
+    // Listings 1 & 2
+    for _ in 0..2 {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam buys listing 1, it's only archived once withdrawn
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(archived(router, "1").is_err(), here("Not withdrawn yet", line!(), column!()));
+
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let sold = archived(router, "1")?.listing;
+    ensure!(
+        sold.claimant == Some(sam.address.clone()) && sold.status == crate::state::Status::Closed,
+        here(format!("{sold:?}"), line!(), column!())
+    );
+    ensure!(!active(router, "1"), here("Sold listing still active", line!(), column!()));
+
+    // John removes listing 2
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let removed = archived(router, "2")?.listing;
+    ensure!(
+        removed.creator == john.address && removed.claimant.is_none(),
+        here(format!("{removed:?}"), line!(), column!())
+    );
+    ensure!(!active(router, "2"), here("Removed listing still active", line!(), column!()));
+
+    // Pruning
+    let prune = crate::msg::ExecuteMsg::PruneArchive {
+        listing_ids: vec!["1".to_string()],
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &prune, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non-admin prune", line!(), column!())
+    );
+    let res = router.execute_contract(contract_admin.address, junovaults.clone(), &prune, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    ensure!(archived(router, "1").is_err(), here("Pruned", line!(), column!()));
+    ensure!(archived(router, "2").is_ok(), here("Not pruned", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Maker / Taker Fees
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Fees above the max are rejected
+// <X> Bucket holding only the ask is short the taker fee
+// <X> Buyer pays the taker fee on top of the ask, seller receives the ask minus the maker fee
+// <X> Both fees go to the fee collector
+#[test]
+fn maker_taker_fees() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let set_fees = |router: &mut App, maker_fee_bps: u64, taker_fee_bps: u64| {
+        let update = crate::msg::ExecuteMsg::UpdateConfig {
+            config_msg: crate::msg::UpdateConfigMsg {
+                refund_grace_seconds: None,
+                auto_match: None,
+                rounding: None,
+                validate_cw20_asks: None,
+                max_transfers_per_buy: None,
+                maker_fee_bps: Some(maker_fee_bps),
+                taker_fee_bps: Some(taker_fee_bps),
+                fee_collector: Some(max.address.to_string()),
+                admin_timelock_seconds: None,
+                listing_bond: None,
+                min_market_ask_value: None,
+                default_listing_ttl: None,
+                referral_bps: None,
+                max_page_size: None,
+                min_active_seconds: None,
+                swap_router: None,
+                max_nfts_per_collection_per_listing: None,
+                restrict_creators: None,
+                fee_holiday_until: None,
+                reveal_deposit_slash_bps: None,
+                buy_cooldown_seconds: None,
+                min_offer_value: None,
+                max_offers: None,
+                dust_threshold: None,
+            },
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[])
+    };
+    let balance = |router: &App, address: &Addr| {
+        router.wrap().query_balance(address.to_string(), VALID_NATIVE).map(|c| c.amount.u128())
+    };
+
+    let res = set_fees(router, 1_001, 0);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::FeeTooHigh {
+                max: 1_000
+            }
+            .to_string(),
+        here("Maker fee too high", line!(), column!())
+    );
+
+    // 1% maker & 2% taker
+    let res = set_fees(router, 100, 200);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(1_000),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_err(), here("Missing the taker fee", line!(), column!()));
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b2",
+        &coins(1_020, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let max_before = balance(router, &max.address)?;
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b2");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let max_after = balance(router, &max.address)?;
+    ensure!(max_after - max_before == 30, here(max_after - max_before, line!(), column!()));
+
+    let john_before = balance(router, &john.address)?;
+    let withdraw = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "b2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let john_after = balance(router, &john.address)?;
+    ensure!(john_after - john_before == 990, here(john_after - john_before, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Active Collections
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Collections appear once an NFT of theirs is listed
+// <X> Collection drops off when its listing is removed
+// <X> Collection drops off when its listing is bought, & stays off after the withdrawal
+#[test]
+fn active_collections() -> Result<(), anyhow::Error> {
+    use crate::query::ActiveCollectionsResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give Sam 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+
+    let active = |router: &App| {
+        let q = crate::msg::QueryMsg::GetActiveCollections {
+            start_after: None,
+            limit: None,
+        };
+        let res: ActiveCollectionsResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res.collections)
+    };
+    let send_nft =
+        |router: &mut App, collection: &Addr, token_id: &str, msg: cosmwasm_std::Binary| {
+            let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+                cw721_base::msg::ExecuteMsg::SendNft {
+                    contract: junovaults.to_string(),
+                    token_id: token_id.to_string(),
+                    msg,
+                };
+            router.execute_contract(john.address.clone(), collection.clone(), &send_nft, &[])
+        };
+    let create_msg = to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(10),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    })?;
+
+    // Listing 1 holds NeonPeepz #1 & #2, listing 2 holds ShittyKittyz #1
+    let res = send_nft(router, &neonpeepz.addr(), "1", create_msg.clone());
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
+        listing_id: "1".to_string(),
+    })?;
+    let res = send_nft(router, &neonpeepz.addr(), "2", add_msg);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = send_nft(router, &shittykittyz.addr(), "1", create_msg);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let mut expected = vec![neonpeepz.addr().to_string(), shittykittyz.addr().to_string()];
+    expected.sort();
+    let collections = active(router)?;
+    ensure!(collections == expected, here(format!("{collections:?}"), line!(), column!()));
+
+    // Removing listing 2
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let collections = active(router)?;
+    ensure!(
+        collections == vec![neonpeepz.addr().to_string()],
+        here(format!("{collections:?}"), line!(), column!())
+    );
+
+    // Sam buys listing 1 & withdraws it
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let collections = active(router)?;
+    ensure!(collections.is_empty(), here(format!("{collections:?}"), line!(), column!()));
+
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let collections = active(router)?;
+    ensure!(collections.is_empty(), here(format!("{collections:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Committed Buys
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Committed bucket can't be removed, added to, or withdrawn in bulk
+// <X> Listing can't be committed to twice or bought with another bucket
+// <X> Anyone can settle, seller gets the committed bucket
+#[test]
+fn committed_buy() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    let bucket_count = |router: &App, owner: &Addr| {
+        let q = crate::msg::QueryMsg::GetBuckets {
+            bucket_owner: owner.to_string(),
+        };
+        let res: crate::query::GetBucketsResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res.buckets.len())
+    };
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &max.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let commit = crate::msg::ExecuteMsg::CommitToListing {
+        listing_id: "1".to_string(),
+        bucket_id: "b1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &commit, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam's bucket is locked
+    let committed_err = crate::ContractError::BucketCommitted {
+        listing_id: "1".to_string(),
+    }
+    .to_string();
+    let remove = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "b1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(native_actions::err_string(res) == committed_err, here("Remove", line!(), column!()));
+    let add = crate::msg::ExecuteMsg::AddToBucket {
+        bucket_id: "b1".to_string(),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &add,
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(native_actions::err_string(res) == committed_err, here("Add", line!(), column!()));
+    let withdraw_all = crate::msg::ExecuteMsg::WithdrawAllBuckets {
+        start_after: None,
+        limit: None,
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw_all, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(bucket_count(router, &sam.address)? == 1, here("Bucket withdrawn", line!(), column!()));
+
+    // Max can't commit or buy
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &commit, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::ListingCommitted {}.to_string(),
+        here("Second commit", line!(), column!())
+    );
+    let res = native_actions::buy(router, &junovaults, &max.address, "1", "b1");
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::ListingCommitted {}.to_string(),
+        here("Other buyer", line!(), column!())
+    );
+
+    // Max settles for Sam
+    let settle = crate::msg::ExecuteMsg::SettleCommitment {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &settle, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    ensure!(bucket_count(router, &sam.address)? == 0, here("Buyer bucket", line!(), column!()));
+    ensure!(bucket_count(router, &john.address)? == 1, here("Seller bucket", line!(), column!()));
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &settle, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::NotFound {
+                typ: "Commitment".to_string(),
+                id: "1".to_string(),
+            }
+            .to_string(),
+        here("Settled twice", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Admin Timelock
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Once the timelock is set, config can't be updated directly
+// <X> Only the admin can queue & execute actions
+// <X> Queued action is rejected before its eta & applied after
+#[test]
+fn admin_timelock() -> Result<(), anyhow::Error> {
+    use crate::msg::{AdminAction, UpdateConfigMsg};
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let config_msg =
+        |maker_fee_bps: Option<u64>, admin_timelock_seconds: Option<u64>| UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        };
+
+    // 1000 second timelock
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: config_msg(None, Some(1000)),
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: config_msg(Some(100), None),
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::TimelockActive {}.to_string(),
+        here("Direct update", line!(), column!())
+    );
+
+    let queue = crate::msg::ExecuteMsg::QueueAdminAction {
+        action: AdminAction::UpdateConfig {
+            config_msg: config_msg(Some(100), None),
+        },
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &queue, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non admin queue", line!(), column!())
+    );
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &queue, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let eta = router.block_info().time.plus_seconds(1000).seconds();
+    let q = crate::msg::QueryMsg::GetQueuedActions {
+        start_after: None,
+        limit: None,
+    };
+    let res: crate::query::QueuedActionsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.actions.len() == 1 && res.actions[0].action_id == 1 && res.actions[0].eta == eta,
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // Too early
+    let execute = crate::msg::ExecuteMsg::ExecuteQueuedAction {
+        action_id: 1,
+    };
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(999);
+    });
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &execute, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::ActionNotReady {
+                eta
+            }
+            .to_string(),
+        here("Before eta", line!(), column!())
+    );
+
+    // Eta reached
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1);
+    });
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &execute, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non admin execute", line!(), column!())
+    );
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &execute, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetConfig {};
+    let res: crate::query::ConfigResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.config.maker_fee_bps == 100, here("Fee not applied", line!(), column!()));
+
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &execute, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::NotFound {
+                typ: "Queued Action".to_string(),
+                id: "1".to_string(),
+            }
+            .to_string(),
+        here("Executed twice", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing For NFT
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> NFTs added at creation or later resolve to their listing
+// <X> Index is cleared when the listing is removed or sold
+#[test]
+fn listing_for_nft() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give Sam 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+
+    let listing_for = |router: &App, collection: &Addr, token_id: &str| {
+        let q = crate::msg::QueryMsg::GetListingForNft {
+            contract: collection.to_string(),
+            token_id: token_id.to_string(),
+        };
+        let res: crate::query::ListingForNftResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res.listing_id)
+    };
+    let send_nft =
+        |router: &mut App, collection: &Addr, token_id: &str, msg: cosmwasm_std::Binary| {
+            let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+                cw721_base::msg::ExecuteMsg::SendNft {
+                    contract: junovaults.to_string(),
+                    token_id: token_id.to_string(),
+                    msg,
+                };
+            router.execute_contract(john.address.clone(), collection.clone(), &send_nft, &[])
+        };
+    let create_msg = to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(10),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    })?;
+
+    // Listing 1 holds NeonPeepz #1 & #2, listing 2 holds ShittyKittyz #1
+    let res = send_nft(router, &neonpeepz.addr(), "1", create_msg.clone());
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
+        listing_id: "1".to_string(),
+    })?;
+    let res = send_nft(router, &neonpeepz.addr(), "2", add_msg);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = send_nft(router, &shittykittyz.addr(), "1", create_msg);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let one = Some("1".to_string());
+    ensure!(listing_for(router, &neonpeepz.addr(), "1")? == one, here("#1", line!(), column!()));
+    ensure!(listing_for(router, &neonpeepz.addr(), "2")? == one, here("#2", line!(), column!()));
+    ensure!(
+        listing_for(router, &shittykittyz.addr(), "1")? == Some("2".to_string()),
+        here("Kitty #1", line!(), column!())
+    );
+    ensure!(listing_for(router, &neonpeepz.addr(), "3")?.is_none(), here("#3", line!(), column!()));
+
+    // Removing listing 2
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        listing_for(router, &shittykittyz.addr(), "1")?.is_none(),
+        here("Removed", line!(), column!())
+    );
+
+    // Sam buys listing 1
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        listing_for(router, &neonpeepz.addr(), "1")?.is_none(),
+        here("Sold", line!(), column!())
+    );
+    ensure!(
+        listing_for(router, &neonpeepz.addr(), "2")?.is_none(),
+        here("Sold", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing Bond
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Listing can't be finalized until exactly the bond is posted
+// <X> Bond is returned when the listing is removed
+// <X> Bond is forfeited to the fee collector when the admin force removes the listing
+// <X> Bond of a transferred listing is returned to whoever posted it
+#[test]
+fn listing_bond() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    let balance = |router: &App, address: &Addr| {
+        router.wrap().query_balance(address.to_string(), VALID_NATIVE).map(|c| c.amount.u128())
+    };
+    let post_bond = |router: &mut App, listing_id: &str, amount: u128| {
+        let msg = crate::msg::ExecuteMsg::PostBond {
+            listing_id: listing_id.to_string(),
+        };
+        router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &msg,
+            &coins(amount, VALID_NATIVE),
+        )
+    };
+    let starting_balance = balance(router, &john.address)?;
+
+    // 5 VALID_NATIVE bond
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: Some(cosmwasm_std::coin(5, VALID_NATIVE)),
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let bond = cosmwasm_std::coin(5, VALID_NATIVE).to_string();
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::BondRequired {
+                bond: bond.clone()
+            }
+            .to_string(),
+        here("Finalized without bond", line!(), column!())
+    );
+    let res = post_bond(router, "1", 4);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::InvalidBond {
+                bond: bond.clone()
+            }
+            .to_string(),
+        here("Short bond", line!(), column!())
+    );
+    let res = post_bond(router, "1", 5);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = post_bond(router, "1", 5);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::BondNotRequired {}.to_string(),
+        here("Bond posted twice", line!(), column!())
+    );
+
+    // Removing returns the listing & the bond
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &john.address)? == starting_balance,
+        here("Bond not returned", line!(), column!())
+    );
+
+    // Listing 2 is bonded & finalized, then force removed
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = post_bond(router, "2", 5);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "2", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let force_remove = crate::msg::ExecuteMsg::ForceRemoveListing {
+        listing_id: "2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &force_remove, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non admin force remove", line!(), column!())
+    );
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &force_remove,
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &john.address)? == starting_balance - 5,
+        here("Listing not returned", line!(), column!())
+    );
+    ensure!(
+        balance(router, &contract_admin.address)? == 5,
+        here("Bond not forfeited", line!(), column!())
+    );
+
+    // Listing 3 is bonded & finalized, then transferred to Max & sold to Sam
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = post_bond(router, "3", 5);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "3", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let transfer = crate::msg::ExecuteMsg::TransferListing {
+        listing_id: "3".to_string(),
+        new_owner: max.address.to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &transfer, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let router = give_natives(&sam, router);
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "3", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John posted the bond, so it's returned to him instead of Max
+    ensure!(
+        balance(router, &john.address)? == starting_balance - 15
+            && balance(router, &max.address)? == 0,
+        here("Bond not returned to its poster", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Ask Options
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Listing can't have more than the max ask options
+// <X> Bucket paying neither the ask nor an option is rejected
+// <X> Bucket paying only the second option buys the listing, seller gets exactly that option
+#[test]
+fn ask_options() -> Result<(), anyhow::Error> {
+    use crate::execute::MAX_ASK_OPTIONS;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // 100 VALID_NATIVE or 90 JVONE
+    let cw20_option = GenericBalance {
+        native: vec![],
+        cw20: vec![Cw20CoinVerified {
+            address: jvone.addr(),
+            amount: Uint128::from(90u32),
+        }],
+        nfts: vec![],
+    };
+    let create = |ask_options: Vec<GenericBalance>| crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(100),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: Some(ask_options),
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    };
+
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create(vec![cw20_option.clone(); MAX_ASK_OPTIONS + 1]),
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::TooManyAskOptions {
+                max: MAX_ASK_OPTIONS
+            }
+            .to_string(),
+        here("Too many options", line!(), column!())
+    );
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create(vec![cw20_option]),
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam's 50 VALID_NATIVE pays neither
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "short",
+        &coins(50, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "short");
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::FundsSentNotFundsAsked {
+                which: "Bucket ID: short".to_string()
+            }
+            .to_string(),
+        here("Neither option", line!(), column!())
+    );
+
+    // Sam's 90 JVONE pays the second option
+    let sam_msg = to_binary(&crate::msg::ReceiveMsg::CreateBucketCw20 {
+        bucket_id: "b1".to_string(),
+    })?;
+    let send = cw20_base::msg::ExecuteMsg::Send {
+        contract: junovaults.to_string(),
+        amount: Uint128::from(90u32),
+        msg: sam_msg,
+    };
+    let res = router.execute_contract(sam.address.clone(), jvone.addr(), &send, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: john.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.buckets.len() == 1
+            && res.buckets[0].1.funds.cw20
+                == vec![Cw20CoinVerified {
+                    address: jvone.addr(),
+                    amount: Uint128::from(90u32),
+                }],
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unique Users
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Counter increments once per new address creating a listing or bucket
+#[test]
+fn unique_users() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let unique_users = |router: &App| {
+        let q = crate::msg::QueryMsg::GetUniqueUsers {};
+        let res: crate::query::UniqueUsersResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res.unique_users)
+    };
+    ensure!(unique_users(router)? == 0, here("Initial", line!(), column!()));
+
+    // John creates a listing, then a bucket
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(unique_users(router)? == 1, here("John listing", line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &john.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(unique_users(router)? == 1, here("John bucket", line!(), column!()));
+
+    // Sam creates a bucket, then a listing
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(unique_users(router)? == 2, here("Sam bucket", line!(), column!()));
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &sam.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(unique_users(router)? == 2, here("Sam listing", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Minimum Market Ask Value
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Listings asking below the denom's minimum are left out of the market
+// <X> Hidden listings are still fetchable directly
+#[test]
+fn min_market_ask_value() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    // Hide listings asking less than 10 VALID_NATIVE
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: Some(coins(10, VALID_NATIVE)),
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Listing 1 asks 9, listing 2 asks 10
+    for ask in [9, 10] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(ask),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    for listing_id in ["1", "2"] {
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetListingsForMarket {
+        page_num: 1,
+    };
+    let market: crate::query::MultiListingResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let ids: Vec<&str> = market.listings.iter().map(|l| l.id.as_str()).collect();
+    ensure!(ids == vec!["2"], here(format!("{ids:?}"), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: cosmwasm_std::StdResult<crate::query::ListingInfoResponse> =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q);
+    ensure!(res.is_ok(), here("Dust listing info", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Transfer Listing
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Only the owner can transfer a listing
+// <X> Finalized listing moves to the new owner, who receives the sale proceeds
+#[test]
+fn transfer_listing() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let owned = |router: &App, owner: &Addr| {
+        let q = crate::msg::QueryMsg::GetListingsByOwner {
+            owner: owner.to_string(),
+        };
+        let res: crate::query::MultiListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res.listings)
+    };
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let transfer = crate::msg::ExecuteMsg::TransferListing {
+        listing_id: "1".to_string(),
+        new_owner: max.address.to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &transfer, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non owner transfer", line!(), column!())
+    );
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &transfer, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    ensure!(owned(router, &john.address)?.is_empty(), here("John", line!(), column!()));
+    let max_listings = owned(router, &max.address)?;
+    ensure!(
+        max_listings.len() == 1
+            && max_listings[0].creator == max.address
+            && max_listings[0].status == crate::state::Status::FinalizedReady,
+        here(format!("{max_listings:?}"), line!(), column!())
+    );
+
+    // Sam buys, Max gets the bucket
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: max.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.buckets.len() == 1, here("Max bucket", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Transfer Bucket
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Only the owner can transfer a bucket, the new owner can't already use the ID
+// <X> New owner controls the bucket, old owner doesn't
+#[test]
+fn transfer_bucket() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    let transfer = |bucket_id: &str| crate::msg::ExecuteMsg::TransferBucket {
+        bucket_id: bucket_id.to_string(),
+        new_owner: max.address.to_string(),
+    };
+    for (owner, bucket_id) in [(&sam, "gift"), (&max, "taken")] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &owner.address,
+            bucket_id,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "taken",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &transfer("gift"), &[]);
+    ensure!(res.is_err(), here("Non owner transfer", line!(), column!()));
+    let res =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &transfer("taken"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::IdAlreadyExists {}.to_string(),
+        here("ID used by new owner", line!(), column!())
+    );
+    let res =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &transfer("gift"), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam can't withdraw it anymore, Max buys with it
+    let remove = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "gift".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_err(), here("Old owner withdraw", line!(), column!()));
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &max.address, "1", "gift");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Default Listing TTL
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Without a default, finalizing needs the seconds
+// <X> Default outside the finalize bounds is rejected
+// <X> Listing finalized without seconds expires after the default
+#[test]
+fn default_listing_ttl() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    let set_default = |router: &mut App, default_listing_ttl: u64| {
+        let update = crate::msg::ExecuteMsg::UpdateConfig {
+            config_msg: crate::msg::UpdateConfigMsg {
+                refund_grace_seconds: None,
+                auto_match: None,
+                rounding: None,
+                validate_cw20_asks: None,
+                max_transfers_per_buy: None,
+                maker_fee_bps: None,
+                taker_fee_bps: None,
+                fee_collector: None,
+                admin_timelock_seconds: None,
+                listing_bond: None,
+                min_market_ask_value: None,
+                default_listing_ttl: Some(default_listing_ttl),
+                referral_bps: None,
+                max_page_size: None,
+                min_active_seconds: None,
+                swap_router: None,
+                max_nfts_per_collection_per_listing: None,
+                restrict_creators: None,
+                fee_holiday_until: None,
+                reveal_deposit_slash_bps: None,
+                buy_cooldown_seconds: None,
+                min_offer_value: None,
+                max_offers: None,
+                dust_threshold: None,
+            },
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[])
+    };
+    let finalize = crate::msg::ExecuteMsg::Finalize {
+        listing_id: "1".to_string(),
+        seconds: None,
+        expiration_height: None,
+    };
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &finalize, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::InvalidExpiration {}.to_string(),
+        here("No default", line!(), column!())
+    );
+
+    let res = set_default(router, 100);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::InvalidExpiration {}.to_string(),
+        here("Default too short", line!(), column!())
+    );
+    let res = set_default(router, 86_400);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &finalize, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingsByOwner {
+        owner: john.address.to_string(),
+    };
+    let res: crate::query::MultiListingResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let expected = router.block_info().time.plus_seconds(86_400);
+    ensure!(
+        res.listings[0].expiration_time == Some(expected),
+        here(format!("{:?}", res.listings[0].expiration_time), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Floor History
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Finalized listings & sales lower the day's floor
+// <X> Sales on a later day add a new snapshot
+// <X> Limit returns the newest snapshots
+#[test]
+fn floor_history() -> Result<(), anyhow::Error> {
+    use crate::state::FloorSnapshot;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give Sam & Max 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+    let router = give_natives(&max, router);
+
+    let floor_history = |router: &App, limit: Option<u32>| {
+        let q = crate::msg::QueryMsg::GetFloorHistory {
+            contract: neonpeepz.addr().to_string(),
+            denom: VALID_NATIVE.to_string(),
+            limit,
+        };
+        let res: crate::query::FloorHistoryResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res.snapshots)
+    };
+
+    // Listing 1 sells NeonPeepz #1 for 10, listing 2 sells #2 for 8
+    for (token_id, ask) in [("1", 10), ("2", 8)] {
+        let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+            cw721_base::msg::ExecuteMsg::SendNft {
+                contract: junovaults.to_string(),
+                token_id: token_id.to_string(),
+                msg: to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+                    create_msg: CreateListingMsg {
+                        ask: native_actions::native_ask(ask),
+                        whitelisted_buyer: None,
+                        whitelist_until: None,
+                        payout_address: None,
+                        min_buyer_purchases: None,
+                        vesting_seconds: None,
+                        on_expire: None,
+                        private: None,
+                        ask_options: None,
+                        auto_swap_to: None,
+                        units: None,
+                        on_sale_callback: None,
+                        min_net_proceeds: None,
+                        max_offers: None,
+                    },
+                })?,
+            };
+        let res = router.execute_contract(john.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, token_id, 200_000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let day = router.block_info().time.seconds() / 86_400;
+
+    // Sam buys listing 2 the same day
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(8, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "2", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let snapshots = floor_history(router, None)?;
+    ensure!(
+        snapshots
+            == vec![FloorSnapshot {
+                day,
+                price: Uint128::new(8)
+            }],
+        here(format!("{snapshots:?}"), line!(), column!())
+    );
+
+    // Max buys listing 1 the next day
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(86_400);
+    });
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &max.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &max.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let next_day = FloorSnapshot {
+        day: day + 1,
+        price: Uint128::new(10),
+    };
+    let snapshots = floor_history(router, None)?;
+    ensure!(
+        snapshots.len() == 2 && snapshots[1] == next_day,
+        here(format!("{snapshots:?}"), line!(), column!())
+    );
+    let snapshots = floor_history(router, Some(1))?;
+    ensure!(snapshots == vec![next_day], here(format!("{snapshots:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Not Found vs Unauthorized
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Listing executes error ListingNotFound on a missing ID
+// <X> Listing executes error Unauthorized for a non-owner
+// <X> Bucket executes error BucketNotFound, buckets are keyed by owner
+#[test]
+fn not_found_vs_unauthorized() -> Result<(), anyhow::Error> {
+    use crate::msg::ExecuteMsg;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John has listing 1 & bucket b1
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &john.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let listing_msgs = |listing_id: &str| {
+        let listing_id = listing_id.to_string();
+        vec![
+            ExecuteMsg::ChangeAsk {
+                listing_id: listing_id.clone(),
+                new_ask: native_actions::native_ask(5),
+            },
+            ExecuteMsg::RemoveListing {
+                listing_id: listing_id.clone(),
+            },
+            ExecuteMsg::Finalize {
+                listing_id: listing_id.clone(),
+                seconds: Some(1000),
+                expiration_height: None,
+            },
+            ExecuteMsg::RefundExpired {
+                listing_id,
+            },
+        ]
+    };
+
+    // Missing listing
+    for msg in listing_msgs("9") {
+        let res = router.execute_contract(john.address.clone(), junovaults.clone(), &msg, &[]);
+        ensure!(
+            native_actions::err_string(res)
+                == crate::ContractError::ListingNotFound {
+                    id: "9".to_string()
+                }
+                .to_string(),
+            here(format!("{msg:?}"), line!(), column!())
+        );
+    }
+
+    // Listing owned by John
+    for msg in listing_msgs("1") {
+        let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &msg, &[]);
+        ensure!(
+            native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+            here(format!("{msg:?}"), line!(), column!())
+        );
+    }
+
+    // Sam has no bucket b1, John's is not visible to him
+    let bucket_msgs = [
+        ExecuteMsg::AddToBucket {
+            bucket_id: "b1".to_string(),
+        },
+        ExecuteMsg::RemoveBucket {
+            bucket_id: "b1".to_string(),
+        },
+        ExecuteMsg::BuyListing {
+            listing_id: "1".to_string(),
+            bucket_id: "b1".to_string(),
+            max_price: None,
+            referrer: None,
+        },
+    ];
+    for msg in bucket_msgs {
+        let res = router.execute_contract(
+            sam.address.clone(),
+            junovaults.clone(),
+            &msg,
+            &coins(1, VALID_NATIVE),
+        );
+        ensure!(
+            native_actions::err_string(res)
+                == crate::ContractError::BucketNotFound {
+                    id: "b1".to_string()
+                }
+                .to_string(),
+            here(format!("{msg:?}"), line!(), column!())
+        );
+    }
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Referral Fee
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Referral above the whole fee is rejected
+// <X> Buyer or seller referring themselves is rejected
+// <X> Referrer receives its share of the maker & taker fees, the fee collector the rest
+#[test]
+fn referral_fee() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let set_referral = |router: &mut App, referral_bps: u64| {
+        let update = crate::msg::ExecuteMsg::UpdateConfig {
+            config_msg: crate::msg::UpdateConfigMsg {
+                refund_grace_seconds: None,
+                auto_match: None,
+                rounding: None,
+                validate_cw20_asks: None,
+                max_transfers_per_buy: None,
+                maker_fee_bps: Some(100),
+                taker_fee_bps: Some(200),
+                fee_collector: None,
+                admin_timelock_seconds: None,
+                listing_bond: None,
+                min_market_ask_value: None,
+                default_listing_ttl: None,
+                referral_bps: Some(referral_bps),
+                max_page_size: None,
+                min_active_seconds: None,
+                swap_router: None,
+                max_nfts_per_collection_per_listing: None,
+                restrict_creators: None,
+                fee_holiday_until: None,
+                reveal_deposit_slash_bps: None,
+                buy_cooldown_seconds: None,
+                min_offer_value: None,
+                max_offers: None,
+                dust_threshold: None,
+            },
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[])
+    };
+    let buy = |router: &mut App, referrer: &Addr| {
+        let msg = crate::msg::ExecuteMsg::BuyListing {
+            listing_id: "1".to_string(),
+            bucket_id: "b1".to_string(),
+            max_price: None,
+            referrer: Some(referrer.to_string()),
+        };
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &msg, &[])
+    };
+    let balance = |router: &App, address: &Addr| {
+        router.wrap().query_balance(address.to_string(), VALID_NATIVE).map(|c| c.amount.u128())
+    };
+
+    let res = set_referral(router, 10_001);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::ReferralTooHigh {
+                max: 10_000
+            }
+            .to_string(),
+        here("Referral too high", line!(), column!())
+    );
+
+    // 1% maker, 2% taker & half of both to the referrer
+    let res = set_referral(router, 5_000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(1_000),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(1_020, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    for referrer in [&sam.address, &john.address] {
+        let res = buy(router, referrer);
+        ensure!(
+            native_actions::err_string(res) == crate::ContractError::InvalidReferrer {}.to_string(),
+            here(referrer, line!(), column!())
+        );
+    }
+
+    let max_before = balance(router, &max.address)?;
+    let admin_before = balance(router, &contract_admin.address)?;
+    let res = buy(router, &max.address);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let max_after = balance(router, &max.address)?;
+    let admin_after = balance(router, &contract_admin.address)?;
+    ensure!(max_after - max_before == 15, here(max_after - max_before, line!(), column!()));
+    ensure!(admin_after - admin_before == 15, here(admin_after - admin_before, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Simulate Buy
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Bucket short of the ask has no payout
+// <X> Payout splits the bucket & bundle into the seller's, buyer's & fee amounts
+// <X> Balances after the buy & withdrawals match the simulation
+#[test]
+fn simulate_buy() -> Result<(), anyhow::Error> {
+    use crate::query::{BuyPayout, SimulateBuyResponse};
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // 1% maker & 2% taker
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: Some(100),
+            taker_fee_bps: Some(200),
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let simulate = |router: &App, bucket_id: &str| {
+        let q = crate::msg::QueryMsg::SimulateBuy {
+            bucket_owner: sam.address.to_string(),
+            bucket_id: bucket_id.to_string(),
+            listing_id: "1".to_string(),
+        };
+        let res: SimulateBuyResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res)
+    };
+    let balance = |router: &App, address: &Addr| {
+        router.wrap().query_balance(address.to_string(), VALID_NATIVE).map(|c| c.amount.u128())
+    };
+
+    // John sells 10_000 for 1_000
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10_000, VALID_NATIVE),
+        native_actions::native_ask(1_000),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Missing the taker fee
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = simulate(router, "b1")?;
+    ensure!(
+        res == SimulateBuyResponse {
+            satisfies_ask: false,
+            payout: None
+        },
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b2",
+        &coins(1_020, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = simulate(router, "b2")?;
+    let expected = BuyPayout {
+        paid_ask: native_actions::native_ask(1_000),
+        seller_receives: native_actions::native_ask(990),
+        marketplace_fee: Uint128::new(30),
+        buyer_receives: native_actions::native_ask(9_990),
+        community_pool_fee: Uint128::new(10),
+    };
+    ensure!(
+        res == SimulateBuyResponse {
+            satisfies_ask: true,
+            payout: Some(expected.clone())
+        },
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // Buy & withdraw both sides
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b2");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let john_before = balance(router, &john.address)?;
+    let withdraw = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "b2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let john_after = balance(router, &john.address)?;
+    ensure!(
+        expected.seller_receives.native[0].amount.u128() == john_after - john_before,
+        here(john_after - john_before, line!(), column!())
+    );
+
+    let sam_before = balance(router, &sam.address)?;
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let sam_after = balance(router, &sam.address)?;
+    ensure!(
+        expected.buyer_receives.native[0].amount.u128() == sam_after - sam_before,
+        here(sam_after - sam_before, line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// NFT Claims
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Failed NFT payout on removal doesn't revert, the NFT stays in the contract
+// <X> Only the owner can claim the NFT, a failed claim keeps it claimable
+// <X> Successful claim sends the NFT & removes the claim
+#[test]
+fn claim_nft() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let set_receiver = |router: &mut App, known: bool| {
+        let msg = crate::msg::ExecuteMsg::SetNftReceiver {
+            address: john.address.to_string(),
+            known,
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &msg, &[])
+    };
+    let claim = crate::msg::ExecuteMsg::ClaimNft {
+        contract: neonpeepz.addr().to_string(),
+        token_id: "1".to_string(),
+    };
+
+    // John lists NeonPeepz #1
+    let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: junovaults.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+                create_msg: CreateListingMsg {
+                    ask: native_actions::native_ask(10),
+                    whitelisted_buyer: None,
+                    whitelist_until: None,
+                    payout_address: None,
+                    min_buyer_purchases: None,
+                    vesting_seconds: None,
+                    on_expire: None,
+                    private: None,
+                    ask_options: None,
+                    auto_swap_to: None,
+                    units: None,
+                    on_sale_callback: None,
+                    min_net_proceeds: None,
+                    max_offers: None,
+                },
+            })?,
+        };
+    let res = router.execute_contract(john.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John isn't a contract, so sending him the NFT with SendNft fails
+    let res = set_receiver(&mut router, true);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    ensure!(owner == junovaults, here(owner, line!(), column!()));
+
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &claim, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Sam claimed", line!(), column!())
+    );
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &claim, &[]);
+    ensure!(res.is_err(), here("Claim sent with SendNft", line!(), column!()));
+
+    let res = set_receiver(&mut router, false);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &claim, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    ensure!(owner == john.address, here(owner, line!(), column!()));
+
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &claim, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::NotFound {
+                typ: "Claim".to_string(),
+                id: format!("{} 1", neonpeepz.addr()),
+            }
+            .to_string(),
+        here("Claimed twice", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Max Page Size
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Max page size is exposed through the limits
+// <X> Limits above the max page size are clamped to it
+#[test]
+fn max_page_size() -> Result<(), anyhow::Error> {
+    use crate::query::{LimitsResponse, MultiListingResponse};
+    use crate::state::Status;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: Some(2),
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetLimits {};
+    let limits: LimitsResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(limits.max_page_size == 2, here(limits.max_page_size, line!(), column!()));
+
+    for _ in 0..3 {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(10),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetListingsByStatus {
+        status: Status::BeingPrepared,
+        start_after: None,
+        limit: Some(10),
+    };
+    let res: MultiListingResponse = router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(res.listings.len() == 2, here(res.listings.len(), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Cw20 Basket
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Listing holds two distinct cw20s for sale
+// <X> Buyer withdraws each cw20 of the basket
+#[test]
+fn cw20_basket() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give Sam 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+
+    let cw20_balance = |router: &App, token: &Addr, address: &Addr| {
+        let q = cw20_base::msg::QueryMsg::Balance {
+            address: address.to_string(),
+        };
+        let res: cw20::BalanceResponse = router.wrap().query_wasm_smart(token, &q)?;
+        Ok::<_, anyhow::Error>(res.balance.u128())
+    };
+
+    // John lists 40 JVONE & adds 30 JVTWO
+    let create_msg = cw20_base::msg::ExecuteMsg::Send {
+        contract: junovaults.to_string(),
+        amount: Uint128::new(40),
+        msg: to_binary(&crate::msg::ReceiveMsg::CreateListingCw20 {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(10),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        })?,
+    };
+    let res = router.execute_contract(john.address.clone(), jvone.addr(), &create_msg, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let add_msg = cw20_base::msg::ExecuteMsg::Send {
+        contract: junovaults.to_string(),
+        amount: Uint128::new(30),
+        msg: to_binary(&crate::msg::ReceiveMsg::AddFundsToSaleCw20 {
+            listing_id: "1".to_string(),
+        })?,
+    };
+    let res = router.execute_contract(john.address.clone(), jvtwo.addr(), &add_msg, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.for_sale.len() == 2, here(format!("{:?}", res.for_sale), line!(), column!()));
+
+    // Sam buys & withdraws the basket
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let jvone_balance = cw20_balance(router, &jvone.addr(), &sam.address)?;
+    ensure!(jvone_balance == 140, here(jvone_balance, line!(), column!()));
+    let jvtwo_balance = cw20_balance(router, &jvtwo.addr(), &sam.address)?;
+    ensure!(jvtwo_balance == 130, here(jvtwo_balance, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Top Up Inventory
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Seller tops up the fungible amount for sale
+// <X> Top up is rejected once finalized
+// <X> Buyer withdraws the topped up amount
+#[test]
+fn top_up_inventory() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let top_up = crate::msg::ExecuteMsg::AddFundsToSaleNative {
+        listing_id: "1".to_string(),
+    };
+
+    // John lists 10 & tops up 15
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &top_up,
+        &coins(15, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.for_sale == vec![(VALID_NATIVE.to_string(), 25)],
+        here(format!("{:?}", res.for_sale), line!(), column!())
+    );
+
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &top_up,
+        &coins(15, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::AlreadyFinalized {}.to_string(),
+        here("Topped up a finalized listing", line!(), column!())
+    );
+
+    // Sam buys & withdraws all 25
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let sam_before = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?.amount;
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let sam_after = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?.amount;
+    ensure!(sam_after - sam_before == Uint128::new(25), here(sam_after, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Can Buy
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Whitelisted address can buy, others are denied with the reason
+// <X> Reputation gated listing opens up once the buyer has a purchase
+// <X> Missing listing can't be bought
+#[test]
+fn can_buy() -> Result<(), anyhow::Error> {
+    use crate::query::CanBuyResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let can_buy = |router: &App, listing_id: &str, address: &Addr| {
+        let q = crate::msg::QueryMsg::CanBuy {
+            listing_id: listing_id.to_string(),
+            address: address.to_string(),
+        };
+        let res: CanBuyResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res)
+    };
+    let denied = |reason: crate::ContractError| CanBuyResponse {
+        can_buy: false,
+        reason: Some(reason.to_string()),
+    };
+    let allowed = CanBuyResponse {
+        can_buy: true,
+        reason: None,
+    };
+
+    // Listing 1 is whitelisted to Sam, listing 2 needs a purchase
+    for (whitelisted_buyer, min_buyer_purchases) in
+        [(Some(sam.address.to_string()), None), (None, Some(1))]
+    {
+        let msg = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(10),
+                whitelisted_buyer,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &msg,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Not finalized yet
+    let res = can_buy(router, "1", &sam.address)?;
+    ensure!(
+        res == denied(crate::ContractError::NotPurchasable {}),
+        here(format!("{res:?}"), line!(), column!())
+    );
+    for listing_id in ["1", "2"] {
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let res = can_buy(router, "1", &sam.address)?;
+    ensure!(res == allowed, here(format!("{res:?}"), line!(), column!()));
+    let res = can_buy(router, "1", &max.address)?;
+    ensure!(
+        res == denied(crate::ContractError::NotWhitelisted {}),
+        here(format!("{res:?}"), line!(), column!())
+    );
+    let res = can_buy(router, "2", &sam.address)?;
+    ensure!(
+        res == denied(crate::ContractError::BuyerReputationTooLow {}),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // Sam's first purchase opens listing 2 to him
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = can_buy(router, "2", &sam.address)?;
+    ensure!(res == allowed, here(format!("{res:?}"), line!(), column!()));
+
+    let res = can_buy(router, "9", &sam.address)?;
+    ensure!(
+        res == denied(crate::ContractError::ListingNotFound {
+            id: "9".to_string()
+        }),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Height Expiry
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Expiration height must be in the future
+// <X> Listing can be bought up to its expiration height, time passing doesn't expire it
+// <X> Past the height it can't be bought & can be refunded
+#[test]
+fn height_expiry() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let finalize = |expiration_height: u64| crate::msg::ExecuteMsg::Finalize {
+        listing_id: "1".to_string(),
+        seconds: None,
+        expiration_height: Some(expiration_height),
+    };
+    let refund = crate::msg::ExecuteMsg::RefundExpired {
+        listing_id: "1".to_string(),
+    };
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let height = router.block_info().height;
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &finalize(height), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::InvalidExpiration {}.to_string(),
+        here("Current height", line!(), column!())
+    );
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &finalize(height + 10),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // A month passes but only 10 blocks, still buyable so it can't be refunded
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 10;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(2_592_000);
+    });
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &refund, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::NotExpired {
+                x: format!("block {}", height + 10),
+            }
+            .to_string(),
+        here("Refunded before the height", line!(), column!())
+    );
+
+    // One block past the height
+    router.update_block(|current_blockinfo| current_blockinfo.height += 1);
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Expired {}.to_string(),
+        here("Bought past the height", line!(), column!())
+    );
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &refund, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Recent Sales
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Sales are returned newest first with the price, buyer & seller
+// <X> Oldest sale is evicted past capacity
+#[test]
+fn recent_sales() -> Result<(), anyhow::Error> {
+    use crate::query::RecentSalesResponse;
+    use crate::state::MAX_RECENT_SALES;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let recent_sales = |router: &App, limit: u32| {
+        let q = crate::msg::QueryMsg::GetRecentSales {
+            limit: Some(limit),
+        };
+        let res: RecentSalesResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res.sales)
+    };
+
+    // Sam buys one more listing than the feed holds, listing N asks N
+    for n in 1..=MAX_RECENT_SALES + 1 {
+        let listing_id = n.to_string();
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(n as u128),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, &listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            &listing_id,
+            &coins(n as u128, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::buy(router, &junovaults, &sam.address, &listing_id, &listing_id);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let sales = recent_sales(router, 2)?;
+    let newest = (MAX_RECENT_SALES + 1).to_string();
+    ensure!(
+        sales.len() == 2
+            && sales[0].listing_id == newest
+            && sales[0].price == native_actions::native_ask(MAX_RECENT_SALES as u128 + 1)
+            && sales[0].buyer == sam.address
+            && sales[0].seller == john.address
+            && sales[1].listing_id == MAX_RECENT_SALES.to_string(),
+        here(format!("{sales:?}"), line!(), column!())
+    );
+
+    // Listing 1 was evicted
+    let sales = recent_sales(router, 30)?;
+    ensure!(
+        sales.len() == MAX_RECENT_SALES && sales[MAX_RECENT_SALES - 1].listing_id == "2",
+        here(format!("{sales:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TVL In Denom
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// <X> Only the admin sets conversion rates
+// <X> Assets without a rate are left unconverted
+// <X> Two denom TVL is converted into the common denom once a rate is set
+#[test]
+fn tvl_in_denom() -> Result<(), anyhow::Error> {
+    use crate::query::TvlResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give Sam 100 VALID_NATIVE
+    let router = give_natives(&sam, &mut router);
+
+    let tvl = |router: &App| {
+        let q = crate::msg::QueryMsg::GetTvlInDenom {
+            denom: VALID_NATIVE.to_string(),
+        };
+        let res: TvlResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(res)
+    };
+
+    // John lists 40 JVONE, Sam has a bucket of 10 VALID_NATIVE
+    let create_msg = cw20_base::msg::ExecuteMsg::Send {
+        contract: junovaults.to_string(),
+        amount: Uint128::new(40),
+        msg: to_binary(&crate::msg::ReceiveMsg::CreateListingCw20 {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(10),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        })?,
+    };
+    let res = router.execute_contract(john.address.clone(), jvone.addr(), &create_msg, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = tvl(router)?;
+    ensure!(
+        res == TvlResponse {
+            total: Uint128::new(10),
+            unconverted: coins(40, jvone.addr().to_string()),
+        },
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // 1 JVONE = 2.5 VALID_NATIVE
+    let set_rate = crate::msg::ExecuteMsg::SetConversionRate {
+        from: jvone.addr().to_string(),
+        to: VALID_NATIVE.to_string(),
+        rate: Some(cosmwasm_std::Decimal::from_ratio(5_u128, 2_u128)),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &set_rate, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non-admin set a rate", line!(), column!())
+    );
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &set_rate, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = tvl(router)?;
+    ensure!(
+        res == TvlResponse {
+            total: Uint128::new(110),
+            unconverted: Vec::new(),
+        },
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Remove All Listings
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Unfinalized listings are removed & their funds returned
+// <X> Finalized & frozen listings are skipped
+// <X> Pages continue from the returned cursor
+#[test]
+fn remove_all_listings() -> Result<(), anyhow::Error> {
+    use cw_multi_test::AppResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let starting_balance = router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+
+    // John creates 5 listings of 10 VALID_NATIVE
+    for _ in 0..5 {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Listing 2 is finalized, listing 4 is frozen by the admin
+    let res = native_actions::finalize(router, &junovaults, &john.address, "2", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let freeze_msg = crate::msg::ExecuteMsg::FreezeListing {
+        listing_id: "4".to_string(),
+        frozen: true,
+    };
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &freeze_msg,
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let attr = |res: &AppResponse, key: &str| -> Option<String> {
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+    };
+
+    // First page of 3 removes listings 1 & 3
+    let msg = crate::msg::ExecuteMsg::RemoveAllListings {
+        start_after: None,
+        limit: Some(3),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &msg, &[])?;
+    let next = attr(&res, "next_start_after");
+    ensure!(next == Some("3".to_string()), here(format!("{next:?}"), line!(), column!()));
+    let removed = attr(&res, "removed_count");
+    ensure!(removed == Some("2".to_string()), here(format!("{removed:?}"), line!(), column!()));
+
+    // Rest from the cursor removes listing 5
+    let msg = crate::msg::ExecuteMsg::RemoveAllListings {
+        start_after: next,
+        limit: Some(3),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &msg, &[])?;
+    ensure!(attr(&res, "next_start_after").is_none(), here("No more listings", line!(), column!()));
+    let removed = attr(&res, "removed_count");
+    ensure!(removed == Some("1".to_string()), here(format!("{removed:?}"), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingsByOwner {
+        owner: john.address.to_string(),
+    };
+    let res: crate::query::MultiListingResponse = router.wrap().query_wasm_smart(junovaults, &q)?;
+    let remaining: Vec<String> = res.listings.into_iter().map(|listing| listing.id).collect();
+    ensure!(remaining == vec!["2", "4"], here(format!("{remaining:?}"), line!(), column!()));
+
+    let balance = router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+    ensure!(
+        balance.amount == starting_balance.amount - Uint128::new(20),
+        here(format!("{balance:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Stablecoin Ask Options
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Native ask with 2 cw20 options at par, each option buys a listing
+// <X> Seller receives exactly the cw20 the buyer paid with
+#[test]
+fn stablecoin_ask_options() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    // 50 VALID_NATIVE, or 50 of either stablecoin
+    let at_par = |address: Addr| GenericBalance {
+        native: vec![],
+        cw20: vec![Cw20CoinVerified {
+            address,
+            amount: Uint128::from(50u32),
+        }],
+        nfts: vec![],
+    };
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(50),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: Some(vec![at_par(jvone.addr()), at_par(jvtwo.addr())]),
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    };
+    for listing_id in ["1", "2"] {
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam pays listing 1 with JVONE, Max pays listing 2 with JVTWO
+    for (buyer, token, listing_id, bucket_id) in
+        [(&sam, &jvone, "1", "sam_stable"), (&max, &jvtwo, "2", "max_stable")]
+    {
+        let send = cw20_base::msg::ExecuteMsg::Send {
+            contract: junovaults.to_string(),
+            amount: Uint128::from(50u32),
+            msg: to_binary(&crate::msg::ReceiveMsg::CreateBucketCw20 {
+                bucket_id: bucket_id.to_string(),
+            })?,
+        };
+        let res = router.execute_contract(buyer.address.clone(), token.addr(), &send, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::buy(router, &junovaults, &buyer.address, listing_id, bucket_id);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: john.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse = router.wrap().query_wasm_smart(junovaults, &q)?;
+    let mut received: Vec<Cw20CoinVerified> =
+        res.buckets.into_iter().flat_map(|(_, bucket)| bucket.funds.cw20).collect();
+    received.sort_by(|a, b| a.address.cmp(&b.address));
+    let mut expected = [at_par(jvone.addr()).cw20, at_par(jvtwo.addr()).cw20].concat();
+    expected.sort_by(|a, b| a.address.cmp(&b.address));
+    ensure!(received == expected, here(format!("{received:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Query Errors
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Querying a missing listing returns the ListingNotFound error
+// <X> Querying a missing bucket returns the BucketNotFound error
+#[test]
+fn query_errors() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    let query_err = |router: &App, q: &crate::msg::QueryMsg| {
+        router
+            .wrap()
+            .query_wasm_smart::<cosmwasm_std::Empty>(junovaults.clone(), q)
+            .map_or_else(|e| e.to_string(), |_| "No error".to_string())
+    };
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "9".to_string(),
+    };
+    let err = query_err(router, &q);
+    ensure!(
+        err.ends_with(
+            &crate::ContractError::ListingNotFound {
+                id: "9".to_string()
+            }
+            .to_string()
+        ),
+        here(err, line!(), column!())
+    );
+
+    // John has a bucket, but not this one
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &john.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetBucketShortfall {
+        bucket_owner: john.address.to_string(),
+        bucket_id: "b2".to_string(),
+        listing_id: "9".to_string(),
+    };
+    let err = query_err(router, &q);
+    ensure!(
+        err.ends_with(
+            &crate::ContractError::BucketNotFound {
+                id: "b2".to_string()
+            }
+            .to_string()
+        ),
+        here(err, line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Bucket Offers
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Offered bucket can't be withdrawn while the offer holds
+// <X> Only the seller can accept, the listing sells for the offered bucket under its ask
+// <X> Expired offer can't be accepted & the bucket unlocks
+#[test]
+fn bucket_offers() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 10 VALID_NATIVE twice, asking 50
+    for listing_id in ["1", "2"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(50),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam offers 30 VALID_NATIVE for each listing, for 100 seconds
+    let offer = |bucket_id: &str, listing_id: &str| crate::msg::ExecuteMsg::LockBucketToListing {
+        bucket_id: bucket_id.to_string(),
+        listing_id: listing_id.to_string(),
+        expiration_seconds: Some(100),
+    };
+    for (bucket_id, listing_id) in [("b1", "1"), ("b2", "2")] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            bucket_id,
+            &coins(30, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = router.execute_contract(
+            sam.address.clone(),
+            junovaults.clone(),
+            &offer(bucket_id, listing_id),
+            &[],
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam's offered bucket is locked
+    let remove = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "b1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::BucketOffered {
+                listing_id: "1".to_string()
+            }
+            .to_string(),
+        here("Offered bucket withdrawn", line!(), column!())
+    );
+
+    // Only John can accept
+    let accept = |listing_id: &str, bucket_id: &str| crate::msg::ExecuteMsg::AcceptOffer {
+        listing_id: listing_id.to_string(),
+        buyer: sam.address.to_string(),
+        bucket_id: bucket_id.to_string(),
+    };
+    let res =
+        router.execute_contract(max.address.clone(), junovaults.clone(), &accept("1", "b1"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Max accepted", line!(), column!())
+    );
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &accept("1", "b1"), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.status == "Closed", here(format!("{res:?}"), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: john.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.buckets.len() == 1 && res.buckets[0].1.funds.native == coins(30, VALID_NATIVE),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // The offer on listing 2 lapses
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(100);
+    });
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &accept("2", "b2"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::OfferExpired {}.to_string(),
+        here("Expired offer accepted", line!(), column!())
+    );
+    let remove = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "b2".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Seller Earnings
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Earnings accumulate across sales in the same denom
+// <X> Native & cw20 earnings are kept apart
+#[test]
+fn seller_earnings() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists for 20 VALID_NATIVE, 30 VALID_NATIVE & 40 JVONE
+    let cw20_ask = GenericBalance {
+        native: vec![],
+        cw20: vec![Cw20CoinVerified {
+            address: jvone.addr(),
+            amount: Uint128::from(40u32),
+        }],
+        nfts: vec![],
+    };
+    let asks = [native_actions::native_ask(20), native_actions::native_ask(30), cw20_ask];
+    for (listing_id, ask) in ["1", "2", "3"].into_iter().zip(asks) {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            ask,
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam buys all 3
+    for (bucket_id, amount) in [("b1", 20), ("b2", 30)] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            bucket_id,
+            &coins(amount, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let send = cw20_base::msg::ExecuteMsg::Send {
+        contract: junovaults.to_string(),
+        amount: Uint128::from(40u32),
+        msg: to_binary(&crate::msg::ReceiveMsg::CreateBucketCw20 {
+            bucket_id: "b3".to_string(),
+        })?,
+    };
+    let res = router.execute_contract(sam.address.clone(), jvone.addr(), &send, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    for (listing_id, bucket_id) in [("1", "b1"), ("2", "b2"), ("3", "b3")] {
+        let res = native_actions::buy(router, &junovaults, &sam.address, listing_id, bucket_id);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetSellerEarnings {
+        seller: john.address.to_string(),
+    };
+    let res: crate::query::SellerEarningsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let mut expected = vec![
+        (VALID_NATIVE.to_string(), Uint128::new(50)),
+        (jvone.addr().to_string(), Uint128::new(40)),
+    ];
+    expected.sort();
+    ensure!(res.earnings == expected, here(format!("{res:?}"), line!(), column!()));
+
+    // Sam never sold anything
+    let q = crate::msg::QueryMsg::GetSellerEarnings {
+        seller: sam.address.to_string(),
+    };
+    let res: crate::query::SellerEarningsResponse =
+        router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(res.earnings.is_empty(), here(format!("{res:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Min Active Seconds
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Owner can't remove a listing before min_active_seconds
+// <X> Owner can remove it once min_active_seconds have passed
+// <X> Admin can force remove a listing at any time
+#[test]
+fn min_active_seconds() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: Some(100),
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John creates 2 listings
+    for _ in 0..2 {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Too early for John, not for the admin
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::ListingTooNew {
+                seconds: 100
+            }
+            .to_string(),
+        here("Removed too early", line!(), column!())
+    );
+    let force_remove = crate::msg::ExecuteMsg::ForceRemoveListing {
+        listing_id: "2".to_string(),
+    };
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &force_remove,
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // 1 second short, then removable
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(99);
+    });
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::ListingTooNew {
+                seconds: 1
+            }
+            .to_string(),
+        here("Removed too early", line!(), column!())
+    );
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1);
+    });
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Coin Math
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Adding past Uint128::MAX errors with Overflow instead of panicking
+// <X> Taking more than is held errors with Overflow instead of clamping to 0
+// <X> Coins left at exactly 0 are dropped
+// <X> Buying a Uint128::MAX ask with a taker fee errors with Overflow
+#[test]
+fn coin_math() -> Result<(), anyhow::Error> {
+    use crate::state::GenericBalanceUtil;
+    use crate::utils::{add_juno, sub_balance, sub_juno};
+    use cw20::Balance;
+
+    let overflow = crate::ContractError::Overflow {}.to_string();
+    let cw20_coin = |amount: Uint128| Cw20CoinVerified {
+        address: Addr::unchecked("cw20"),
+        amount,
+    };
+    let balance = |juno: Uint128, cw20: Uint128| GenericBalance {
+        native: vec![coin(juno.u128(), VALID_NATIVE)],
+        cw20: vec![cw20_coin(cw20)],
+        nfts: vec![],
+    };
+
+    // Up to Uint128::MAX is fine, past it is not
+    let near_max = balance(Uint128::MAX - Uint128::one(), Uint128::MAX);
+    let res = add_juno(&near_max, Uint128::one())?;
+    ensure!(
+        res.native == vec![coin(Uint128::MAX.u128(), VALID_NATIVE)],
+        here(format!("{res:?}"), line!(), column!())
+    );
+    let res = add_juno(&res, Uint128::one());
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow.clone()),
+        here("JUNO overflow", line!(), column!())
+    );
+    let mut added = near_max.clone();
+    let res = added.add_tokens(Balance::Cw20(cw20_coin(Uint128::one())));
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow.clone()),
+        here("Cw20 overflow", line!(), column!())
+    );
+
+    // Exactly everything leaves nothing behind
+    let res = sub_juno(&near_max, Uint128::MAX - Uint128::one())?;
+    ensure!(res.native.is_empty(), here(format!("{res:?}"), line!(), column!()));
+    let res = sub_balance(&near_max, &near_max)?;
+    ensure!(
+        res.native.is_empty() && res.cw20.is_empty(),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // Taking more than is held, or a coin that isn't held
+    let res = sub_juno(&near_max, Uint128::MAX);
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow.clone()),
+        here("JUNO underflow", line!(), column!())
+    );
+    let res = sub_balance(&near_max, &balance(Uint128::one(), Uint128::MAX))?;
+    ensure!(
+        res.native == vec![coin(Uint128::MAX.u128() - 2, VALID_NATIVE)] && res.cw20.is_empty(),
+        here(format!("{res:?}"), line!(), column!())
+    );
+    let res = sub_balance(&balance(Uint128::one(), Uint128::one()), &near_max);
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow.clone()),
+        here("Balance underflow", line!(), column!())
+    );
+    let other_denom = GenericBalance {
+        native: coins(1, "uother"),
+        cw20: vec![],
+        nfts: vec![],
+    };
+    let res = sub_balance(&near_max, &other_denom);
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow.clone()),
+        here("Coin not held", line!(), column!())
+    );
+
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // 2% taker
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: Some(200),
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John asks Uint128::MAX, so the ask plus the taker fee can't be represented
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(Uint128::MAX.u128()),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(native_actions::err_string(res) == overflow, here("Buy overflow", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Auto Swap Proceeds
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Non-admin can't set the swap router
+// <X> Proceeds of an auto_swap_to listing are swapped & sent straight to the seller
+// <X> A failed swap leaves the proceeds in the seller's bucket
+#[test]
+fn auto_swap_proceeds() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let swap_router_id = router.store_code(create_contract::swap_router_contract());
+    let swap_router = router.instantiate_contract(
+        swap_router_id,
+        contract_admin.address.clone(),
+        &Empty {},
+        &[],
+        "swap_router",
+        None,
+    )?;
+
+    // Give John & Sam 100 VALID_NATIVE, the router 100 uusdc
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    router.init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &swap_router, coins(100, "uusdc")).unwrap()
+    });
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: Some(swap_router.to_string()),
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non-admin set router", line!(), column!())
+    );
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John lists twice, once into uusdc & once into a denom the router can't pay
+    for auto_swap_to in ["uusdc", "unknown"] {
+        let create = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(10),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire: None,
+                private: None,
+                ask_options: None,
+                auto_swap_to: Some(auto_swap_to.to_string()),
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(5, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    for listing_id in ["1", "2"] {
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam buys the uusdc listing, John gets paid 10 uusdc & keeps no bucket
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let usdc = router.wrap().query_balance(&john.address, "uusdc")?;
+    ensure!(usdc.amount == Uint128::from(10u32), here(format!("{usdc:?}"), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: john.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.buckets.is_empty(), here("Bucket kept", line!(), column!()));
+
+    // Sam buys the other one, the swap fails & John's bucket holds the VALID_NATIVE
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b2",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "2", "b2")?;
+    ensure!(
+        res.events.iter().any(|e| e.attributes.iter().any(|a| a.value == "swap_failed")),
+        here("No swap_failed", line!(), column!())
+    );
+
+    let res: crate::query::GetBucketsResponse = router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(
+        res.buckets.len() == 1 && res.buckets[0].1.funds.native == coins(10, VALID_NATIVE),
+        here(format!("{:?}", res.buckets), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Whitelist Until
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Non-whitelisted buyer can't buy during the presale
+// <X> Non-whitelisted buyer can buy once the presale is over
+// <X> whitelist_until is returned by GetListingInfo
+#[test]
+fn whitelist_until() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John's presale for Max runs 100 seconds
+    let presale_end = router.block_info().time.plus_seconds(100);
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(10),
+            whitelisted_buyer: Some(max.address.to_string()),
+            whitelist_until: Some(presale_end),
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create,
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.whitelist_until == Some(presale_end), here(format!("{res:?}"), line!(), column!()));
+
+    // Sam isn't whitelisted during the presale
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NotWhitelisted {}.to_string(),
+        here("Bought during presale", line!(), column!())
+    );
+
+    // Anyone can buy once it's over
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(100);
+    });
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Reconciliation
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Listings & buckets are tracked, the balance matches
+// <X> A direct transfer to the contract shows up as surplus
+#[test]
+fn reconciliation() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 10, Sam fills a bucket with 20
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let q = crate::msg::QueryMsg::GetReconciliation {
+        denom: VALID_NATIVE.to_string(),
+    };
+    let res: crate::query::ReconciliationResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res == crate::query::ReconciliationResponse {
+            balance: Uint128::from(30u32),
+            tracked: Uint128::from(30u32),
+            surplus: Uint128::zero(),
+            deficit: Uint128::zero(),
+        },
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // Sam sends 7 straight to the contract
+    router.send_tokens(sam.address.clone(), junovaults.clone(), &coins(7, VALID_NATIVE))?;
+    let res: crate::query::ReconciliationResponse =
+        router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(
+        res == crate::query::ReconciliationResponse {
+            balance: Uint128::from(37u32),
+            tracked: Uint128::from(30u32),
+            surplus: Uint128::from(7u32),
+            deficit: Uint128::zero(),
+        },
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Max NFTs Per Collection Per Listing
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Listing can hold up to the max from a collection
+// <X> Adding past the max errors with TooManyNftsFromCollection
+// <X> NFTs from other collections don't count towards it
+// <X> Creating a listing past the max errors too
+#[test]
+fn max_nfts_per_collection_per_listing() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let set_max = |max_nfts: u32| crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: Some(max_nfts),
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &set_max(1),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let send_nft = |token_id: &str, msg: crate::msg::ReceiveNftMsg| -> anyhow::Result<_> {
+        let send: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+            cw721_base::msg::ExecuteMsg::SendNft {
+                contract: junovaults.to_string(),
+                token_id: token_id.to_string(),
+                msg: to_binary(&msg)?,
+            };
+        Ok(send)
+    };
+    let create = || crate::msg::ReceiveNftMsg::CreateListingCw721 {
+        create_msg: create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None),
+    };
+    let add = || crate::msg::ReceiveNftMsg::AddToListingCw721 {
+        listing_id: "1".to_string(),
+    };
+
+    // John lists neonpeepz 1, at the max
+    let res = router.execute_contract(
+        john.address.clone(),
+        neonpeepz.addr(),
+        &send_nft("1", create())?,
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // A 2nd neonpeepz is past it
+    let too_many = crate::ContractError::TooManyNftsFromCollection {
+        collection: neonpeepz.addr().to_string(),
+        max: 1,
+    }
+    .to_string();
+    let res = router.execute_contract(
+        john.address.clone(),
+        neonpeepz.addr(),
+        &send_nft("2", add())?,
+        &[],
+    );
+    ensure!(
+        native_actions::err_string(res) == too_many,
+        here("Added past max", line!(), column!())
+    );
+
+    // A shittykittyz doesn't count towards it
+    let res = router.execute_contract(
+        john.address.clone(),
+        shittykittyz.addr(),
+        &send_nft("1", add())?,
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // With a max of 0 no listing can be created from the collection
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &set_max(0),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(
+        john.address.clone(),
+        neonpeepz.addr(),
+        &send_nft("2", create())?,
+        &[],
+    );
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::TooManyNftsFromCollection {
+                collection: neonpeepz.addr().to_string(),
+                max: 0,
+            }
+            .to_string(),
+        here("Created past max", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Trending Listings
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Listings are ordered by interest count, most first
+// <X> Listings that aren't finalized are left out
+// <X> Results are limited
+#[test]
+fn trending_listings() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    // John creates 4 listings, only the first 3 are finalized
+    for listing_id in ["1", "2", "3", "4"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        if listing_id != "4" {
+            let res =
+                native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+            ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        }
+    }
+
+    // Listing 2 gets 2 interests, 3 gets 1, the unfinalized 4 gets 3
+    let interests =
+        [(&sam, "2"), (&max, "2"), (&sam, "3"), (&sam, "4"), (&max, "4"), (&contract_admin, "4")];
+    for (user, listing_id) in interests {
+        let msg = crate::msg::ExecuteMsg::ExpressInterest {
+            listing_id: listing_id.to_string(),
+        };
+        let res = router.execute_contract(user.address.clone(), junovaults.clone(), &msg, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetTrendingListings {
+        limit: None,
+    };
+    let res: crate::query::TrendingListingsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let trending: Vec<(String, u64)> =
+        res.listings.into_iter().map(|entry| (entry.listing.id, entry.interest)).collect();
+    ensure!(
+        trending == vec![("2".to_string(), 2), ("3".to_string(), 1), ("1".to_string(), 0)],
+        here(format!("{trending:?}"), line!(), column!())
+    );
+
+    let q = crate::msg::QueryMsg::GetTrendingListings {
+        limit: Some(1),
+    };
+    let res: crate::query::TrendingListingsResponse =
+        router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(
+        res.listings.len() == 1 && res.listings[0].listing.id == "2",
+        here(format!("{:?}", res.listings), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Attribute Schema Version
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Instantiate response carries attr_schema_version
+// <X> Execute & reply responses carry attr_schema_version
+#[test]
+fn attr_schema_version() -> Result<(), anyhow::Error> {
+    use crate::contract::ATTR_SCHEMA_VERSION;
+    use cosmwasm_std::{Event, WasmMsg};
+    use cw20::Cw20ExecuteMsg;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Every wasm event the contract emitted, with whether it has the version
+    let versioned = |events: &[Event], contract: &str| -> Vec<bool> {
+        events
+            .iter()
+            .filter(|e| {
+                e.ty == "wasm"
+                    && e.attributes.iter().any(|a| a.key == "_contract_addr" && a.value == contract)
+            })
+            .map(|e| {
+                e.attributes
+                    .iter()
+                    .any(|a| a.key == "attr_schema_version" && a.value == ATTR_SCHEMA_VERSION)
+            })
+            .collect()
+    };
+
+    // Instantiate a 2nd vault to see its response
+    let jv_id = router.store_code(create_contract::junovaults_contract());
+    let instantiate = WasmMsg::Instantiate {
+        admin: None,
+        code_id: jv_id,
+        msg: to_binary(&InstantiateMsg {
+            admin: None,
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        })?,
+        funds: Vec::new(),
+        label: "jv2".to_string(),
+    };
+    let res = router.execute(contract_admin.address.clone(), instantiate.into())?;
+    let event = res.events.iter().find(|e| e.ty == "instantiate").expect("instantiate event");
+    let new_vault = &event.attributes[0].value;
+    ensure!(
+        versioned(&res.events, new_vault) == vec![true],
+        here(format!("{:?}", res.events), line!(), column!())
+    );
+
+    // Sam lists through an allowance, so the listing is created in a reply
+    let approve = Cw20ExecuteMsg::IncreaseAllowance {
+        spender: junovaults.to_string(),
+        amount: Uint128::from(10u32),
+        expires: None,
+    };
+    let res = router.execute_contract(sam.address.clone(), jvone.addr(), &approve, &[]);
+    ensure!(res.is_ok(), here("Sam approve", line!(), column!()));
+
+    let mut create_msg = create_valid_listing::create_listing_msg(jvone.addr(), jvone.addr(), None);
+    create_msg.ask = native_actions::native_ask(10);
+    let create = crate::msg::ExecuteMsg::CreateListingCw20ViaAllowance {
+        create_msg,
+        token: jvone.addr().to_string(),
+        amount: Uint128::from(10u32),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &create, &[])?;
+    ensure!(
+        versioned(&res.events, junovaults.as_str()) == vec![true, true],
+        here(format!("{:?}", res.events), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Withdraw Purchased Partial
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Only the claimant can withdraw
+// <X> Can't withdraw more than the listing holds
+// <X> Half is withdrawn, the rest stays in the listing
+// <X> The rest is withdrawn & the listing is archived
+#[test]
+fn withdraw_purchased_partial() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 10 VALID_NATIVE & neonpeepz 1, Sam buys it
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let add_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: junovaults.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
+                listing_id: "1".to_string(),
+            })?,
+        };
+    let res = router.execute_contract(john.address.clone(), neonpeepz.addr(), &add_nft, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let withdraw =
+        |native: u128, nfts: Vec<Nft>| crate::msg::ExecuteMsg::WithdrawPurchasedPartial {
+            listing_id: "1".to_string(),
+            assets: GenericBalance {
+                native: coins(native, VALID_NATIVE),
+                cw20: Vec::new(),
+                nfts,
+            },
+        };
+    let nft = Nft {
+        contract_address: neonpeepz.addr(),
+        token_id: "1".to_string(),
+    };
+
+    // John isn't the claimant, Sam can't take 11
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &withdraw(5, Vec::new()),
+        &[],
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("John withdrew", line!(), column!())
+    );
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &withdraw(11, Vec::new()),
+        &[],
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NotInListing {}.to_string(),
+        here("Withdrew too much", line!(), column!())
+    );
+
+    // Sam takes half the VALID_NATIVE now
+    let start = router.wrap().query_balance(&sam.address, VALID_NATIVE)?.amount;
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &withdraw(5, Vec::new()),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let balance = router.wrap().query_balance(&sam.address, VALID_NATIVE)?;
+    ensure!(
+        balance.amount == start + Uint128::from(5u32),
+        here(format!("{balance:?}"), line!(), column!())
+    );
+
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.for_sale == vec![(VALID_NATIVE.to_string(), 5), (neonpeepz.addr().to_string(), 1)],
+        here(format!("{:?}", res.for_sale), line!(), column!())
+    );
+
+    // Then the rest
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &withdraw(5, vec![nft]),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let balance = router.wrap().query_balance(&sam.address, VALID_NATIVE)?;
+    ensure!(
+        balance.amount == start + Uint128::from(10u32),
+        here(format!("{balance:?}"), line!(), column!())
+    );
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    ensure!(owner == sam.address, here(owner, line!(), column!()));
+
+    let res: Result<crate::query::ListingInfoResponse, _> =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q);
+    ensure!(res.is_err(), here("Listing not archived", line!(), column!()));
+    let q = crate::msg::QueryMsg::GetArchivedListing {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ArchivedListingResponse =
+        router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(res.listing.id == "1", here(format!("{res:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Restrict Creators
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Anyone can create listings while unrestricted
+// <X> Non-admin can't allow creators
+// <X> Restricted mode denies creators not on the allowlist
+// <X> Allowlisted creators can create, until they're removed
+#[test]
+fn restrict_creators() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let create = |router: &mut App, creator: &Addr| {
+        native_actions::create_listing(
+            router,
+            &junovaults,
+            creator,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        )
+    };
+    let not_allowed = crate::ContractError::NotAllowedToCreate {}.to_string();
+
+    // Unrestricted by default
+    let res = create(router, &sam.address);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let allow = |allowed: bool| crate::msg::ExecuteMsg::SetCreatorAllowed {
+        address: john.address.to_string(),
+        allowed,
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &allow(true), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Non-admin allowed", line!(), column!())
+    );
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &allow(true),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: Some(true),
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam isn't allowlisted, John is
+    let res = create(router, &sam.address);
+    ensure!(
+        native_actions::err_string(res) == not_allowed,
+        here("Sam created", line!(), column!())
+    );
+    let res = create(router, &john.address);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Once removed John is denied too
+    let res = router.execute_contract(
+        contract_admin.address.clone(),
+        junovaults.clone(),
+        &allow(false),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = create(router, &john.address);
+    ensure!(
+        native_actions::err_string(res) == not_allowed,
+        here("John created", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Sealed-Bid Auction
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Auctioned listing can't be bought at its ask
+// <X> Bids are committed, a second bid & a reveal during commit fail
+// <X> Reveal must match the commitment
+// <X> Can't settle before the reveal phase ends
+// <X> Highest revealed bid wins, the loser is refunded & the seller is paid
+#[test]
+fn sealed_bid_auction() -> Result<(), anyhow::Error> {
+    use cosmwasm_std::HexBinary;
+    use sha2::{Digest, Sha256};
+
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+    let balance = |router: &App, user: &Addr| -> Uint128 {
+        router.wrap().query_balance(user.to_string(), VALID_NATIVE).unwrap().amount
+    };
+
+    // John lists 10 VALID_NATIVE with a reserve of 5 & auctions it
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let start = crate::msg::ExecuteMsg::StartSealedAuction {
+        listing_id: "1".to_string(),
+        commit_seconds: 1000,
+        reveal_seconds: 1000,
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &start, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let john_start = balance(router, &john.address);
+    let sam_start = balance(router, &sam.address);
+
+    // Sam can't buy it at its ask
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::SealedAuction {}.to_string(),
+        here("Auction bought at its ask", line!(), column!())
+    );
+
+    // Sam bids 7 escrowing 20, Max bids 8 escrowing 8
+    let commitment = |bidder: &Addr, amount: u128, nonce: &str| {
+        HexBinary::from(Sha256::digest(format!("{bidder}:{amount}:{nonce}").as_bytes()).to_vec())
+    };
+    let commit = |commitment: HexBinary| crate::msg::ExecuteMsg::CommitBid {
+        listing_id: "1".to_string(),
+        commitment,
+    };
+    let reveal = |amount: u128, nonce: &str| crate::msg::ExecuteMsg::RevealBid {
+        listing_id: "1".to_string(),
+        amount: Uint128::new(amount),
+        nonce: nonce.to_string(),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &commit(commitment(&sam.address, 7, "sam")),
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &commit(commitment(&sam.address, 9, "sam")),
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::AlreadyBid {}.to_string(),
+        here("Second bid committed", line!(), column!())
+    );
+    let res = router.execute_contract(
+        max.address.clone(),
+        junovaults.clone(),
+        &commit(commitment(&max.address, 8, "max")),
+        &coins(8, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Can't reveal while bids are being committed
+    let res =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &reveal(7, "sam"), &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::AuctionPhase {
+                phase: "reveal".to_string()
+            }
+            .to_string(),
+        here("Revealed during commit", line!(), column!())
+    );
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1000);
+    });
+
+    // Sam's reveal must match the commitment
+    let res =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &reveal(9, "sam"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::InvalidReveal {}.to_string(),
+        here("Mismatched reveal accepted", line!(), column!())
+    );
+    for (bidder, amount, nonce) in [(&sam.address, 7, "sam"), (&max.address, 8, "max")] {
+        let res = router.execute_contract(
+            bidder.clone(),
+            junovaults.clone(),
+            &reveal(amount, nonce),
+            &[],
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let q = crate::msg::QueryMsg::GetSealedAuction {
+        listing_id: "1".to_string(),
+    };
+    let auction: crate::query::SealedAuctionResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        auction.bids == 2 && auction.revealed == 2,
+        here(format!("{auction:?}"), line!(), column!())
+    );
+
+    // Can't settle before the reveal phase ends
+    let settle = crate::msg::ExecuteMsg::SettleAuction {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &settle, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::AuctionPhase {
+                phase: "settlement".to_string()
+            }
+            .to_string(),
+        here("Settled during reveal", line!(), column!())
+    );
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1000);
+    });
+
+    // Max wins with 8, Sam gets the 20 escrowed back & John is paid 8
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &settle, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &sam.address) == sam_start - Uint128::new(5),
+        here("Losing bid not refunded", line!(), column!())
+    );
+    ensure!(
+        balance(router, &john.address) == john_start + Uint128::new(8),
+        here("Seller not paid", line!(), column!())
+    );
+    let res = router
+        .wrap()
+        .query_wasm_smart::<crate::query::SealedAuctionResponse>(junovaults.clone(), &q);
+    ensure!(res.is_err(), here("Auction not removed", line!(), column!()));
+
+    // Max withdraws the 10 VALID_NATIVE
+    let max_before = balance(router, &max.address);
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &max.address) == max_before + Uint128::new(10),
+        here("Winner didn't receive the listing", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Units Available
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Units that don't split for_sale evenly can't be finalized
+// <X> Each buy sends the buyer 1 unit & leaves the listing up
+// <X> The last unit closes & archives the listing
+#[test]
+fn units_available() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+    let balance = |router: &App, user: &Addr| -> Uint128 {
+        router.wrap().query_balance(user.to_string(), VALID_NATIVE).unwrap().amount
+    };
+    let listing = |router: &App| -> Result<crate::state::Listing, anyhow::Error> {
+        let q = crate::msg::QueryMsg::GetListingsByOwner {
+            owner: john.address.to_string(),
+        };
+        let res: crate::query::MultiListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok(res.listings[0].clone())
+    };
+
+    // John lists 30 VALID_NATIVE at 5 a unit in 4 units, which can't be finalized
+    let create = |units: u32| crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(5),
+            units: Some(units),
+            on_sale_callback: None,
+            ..create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None)
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create(4),
+        &coins(30, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::InvalidUnits {}.to_string(),
+        here("Uneven units finalized", line!(), column!())
+    );
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John lists 30 VALID_NATIVE in 3 units
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create(3),
+        &coins(30, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "2", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam & Max each buy a unit, getting 10 VALID_NATIVE right away
+    for (buyer, bucket_id) in [(&sam.address, "b1"), (&max.address, "b2")] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            buyer,
+            bucket_id,
+            &coins(5, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let before = balance(router, buyer);
+        let res = native_actions::buy(router, &junovaults, buyer, "2", bucket_id);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        ensure!(
+            balance(router, buyer) == before + Uint128::new(10),
+            here("Unit not received", line!(), column!())
+        );
+    }
+    let unsold = listing(router)?;
+    ensure!(
+        unsold.units_available == Some(1)
+            && unsold.claimant.is_none()
+            && unsold.for_sale.native == coins(10, VALID_NATIVE),
+        here(format!("{unsold:?}"), line!(), column!())
+    );
+
+    // Sam buys the last unit, closing the listing
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b3",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let before = balance(router, &sam.address);
+    let res = native_actions::buy(router, &junovaults, &sam.address, "2", "b3");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &sam.address) == before + Uint128::new(10),
+        here("Last unit not received", line!(), column!())
+    );
+    let q = crate::msg::QueryMsg::GetArchivedListing {
+        listing_id: "2".to_string(),
+    };
+    let sold: crate::query::ArchivedListingResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        sold.listing.units_available == Some(0)
+            && sold.listing.status == crate::state::Status::Closed
+            && sold.listing.claimant == Some(sam.address.clone()),
+        here(format!("{:?}", sold.listing), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Fee Holiday
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Bucket holding only the ask buys during the holiday, no fees are collected
+// <X> After the holiday the same bucket is short the taker fee
+// <X> Both fees are collected again after the holiday
+#[test]
+fn fee_holiday() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let balance = |router: &App, address: &Addr| {
+        router.wrap().query_balance(address.to_string(), VALID_NATIVE).map(|c| c.amount.u128())
+    };
+
+    // 1% maker & 2% taker to Max, waived for the next 1000 seconds
+    let holiday_until = router.block_info().time.plus_seconds(1000);
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: Some(100),
+            taker_fee_bps: Some(200),
+            fee_collector: Some(max.address.to_string()),
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: Some(holiday_until),
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John lists 2 listings asking 1000 VALID_NATIVE
+    for listing_id in ["1", "2"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(1_000),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 2000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam buys listing 1 with only the ask, John's proceeds aren't cut
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let max_before = balance(router, &max.address)?;
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let max_after = balance(router, &max.address)?;
+    ensure!(max_after == max_before, here(max_after - max_before, line!(), column!()));
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: john.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.buckets[0].1.funds.native == coins(1_000, VALID_NATIVE),
+        here(format!("{:?}", res.buckets), line!(), column!())
+    );
+
+    // Holiday ends
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1000);
+    });
+
+    // The ask alone no longer covers listing 2, the taker fee is due again
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b2",
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "2", "b2");
+    ensure!(res.is_err(), here("Missing the taker fee", line!(), column!()));
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b3",
+        &coins(1_020, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "2", "b3");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let max_after = balance(router, &max.address)?;
+    ensure!(max_after - max_before == 30, here(max_after - max_before, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// My Whitelisted Listings
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Open filter returns only listings being prepared
+// <X> Finalized filter returns only listings that can still be bought
+// <X> Expired filter returns only finalized listings past their expiry
+// <X> Listings not whitelisting the address are left out
+// <X> Pages by listing ID
+// <X> Removed listing drops out
+#[test]
+fn my_whitelisted_listings() -> Result<(), anyhow::Error> {
+    use crate::msg::ListingStage;
+
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    // John lists 1 to 4 whitelisting Sam & 5 whitelisting Max
+    for whitelisted in [&sam, &sam, &sam, &sam, &max] {
+        let create = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(5),
+                ..create_valid_listing::create_listing_msg(
+                    jvone.addr(),
+                    neonpeepz.addr(),
+                    Some(whitelisted.address.clone()),
+                )
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // 2 & 5 are finalized for 10000 seconds, 3 & 4 for 1000
+    for (listing_id, seconds) in [("2", 10_000), ("3", 1_000), ("4", 1_000), ("5", 10_000)] {
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, seconds);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 400;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(2000);
+    });
+
+    let ids = |router: &App,
+               address: &Addr,
+               status: ListingStage,
+               start_after: Option<&str>,
+               limit: Option<u32>|
+     -> Result<Vec<String>, anyhow::Error> {
+        let q = crate::msg::QueryMsg::GetMyWhitelistedListings {
+            address: address.to_string(),
+            status,
+            start_after: start_after.map(str::to_string),
+            limit,
+        };
+        let res: crate::query::MultiListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok(res.listings.into_iter().map(|listing| listing.id).collect())
+    };
+
+    let open = ids(router, &sam.address, ListingStage::Open, None, None)?;
+    ensure!(open == vec!["1"], here(format!("{open:?}"), line!(), column!()));
+    let finalized = ids(router, &sam.address, ListingStage::Finalized, None, None)?;
+    ensure!(finalized == vec!["2"], here(format!("{finalized:?}"), line!(), column!()));
+    let expired = ids(router, &sam.address, ListingStage::Expired, None, None)?;
+    ensure!(expired == vec!["3", "4"], here(format!("{expired:?}"), line!(), column!()));
+    let finalized = ids(router, &max.address, ListingStage::Finalized, None, None)?;
+    ensure!(finalized == vec!["5"], here(format!("{finalized:?}"), line!(), column!()));
+
+    // Expired listings 1 at a time
+    let page = ids(router, &sam.address, ListingStage::Expired, None, Some(1))?;
+    ensure!(page == vec!["3"], here(format!("{page:?}"), line!(), column!()));
+    let page = ids(router, &sam.address, ListingStage::Expired, Some("3"), Some(1))?;
+    ensure!(page == vec!["4"], here(format!("{page:?}"), line!(), column!()));
+    let page = ids(router, &sam.address, ListingStage::Expired, Some("4"), Some(1))?;
+    ensure!(page.is_empty(), here(format!("{page:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Swap Listings
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Can't propose a swap of another owner's listing
+// <X> Can't propose a swap of listings that aren't finalized
+// <X> Rejected proposal can't be accepted
+// <X> Only the counter listing's owner can accept
+// <X> Accepted swap sends each owner the other's assets & archives both listings
+#[test]
+fn swap_listings() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    // John lists 10 VALID_NATIVE, Sam lists NeonPeepz #3
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: junovaults.to_string(),
+            token_id: "3".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+                create_msg: CreateListingMsg {
+                    ask: native_actions::native_ask(10),
+                    whitelisted_buyer: None,
+                    whitelist_until: None,
+                    payout_address: None,
+                    min_buyer_purchases: None,
+                    vesting_seconds: None,
+                    on_expire: None,
+                    private: None,
+                    ask_options: None,
+                    auto_swap_to: None,
+                    units: None,
+                    on_sale_callback: None,
+                    min_net_proceeds: None,
+                    max_offers: None,
+                },
+            })?,
+        };
+    let res = router.execute_contract(sam.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let propose =
+        |listing_id: &str, counter_listing_id: &str| crate::msg::ExecuteMsg::ProposeSwap {
+            listing_id: listing_id.to_string(),
+            counter_listing_id: counter_listing_id.to_string(),
+        };
+    let accept = crate::msg::ExecuteMsg::AcceptSwap {
+        proposer_listing_id: "1".to_string(),
+        listing_id: "2".to_string(),
+    };
+
+    // Neither listing is finalized, their contents could still change
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &propose("1", "2"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NotSwappable {}.to_string(),
+        here("Proposed a listing being prepared", line!(), column!())
+    );
+    for (owner, listing_id) in [(&john, "1"), (&sam, "2")] {
+        let res = native_actions::finalize(router, &junovaults, &owner.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Max can't offer John's listing
+    let res =
+        router.execute_contract(max.address.clone(), junovaults.clone(), &propose("1", "2"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Proposed another owner's listing", line!(), column!())
+    );
+
+    // John proposes, Sam rejects
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &propose("1", "2"), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let reject = crate::msg::ExecuteMsg::RejectSwap {
+        proposer_listing_id: "1".to_string(),
+        listing_id: "2".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &reject, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &accept, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NoSwapProposal {}.to_string(),
+        here("Rejected swap accepted", line!(), column!())
+    );
+
+    // John proposes again, only Sam can accept
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &propose("1", "2"), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    for user in [&john, &max] {
+        let res = router.execute_contract(user.address.clone(), junovaults.clone(), &accept, &[]);
+        ensure!(
+            native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+            here("Accepted by a non owner", line!(), column!())
+        );
+    }
+
+    let sam_before = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?.amount;
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &accept, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam has the 10 VALID_NATIVE, John has NeonPeepz #3
+    let sam_after = router.wrap().query_balance(sam.address.to_string(), VALID_NATIVE)?.amount;
+    ensure!(
+        sam_after == sam_before + Uint128::new(10),
+        here(format!("{sam_before} -> {sam_after}"), line!(), column!())
+    );
+    let owner = neonpeepz.owner_of(&router.wrap(), "3".to_string(), false)?.owner;
+    ensure!(owner == john.address, here(owner, line!(), column!()));
+
+    for (listing_id, claimant) in [("1", &sam.address), ("2", &john.address)] {
+        let q = crate::msg::QueryMsg::GetArchivedListing {
+            listing_id: listing_id.to_string(),
+        };
+        let res: crate::query::ArchivedListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        ensure!(
+            res.listing.claimant.as_ref() == Some(claimant),
+            here(format!("{:?}", res.listing), line!(), column!())
+        );
+    }
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Reveal Deposit Slash
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Slash above the whole escrow is rejected
+// <X> Bidder who never reveals is refunded their escrow less the slash
+// <X> Seller is paid the winning bid & the slash
+#[test]
+fn reveal_deposit_slash() -> Result<(), anyhow::Error> {
+    use cosmwasm_std::HexBinary;
+    use sha2::{Digest, Sha256};
+
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+    let balance = |router: &App, user: &Addr| -> Uint128 {
+        router.wrap().query_balance(user.to_string(), VALID_NATIVE).unwrap().amount
+    };
+
+    let set_slash = |router: &mut App, reveal_deposit_slash_bps: u64| {
+        let update = crate::msg::ExecuteMsg::UpdateConfig {
+            config_msg: crate::msg::UpdateConfigMsg {
+                refund_grace_seconds: None,
+                auto_match: None,
+                rounding: None,
+                validate_cw20_asks: None,
+                max_transfers_per_buy: None,
+                maker_fee_bps: None,
+                taker_fee_bps: None,
+                fee_collector: None,
+                admin_timelock_seconds: None,
+                listing_bond: None,
+                min_market_ask_value: None,
+                default_listing_ttl: None,
+                referral_bps: None,
+                max_page_size: None,
+                min_active_seconds: None,
+                swap_router: None,
+                max_nfts_per_collection_per_listing: None,
+                restrict_creators: None,
+                fee_holiday_until: None,
+                reveal_deposit_slash_bps: Some(reveal_deposit_slash_bps),
+                buy_cooldown_seconds: None,
+                min_offer_value: None,
+                max_offers: None,
+                dust_threshold: None,
+            },
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[])
+    };
+    let res = set_slash(router, 10_001);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::SlashTooHigh {
+                max: 10_000
+            }
+            .to_string(),
+        here("Slash above the escrow", line!(), column!())
+    );
+
+    // 25% of an unrevealed escrow goes to the seller
+    let res = set_slash(router, 2_500);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John auctions 10 VALID_NATIVE with a reserve of 5
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let start = crate::msg::ExecuteMsg::StartSealedAuction {
+        listing_id: "1".to_string(),
+        commit_seconds: 1000,
+        reveal_seconds: 1000,
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &start, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let john_start = balance(router, &john.address);
+    let sam_start = balance(router, &sam.address);
+
+    // Sam bids 7 escrowing 20, Max bids 8 escrowing 8
+    for (bidder, amount, escrow) in [(&sam.address, 7, 20), (&max.address, 8, 8)] {
+        let commit = crate::msg::ExecuteMsg::CommitBid {
+            listing_id: "1".to_string(),
+            commitment: HexBinary::from(
+                Sha256::digest(format!("{bidder}:{amount}:nonce").as_bytes()).to_vec(),
+            ),
+        };
+        let res = router.execute_contract(
+            bidder.clone(),
+            junovaults.clone(),
+            &commit,
+            &coins(escrow, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Only Max reveals
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1000);
+    });
+    let reveal = crate::msg::ExecuteMsg::RevealBid {
+        listing_id: "1".to_string(),
+        amount: Uint128::new(8),
+        nonce: "nonce".to_string(),
+    };
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &reveal, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 200;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(1000);
+    });
+
+    // Sam gets 15 of the 20 back, John is paid 8 & the 5 slashed
+    let settle = crate::msg::ExecuteMsg::SettleAuction {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &settle, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &sam.address) == sam_start - Uint128::new(5),
+        here(balance(router, &sam.address), line!(), column!())
+    );
+    ensure!(
+        balance(router, &john.address) == john_start + Uint128::new(13),
+        here(balance(router, &john.address), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Active Denoms
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Denoms appear once a listing holds or asks for them
+// <X> Denom stays while another listing still uses it
+// <X> Denoms drop off when their listings are removed or bought
+#[test]
+fn active_denoms() -> Result<(), anyhow::Error> {
+    use crate::query::ActiveDenomsResponse;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let active = |router: &App| -> Result<Vec<String>, anyhow::Error> {
+        let q = crate::msg::QueryMsg::GetActiveDenoms {
+            start_after: None,
+            limit: None,
+        };
+        let res: ActiveDenomsResponse = router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok(res.denoms)
+    };
+    ensure!(active(router)?.is_empty(), here("Denoms before listing", line!(), column!()));
+
+    // John lists VALID_NATIVE for VALID_NATIVE, then VALID_NATIVE for JVONE
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let denoms = active(router)?;
+    ensure!(denoms == vec![VALID_NATIVE], here(format!("{denoms:?}"), line!(), column!()));
+
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: GenericBalance {
+                native: Vec::new(),
+                cw20: vec![Cw20CoinVerified {
+                    address: jvone.addr(),
+                    amount: Uint128::new(5),
+                }],
+                nfts: Vec::new(),
+            },
+            ..create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None)
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create,
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let denoms = active(router)?;
+    ensure!(
+        denoms == vec![jvone.addr().to_string(), VALID_NATIVE.to_string()],
+        here(format!("{denoms:?}"), line!(), column!())
+    );
+
+    // John removes listing 2, VALID_NATIVE is still in listing 1
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let denoms = active(router)?;
+    ensure!(denoms == vec![VALID_NATIVE], here(format!("{denoms:?}"), line!(), column!()));
+
+    // Sam buys listing 1
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let denoms = active(router)?;
+    ensure!(denoms.is_empty(), here(format!("{denoms:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Clone Listing
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Only the source owner can clone it
+// <X> Clone has the source's terms & the new assets, & is being prepared
+// <X> Archived listings can be cloned
+#[test]
+fn clone_listing() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 10 VALID_NATIVE, private & whitelisting Sam, paid out to Max
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            payout_address: Some(max.address.to_string()),
+            private: Some(true),
+            min_buyer_purchases: Some(2),
+            ..create_valid_listing::create_listing_msg(
+                jvone.addr(),
+                neonpeepz.addr(),
+                Some(sam.address.clone()),
+            )
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create,
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let clone = crate::msg::ExecuteMsg::CloneListing {
+        source_listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &clone,
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Cloned another owner's listing", line!(), column!())
+    );
+
+    let listings = |router: &App| -> Result<Vec<crate::state::Listing>, anyhow::Error> {
+        let q = crate::msg::QueryMsg::GetListingsByOwner {
+            owner: john.address.to_string(),
+        };
+        let res: crate::query::MultiListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok(res.listings)
+    };
+    let same_terms = |a: &crate::state::Listing, b: &crate::state::Listing| {
+        a.ask == b.ask
+            && a.ask_options == b.ask_options
+            && a.whitelisted_buyers == b.whitelisted_buyers
+            && a.payout_address == b.payout_address
+            && a.min_buyer_purchases == b.min_buyer_purchases
+            && a.private == b.private
+            && a.on_expire == b.on_expire
+    };
+
+    // John clones listing 1 with 20 VALID_NATIVE
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &clone,
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let all = listings(router)?;
+    let (source, cloned) = (&all[0], &all[1]);
+    ensure!(
+        cloned.id == "2"
+            && same_terms(source, cloned)
+            && cloned.for_sale.native == coins(20, VALID_NATIVE)
+            && cloned.status == crate::state::Status::BeingPrepared,
+        here(format!("{cloned:?}"), line!(), column!())
+    );
+
+    // John removes listing 1, then clones it from the archive
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &clone,
+        &coins(30, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let all = listings(router)?;
+    let (first_clone, cloned) = (&all[0], &all[1]);
+    ensure!(
+        cloned.id == "3"
+            && same_terms(first_clone, cloned)
+            && cloned.for_sale.native == coins(30, VALID_NATIVE),
+        here(format!("{cloned:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Buy Cooldown
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Buying again within the cooldown is rejected
+// <X> Cooldown is per address
+// <X> Buying again once the cooldown has passed works
+#[test]
+fn buy_cooldown() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    // Addresses must wait 100 seconds between buys
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: Some(100),
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John lists 3 listings asking 5 VALID_NATIVE, Sam & Max make a bucket for each
+    for listing_id in ["1", "2", "3"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 2000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        for buyer in [&sam, &max] {
+            let res = native_actions::create_bucket(
+                router,
+                &junovaults,
+                &buyer.address,
+                listing_id,
+                &coins(5, VALID_NATIVE),
+            );
+            ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        }
+    }
+
+    // Sam buys listing 1, then can't buy listing 2 straight away
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "2", "2");
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::BuyCooldown {
+                seconds: 100
+            }
+            .to_string(),
+        here("Bought within the cooldown", line!(), column!())
+    );
+
+    // Max hasn't bought yet, so can buy listing 2
+    let res = native_actions::buy(router, &junovaults, &max.address, "2", "2");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Halfway through Sam's cooldown
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 10;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(50);
+    });
+    let res = native_actions::buy(router, &junovaults, &sam.address, "3", "3");
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::BuyCooldown {
+                seconds: 50
+            }
+            .to_string(),
+        here("Bought within the cooldown", line!(), column!())
+    );
+
+    // Sam's cooldown has passed
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 10;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(50);
+    });
+    let res = native_actions::buy(router, &junovaults, &sam.address, "3", "3");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Convert To Auction
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Only the owner of a finalized listing can convert it
+// <X> Converted listing can't be bought at its ask
+// <X> First bid must reach the reserve, later ones must beat the highest by the increment
+// <X> Outbid escrow is refunded
+// <X> Settling pays the seller the highest bid & gives the bidder the listing
+#[test]
+fn convert_to_auction() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+    let balance = |router: &App, address: &Addr| {
+        router.wrap().query_balance(address.to_string(), VALID_NATIVE).map(|c| c.amount.u128())
+    };
+
+    // John lists 10 VALID_NATIVE asking 5
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Can't convert before it's finalized
+    let end_time = router.block_info().time.plus_seconds(500);
+    let convert = crate::msg::ExecuteMsg::ConvertToAuction {
+        listing_id: "1".to_string(),
+        reserve: coin(100, VALID_NATIVE),
+        min_increment: Uint128::new(10),
+        end_time,
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &convert, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NotConvertible {}.to_string(),
+        here("Converted an unfinalized listing", line!(), column!())
+    );
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 2000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Only John can convert it
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &convert, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Converted another owner's listing", line!(), column!())
+    );
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &convert, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam can't buy it at its ask anymore
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::SealedAuction {}.to_string(),
+        here("Bought an auctioned listing", line!(), column!())
+    );
+
+    let bid = |amount: u128| crate::msg::ExecuteMsg::PlaceBid {
+        listing_id: "1".to_string(),
+        amount: Uint128::new(amount),
+    };
+    let out_of_range = crate::ContractError::BidOutOfRange {}.to_string();
+
+    // Sam's bid must reach the reserve
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &bid(90),
+        &coins(90, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == out_of_range,
+        here("Below reserve", line!(), column!())
+    );
+    let sam_start = balance(router, &sam.address)?;
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &bid(100),
+        &coins(100, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Max must beat it by 10, outbidding Sam refunds Sam's escrow
+    let res = router.execute_contract(
+        max.address.clone(),
+        junovaults.clone(),
+        &bid(105),
+        &coins(105, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == out_of_range,
+        here("Below the increment", line!(), column!())
+    );
+    let max_start = balance(router, &max.address)?;
+    let res = router.execute_contract(
+        max.address.clone(),
+        junovaults.clone(),
+        &bid(110),
+        &coins(120, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &sam.address)? == sam_start,
+        here("Outbid escrow not refunded", line!(), column!())
+    );
+    let q = crate::msg::QueryMsg::GetAuction {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::AuctionResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.auction.high_bid.as_ref().is_some_and(
+            |high_bid| high_bid.bidder == max.address && high_bid.amount == Uint128::new(110)
+        ),
+        here(format!("{:?}", res.auction), line!(), column!())
+    );
+
+    // Can't settle before it ends
+    let settle = crate::msg::ExecuteMsg::SettleAuction {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &settle, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::AuctionPhase {
+                phase: "settlement".to_string()
+            }
+            .to_string(),
+        here("Settled while bidding", line!(), column!())
+    );
+
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 100;
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(500);
+    });
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &bid(200),
+        &coins(200, VALID_NATIVE),
+    );
+    ensure!(res.is_err(), here("Bid after the end", line!(), column!()));
+
+    // Max wins with 110 & gets the 10 unused back, John is paid 110
+    let john_start = balance(router, &john.address)?;
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &settle, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &john.address)? == john_start + 110,
+        here("Seller not paid", line!(), column!())
+    );
+    ensure!(
+        balance(router, &max.address)? == max_start - 110,
+        here("Unused escrow not refunded", line!(), column!())
+    );
+
+    // Max withdraws the 10 VALID_NATIVE
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &max.address)? == max_start - 100,
+        here("Winner didn't receive the listing", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing Bundles
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Only the owner of every listing can bundle them, at least 2 distinct ones
+// <X> Buying a bundle buys every listing for the bundle's price
+// <X> Bundle fails if one of its listings was bought separately
+#[test]
+fn listing_bundles() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    // John lists 5 listings of 10 VALID_NATIVE asking 5
+    for listing_id in ["1", "2", "3", "4", "5"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 2000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let bundle = |listing_ids: &[&str], price: u128| crate::msg::ExecuteMsg::CreateBundle {
+        listing_ids: listing_ids.iter().map(|id| id.to_string()).collect(),
+        price: native_actions::native_ask(price),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &bundle(&["1", "2"], 8),
+        &[],
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Bundled another owner's listings", line!(), column!())
+    );
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &bundle(&["1", "1"], 8),
+        &[],
+    );
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::InvalidBundle {
+                max: crate::execute::MAX_BUNDLE_LISTINGS
+            }
+            .to_string(),
+        here("Bundled a listing twice", line!(), column!())
+    );
+
+    // John bundles listings 1-3 for 12 & listings 4-5 for 8
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &bundle(&["1", "2", "3"], 12),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &bundle(&["4", "5"], 8),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetBundle {
+        bundle_id: 1,
+    };
+    let res: crate::query::BundleResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.bundle.listing_ids == ["1", "2", "3"]
+            && res.bundle.price == native_actions::native_ask(12),
+        here(format!("{:?}", res.bundle), line!(), column!())
+    );
+
+    // Sam buys bundle 1 with 12, getting listings 1-3 & paying John 12
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(12, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let buy = |bundle_id: u64, bucket_id: &str| crate::msg::ExecuteMsg::BuyBundle {
+        bundle_id,
+        bucket_id: bucket_id.to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &buy(1, "b1"), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingsByOwner {
+        owner: sam.address.to_string(),
+    };
+    let res: crate::query::MultiListingResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.listings.len() == 3
+            && res.listings.iter().all(|listing| listing.claimant == Some(sam.address.clone())),
+        here(format!("{:?}", res.listings), line!(), column!())
+    );
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: john.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.buckets[0].1.funds.native == coins(12, VALID_NATIVE),
+        here(format!("{:?}", res.buckets), line!(), column!())
+    );
+    let q = crate::msg::QueryMsg::GetBundle {
+        bundle_id: 1,
+    };
+    let res =
+        router.wrap().query_wasm_smart::<crate::query::BundleResponse>(junovaults.clone(), &q);
+    ensure!(res.is_err(), here("Bought bundle not removed", line!(), column!()));
+
+    // Max buys listing 5 alone, so bundle 2 can't be bought
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &max.address,
+        "b2",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &max.address, "5", "b2");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b3",
+        &coins(8, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &buy(2, "b3"), &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::BundleUnavailable {
+                id: "5".to_string()
+            }
+            .to_string(),
+        here("Bought a bundle missing a listing", line!(), column!())
+    );
+
+    // Listing 4 is still John's to sell
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "4".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.creator == john.address, here(format!("{res:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Bucket Power
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Counts the finalized listings a bucket holds enough for
+// <X> Sums what those listings ask by denom
+// <X> Listings that aren't finalized are left out
+#[test]
+fn bucket_power() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists asking 5, 10 & 50, and a 4th asking 5 that isn't finalized
+    for (listing_id, ask) in [("1", 5), ("2", 10), ("3", 50), ("4", 5)] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(ask),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        if listing_id != "4" {
+            let res =
+                native_actions::finalize(router, &junovaults, &john.address, listing_id, 2000);
+            ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        }
+    }
+
+    // Sam's buckets of 20 & 5
+    for (bucket_id, amount) in [("b20", 20), ("b5", 5)] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            bucket_id,
+            &coins(amount, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let power = |router: &App, bucket_id: &str| {
+        let q = crate::msg::QueryMsg::GetBucketPower {
+            bucket_owner: sam.address.to_string(),
+            bucket_id: bucket_id.to_string(),
+        };
+        router.wrap().query_wasm_smart::<crate::query::BucketPowerResponse>(junovaults.clone(), &q)
+    };
+
+    // 20 covers listings 1 & 2, asking 15 in all
+    let res = power(router, "b20")?;
+    ensure!(
+        res.affordable_listings == 2 && res.affordable_value == coins(15, VALID_NATIVE),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // 5 only covers listing 1
+    let res = power(router, "b5")?;
+    ensure!(
+        res.affordable_listings == 1 && res.affordable_value == coins(5, VALID_NATIVE),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // Unknown buckets error
+    ensure!(power(router, "b0").is_err(), here("Found a missing bucket", line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// On Sale Callback
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Seller's contract is sent the callback when the listing is bought
+// <X> Failing callback doesn't revert the sale
+#[test]
+fn on_sale_callback() -> Result<(), anyhow::Error> {
+    use self::create_contract::SaleCallbackExecuteMsg;
+
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let callback_id = router.store_code(create_contract::sale_callback_contract());
+    let callback_contract = router.instantiate_contract(
+        callback_id,
+        john.address.clone(),
+        &Empty {},
+        &[],
+        "sale_callback",
+        None,
+    )?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 2 listings asking 5, listing 1 calls back & listing 2's callback fails
+    for (listing_id, callback) in [
+        (
+            "1",
+            SaleCallbackExecuteMsg::ListingSold {
+                listing_id: "1".to_string(),
+            },
+        ),
+        ("2", SaleCallbackExecuteMsg::Fail {}),
+    ] {
+        let create = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(5),
+                on_sale_callback: Some(crate::msg::SaleCallbackMsg {
+                    contract_addr: callback_contract.to_string(),
+                    msg: to_binary(&callback)?,
+                }),
+                ..create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None)
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 2000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam buys both
+    for listing_id in ["1", "2"] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            listing_id,
+            &coins(5, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::buy(router, &junovaults, &sam.address, listing_id, listing_id);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Only listing 1's callback went through, yet Sam bought both
+    let sold: Vec<String> = router.wrap().query_wasm_smart(callback_contract, &Empty {})?;
+    ensure!(sold == ["1"], here(format!("{sold:?}"), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingsByOwner {
+        owner: sam.address.to_string(),
+    };
+    let res: crate::query::MultiListingResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.listings.iter().all(|listing| listing.claimant == Some(sam.address.clone()))
+            && res.listings.len() == 2,
+        here(format!("{:?}", res.listings), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Min Offer Value
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Offer under the minimum of its denom is rejected
+// <X> Offer in a denom without a minimum is rejected
+// <X> Offer at the minimum is accepted
+#[test]
+fn min_offer_value() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John VALID_NATIVE, Sam VALID_NATIVE & some uusdc
+    let router = give_natives(&john, &mut router);
+    router.init_modules(|router, _, storage| {
+        let funds = vec![coin(100_000_000, VALID_NATIVE), coin(100, "uusdc")];
+        router.bank.init_balance(storage, &sam.address, funds).unwrap()
+    });
+
+    // Offers must hold at least 10 VALID_NATIVE
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: Some(coins(10, VALID_NATIVE)),
+            max_offers: None,
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John lists 10 VALID_NATIVE asking 50
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(50),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam's buckets of 9 & 10 VALID_NATIVE, and of 50 uusdc
+    for (bucket_id, funds) in [
+        ("b9", coins(9, VALID_NATIVE)),
+        ("b10", coins(10, VALID_NATIVE)),
+        ("usdc", coins(50, "uusdc")),
+    ] {
+        let res =
+            native_actions::create_bucket(router, &junovaults, &sam.address, bucket_id, &funds);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    let offer = |bucket_id: &str| crate::msg::ExecuteMsg::LockBucketToListing {
+        bucket_id: bucket_id.to_string(),
+        listing_id: "1".to_string(),
+        expiration_seconds: None,
+    };
+    for bucket_id in ["b9", "usdc"] {
+        let res = router.execute_contract(
+            sam.address.clone(),
+            junovaults.clone(),
+            &offer(bucket_id),
+            &[],
+        );
+        ensure!(
+            native_actions::err_string(res) == crate::ContractError::OfferTooLow {}.to_string(),
+            here(format!("Offered {bucket_id}"), line!(), column!())
+        );
+    }
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &offer("b10"), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listings By Collection
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Returns every owner's listings holding NFTs of the collection
+// <X> Listing holding many NFTs of the collection is returned once
+// <X> Pages by listing ID
+// <X> Removed listing drops out
+#[test]
+fn listings_by_collection() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let send_nft = |router: &mut App,
+                    owner: &Addr,
+                    collection: &Addr,
+                    token_id: &str,
+                    msg: cosmwasm_std::Binary| {
+        let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+            cw721_base::msg::ExecuteMsg::SendNft {
+                contract: junovaults.to_string(),
+                token_id: token_id.to_string(),
+                msg,
+            };
+        router.execute_contract(owner.clone(), collection.clone(), &send_nft, &[])
+    };
+    let create_msg = to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(10),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    })?;
+
+    // John's listing 1 holds NeonPeepz #1 & #2, Sam's listing 2 holds NeonPeepz #3
+    // Max's listing 3 holds ShittyKittyz #5
+    let res = send_nft(&mut router, &john.address, &neonpeepz.addr(), "1", create_msg.clone());
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let add_msg = to_binary(&crate::msg::ReceiveNftMsg::AddToListingCw721 {
+        listing_id: "1".to_string(),
+    })?;
+    let res = send_nft(&mut router, &john.address, &neonpeepz.addr(), "2", add_msg);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = send_nft(&mut router, &sam.address, &neonpeepz.addr(), "3", create_msg.clone());
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = send_nft(&mut router, &max.address, &shittykittyz.addr(), "5", create_msg);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let by_collection = |collection: &Addr, start_after: Option<&str>, limit: Option<u32>| {
+        let q = crate::msg::QueryMsg::GetListingsByCollection {
+            contract: collection.to_string(),
+            start_after: start_after.map(str::to_string),
+            limit,
+        };
+        let res: crate::query::MultiListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok::<_, anyhow::Error>(
+            res.listings
+                .into_iter()
+                .map(|listing| (listing.id, listing.creator))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    let res = by_collection(&neonpeepz.addr(), None, None)?;
+    ensure!(
+        res == [("1".to_string(), john.address.clone()), ("2".to_string(), sam.address.clone())],
+        here(format!("{res:?}"), line!(), column!())
+    );
+    let res = by_collection(&shittykittyz.addr(), None, None)?;
+    ensure!(
+        res == [("3".to_string(), max.address.clone())],
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // One at a time
+    let res = by_collection(&neonpeepz.addr(), None, Some(1))?;
+    ensure!(res.len() == 1 && res[0].0 == "1", here(format!("{res:?}"), line!(), column!()));
+    let res = by_collection(&neonpeepz.addr(), Some("1"), Some(1))?;
+    ensure!(res.len() == 1 && res[0].0 == "2", here(format!("{res:?}"), line!(), column!()));
+    let res = by_collection(&neonpeepz.addr(), Some("2"), Some(1))?;
+    ensure!(res.is_empty(), here(format!("{res:?}"), line!(), column!()));
+
+    // John removes listing 1, only Sam's is left
+    let remove = crate::msg::ExecuteMsg::RemoveListing {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingsByCollection {
+        contract: neonpeepz.addr().to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: crate::query::MultiListingResponse = router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(
+        res.listings.len() == 1 && res.listings[0].id == "2",
+        here(format!("{:?}", res.listings), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Accept Highest Bid
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Can't accept before anyone bids, a bid below the reserve is rejected
+// <X> Only the owner can accept
+// <X> Accepting sells to the highest bid before the auction ends
+#[test]
+fn accept_highest_bid_before_end() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+    let balance = |router: &App, address: &Addr| {
+        router.wrap().query_balance(address.to_string(), VALID_NATIVE).map(|c| c.amount.u128())
+    };
+
+    // John auctions 2 listings of 10 VALID_NATIVE with a reserve of 100, for 500 seconds
+    let end_time = router.block_info().time.plus_seconds(500);
+    for listing_id in ["1", "2"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 2000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let convert = crate::msg::ExecuteMsg::ConvertToAuction {
+            listing_id: listing_id.to_string(),
+            reserve: coin(100, VALID_NATIVE),
+            min_increment: Uint128::new(10),
+            end_time,
+        };
+        let res = router.execute_contract(john.address.clone(), junovaults.clone(), &convert, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam's bid of 90 on listing 1 is below the reserve, so it has no bid to accept
+    let bid = crate::msg::ExecuteMsg::PlaceBid {
+        listing_id: "1".to_string(),
+        amount: Uint128::new(90),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &bid,
+        &coins(90, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::BidOutOfRange {}.to_string(),
+        here("Bid below the reserve", line!(), column!())
+    );
+    let accept = |listing_id: &str| crate::msg::ExecuteMsg::AcceptHighestBid {
+        listing_id: listing_id.to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &accept("1"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::ReserveNotMet {}.to_string(),
+        here("Accepted without a bid", line!(), column!())
+    );
+
+    // Sam bids 100 & Max 110 on listing 2
+    let sam_start = balance(router, &sam.address)?;
+    let max_start = balance(router, &max.address)?;
+    for (bidder, amount) in [(&sam, 100), (&max, 110)] {
+        let bid = crate::msg::ExecuteMsg::PlaceBid {
+            listing_id: "2".to_string(),
+            amount: Uint128::new(amount),
+        };
+        let res = router.execute_contract(
+            bidder.address.clone(),
+            junovaults.clone(),
+            &bid,
+            &coins(amount, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Only John can accept
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &accept("2"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Accepted another owner's auction", line!(), column!())
+    );
+
+    // John accepts Max's 110 before the auction ends
+    let john_start = balance(router, &john.address)?;
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &accept("2"), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    ensure!(
+        balance(router, &john.address)? == john_start + 110
+            && balance(router, &max.address)? == max_start - 110
+            && balance(router, &sam.address)? == sam_start,
+        here("Wrong payout", line!(), column!())
+    );
+    let q = crate::msg::QueryMsg::GetAuction {
+        listing_id: "2".to_string(),
+    };
+    let res =
+        router.wrap().query_wasm_smart::<crate::query::AuctionResponse>(junovaults.clone(), &q);
+    ensure!(res.is_err(), here("Auction not removed", line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingsByOwner {
+        owner: max.address.to_string(),
+    };
+    let res: crate::query::MultiListingResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.listings.len() == 1 && res.listings[0].claimant == Some(max.address.clone()),
+        here(format!("{:?}", res.listings), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Balance Helpers
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Merge adds coins by denom / address & doesn't add an NFT twice
+// <X> Merging past Uint128::MAX errors with Overflow
+// <X> Subtract drops coins left at 0 & errors when taking more than is held
+// <X> Contains needs every coin at its amount & every NFT
+#[test]
+fn balance_helpers() -> Result<(), anyhow::Error> {
+    use crate::state::GenericBalanceUtil;
+
+    let overflow = crate::ContractError::Overflow {}.to_string();
+    let nft = |token_id: &str| Nft {
+        contract_address: Addr::unchecked("nfts"),
+        token_id: token_id.to_string(),
+    };
+    let balance = |juno: u128, cw20: u128, nfts: Vec<Nft>| GenericBalance {
+        native: coins(juno, VALID_NATIVE),
+        cw20: vec![Cw20CoinVerified {
+            address: Addr::unchecked("cw20"),
+            amount: Uint128::new(cw20),
+        }],
+        nfts,
+    };
+    let empty = GenericBalance {
+        native: vec![],
+        cw20: vec![],
+        nfts: vec![],
+    };
+
+    // Merging into nothing copies, merging again adds up
+    let mut merged = empty.clone();
+    merged.merge(&balance(10, 5, vec![nft("1")]))?;
+    ensure!(
+        merged == balance(10, 5, vec![nft("1")]),
+        here(format!("{merged:?}"), line!(), column!())
+    );
+    merged.merge(&balance(1, 2, vec![nft("1"), nft("2")]))?;
+    merged.merge(&empty)?;
+    ensure!(
+        merged == balance(11, 7, vec![nft("1"), nft("2")]),
+        here(format!("{merged:?}"), line!(), column!())
+    );
+    let other_denom = GenericBalance {
+        native: coins(3, "uother"),
+        ..empty.clone()
+    };
+    merged.merge(&other_denom)?;
+    ensure!(
+        merged.native == vec![coin(11, VALID_NATIVE), coin(3, "uother")],
+        here(format!("{merged:?}"), line!(), column!())
+    );
+    let res = merged.merge(&balance(u128::MAX, 0, vec![]));
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow.clone()),
+        here("Merge overflow", line!(), column!())
+    );
+
+    // Subtracting everything leaves nothing, NFTs taken are removed
+    let held = balance(10, 5, vec![nft("1"), nft("2")]);
+    let res = held.subtract(&held)?;
+    ensure!(res == empty, here(format!("{res:?}"), line!(), column!()));
+    let res = held.subtract(&balance(4, 5, vec![nft("2")]))?;
+    ensure!(
+        res.native == coins(6, VALID_NATIVE) && res.cw20.is_empty() && res.nfts == vec![nft("1")],
+        here(format!("{res:?}"), line!(), column!())
+    );
+    let res = held.subtract(&empty)?;
+    ensure!(res == held, here(format!("{res:?}"), line!(), column!()));
+    let res = held.subtract(&balance(11, 0, vec![]));
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow.clone()),
+        here("Subtract underflow", line!(), column!())
+    );
+    let res = held.subtract(&other_denom);
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow.clone()),
+        here("Subtracted a coin not held", line!(), column!())
+    );
+    let res = held.subtract(&balance(0, 0, vec![nft("3")]));
+    ensure!(
+        res.map_err(|e| e.to_string()).err() == Some(overflow),
+        here("Subtracted an NFT not held", line!(), column!())
+    );
+
+    // Contains
+    ensure!(
+        held.contains(&held) && held.contains(&empty) && empty.contains(&empty),
+        here("Doesn't contain itself or nothing", line!(), column!())
+    );
+    ensure!(
+        held.contains(&balance(10, 1, vec![nft("2")])),
+        here("Doesn't contain a part", line!(), column!())
+    );
+    ensure!(
+        !held.contains(&balance(11, 5, vec![])),
+        here("Contains too much JUNO", line!(), column!())
+    );
+    ensure!(
+        !held.contains(&balance(10, 5, vec![nft("3")])),
+        here("Contains an NFT not held", line!(), column!())
+    );
+    ensure!(
+        !held.contains(&other_denom) && !empty.contains(&held),
+        here("Contains a coin not held", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Offers Expiring Soon
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Only offers expiring within the window are returned, soonest first
+// <X> Accepted & expired offers aren't returned
+// <X> Offers without an expiration are never returned
+#[test]
+fn offers_expiring_soon() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 10 VALID_NATIVE 4 times, asking 50
+    for listing_id in ["1", "2", "3", "4"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(50),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 2000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam offers 30 VALID_NATIVE for each, expiring after 600, 100, 300 seconds & never
+    let offers =
+        [("b1", "1", Some(600)), ("b2", "2", Some(100)), ("b3", "3", Some(300)), ("b4", "4", None)];
+    for (bucket_id, listing_id, expiration_seconds) in offers {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            bucket_id,
+            &coins(30, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let offer = crate::msg::ExecuteMsg::LockBucketToListing {
+            bucket_id: bucket_id.to_string(),
+            listing_id: listing_id.to_string(),
+            expiration_seconds,
+        };
+        let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &offer, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let expiring = |router: &App, within_seconds: u64, limit: Option<u32>| {
+        let q = crate::msg::QueryMsg::GetOffersExpiringSoon {
+            within_seconds,
+            limit,
+        };
+        router
+            .wrap()
+            .query_wasm_smart::<crate::query::OffersResponse>(junovaults.clone(), &q)
+            .map(|res| res.offers.into_iter().map(|o| o.bucket_id).collect::<Vec<_>>())
+    };
+
+    // Within 400 seconds, the 100 & 300 second offers lapse
+    let res = expiring(router, 400, None)?;
+    ensure!(res == vec!["b2", "b3"], here(format!("{res:?}"), line!(), column!()));
+    let res = expiring(router, 400, Some(1))?;
+    ensure!(res == vec!["b2"], here(format!("{res:?}"), line!(), column!()));
+    let res = expiring(router, u64::MAX, None)?;
+    ensure!(res == vec!["b2", "b3", "b1"], here(format!("{res:?}"), line!(), column!()));
+
+    // John accepts the 300 second offer
+    let accept = crate::msg::ExecuteMsg::AcceptOffer {
+        listing_id: "3".to_string(),
+        buyer: sam.address.to_string(),
+        bucket_id: "b3".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &accept, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = expiring(router, 400, None)?;
+    ensure!(res == vec!["b2"], here(format!("{res:?}"), line!(), column!()));
+
+    // 200 seconds later the 100 second offer has lapsed & the 600 second offer is within 400
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(200);
+    });
+    let res = expiring(router, 400, None)?;
+    ensure!(res == vec!["b1"], here(format!("{res:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Commit Buy Checks
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> A buyer who isn't whitelisted can't commit & hold the listing
+// <X> A whitelisted buyer can commit
+#[test]
+fn commit_buy_checks() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    // John lists 10 VALID_NATIVE for 10, only Sam can buy it
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let whitelist = crate::msg::ExecuteMsg::SetWhitelistedBuyers {
+        listing_id: "1".to_string(),
+        addresses: vec![sam.address.to_string()],
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &whitelist, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    for user in [&sam, &max] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &user.address,
+            "b1",
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Max isn't whitelisted
+    let commit = crate::msg::ExecuteMsg::CommitToListing {
+        listing_id: "1".to_string(),
+        bucket_id: "b1".to_string(),
+    };
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &commit, &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NotWhitelisted {}.to_string(),
+        here("Max committed", line!(), column!())
+    );
+
+    // Sam is
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &commit, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Height Expiry Actions
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> A relisting listing can't expire by height
+// <X> Can't process a height expiry before its block
+// <X> A height expiry is refunded by anyone once past its block
+#[test]
+fn height_expiry_actions() -> Result<(), anyhow::Error> {
+    use crate::state::OnExpire;
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    // Listing 1 refunds on expiry, listing 2 relists
+    for on_expire in [None, Some(OnExpire::Relist)] {
+        let create = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(5),
+                whitelisted_buyer: None,
+                whitelist_until: None,
+                payout_address: None,
+                min_buyer_purchases: None,
+                vesting_seconds: None,
+                on_expire,
+                private: None,
+                ask_options: None,
+                auto_swap_to: None,
+                units: None,
+                on_sale_callback: None,
+                min_net_proceeds: None,
+                max_offers: None,
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let expiration_height = router.block_info().height + 10;
+    let finalize = |listing_id: &str| crate::msg::ExecuteMsg::Finalize {
+        listing_id: listing_id.to_string(),
+        seconds: None,
+        expiration_height: Some(expiration_height),
+    };
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &finalize("2"), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::InvalidExpiration {}.to_string(),
+        here("Relisting listing expires by height", line!(), column!())
+    );
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &finalize("1"), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Max is the keeper
+    let process = crate::msg::ExecuteMsg::ProcessExpiry {
+        listing_id: "1".to_string(),
+    };
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height = expiration_height;
+    });
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &process, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::NotExpired {
+                x: format!("block {expiration_height}"),
+            }
+            .to_string(),
+        here("Processed before expiring", line!(), column!())
+    );
+
+    // Refund
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.height += 1;
+    });
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &process, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let john_native = router.wrap().query_balance(john.address.to_string(), VALID_NATIVE)?;
+    ensure!(john_native.amount.u128() == 99_999_990, here(john_native, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Proceeds Into Payee Bucket
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Can't pay into a bucket the payee has offered
+// <X> Proceeds are added to the payee's bucket of the same ID, not written over it
+#[test]
+fn proceeds_into_payee_bucket() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give each user 100 VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    // John lists 10 VALID_NATIVE twice for 5, paid to Max
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(5),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: Some(max.address.to_string()),
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: None,
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    };
+    for listing_id in ["1", "2"] {
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Max already holds a b1 bucket of 7
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &max.address,
+        "b1",
+        &coins(7, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // While Max offers b1 on listing 2, proceeds can't be paid into it
+    let offer = crate::msg::ExecuteMsg::LockBucketToListing {
+        bucket_id: "b1".to_string(),
+        listing_id: "2".to_string(),
+        expiration_seconds: Some(100),
+    };
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &offer, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::BucketOffered {
+                listing_id: "2".to_string(),
+            }
+            .to_string(),
+        here("Paid into an offered bucket", line!(), column!())
+    );
+
+    // Once the offer lapses, Sam's purchase adds to Max's b1
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(100);
+    });
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: max.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.buckets.len() == 1 && res.buckets[0].1.funds.native == coins(12, VALID_NATIVE),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Bundle Sale Checks
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> The transfer limit counts every listing in the bundle
+// <X> Recorded listing prices add up to the bundle's price, none of it is lost to rounding
+#[test]
+fn bundle_sale_checks() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John bundles 3 listings of 10 VALID_NATIVE for 10
+    for listing_id in ["1", "2", "3"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(5),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 2000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let bundle = crate::msg::ExecuteMsg::CreateBundle {
+        listing_ids: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+        price: native_actions::native_ask(10),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &bundle, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let set_max_transfers = |router: &mut App, max_transfers_per_buy: u32| {
+        let update = crate::msg::ExecuteMsg::UpdateConfig {
+            config_msg: crate::msg::UpdateConfigMsg {
+                refund_grace_seconds: None,
+                auto_match: None,
+                rounding: None,
+                validate_cw20_asks: None,
+                max_transfers_per_buy: Some(max_transfers_per_buy),
+                maker_fee_bps: None,
+                taker_fee_bps: None,
+                fee_collector: None,
+                admin_timelock_seconds: None,
+                listing_bond: None,
+                min_market_ask_value: None,
+                default_listing_ttl: None,
+                referral_bps: None,
+                max_page_size: None,
+                min_active_seconds: None,
+                swap_router: None,
+                max_nfts_per_collection_per_listing: None,
+                restrict_creators: None,
+                fee_holiday_until: None,
+                reveal_deposit_slash_bps: None,
+                buy_cooldown_seconds: None,
+                min_offer_value: None,
+                max_offers: None,
+                dust_threshold: None,
+            },
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[])
+    };
+    let buy = crate::msg::ExecuteMsg::BuyBundle {
+        bundle_id: 1,
+        bucket_id: "b1".to_string(),
+    };
+
+    // The bucket & 3 listings are 4 transfers
+    let res = set_max_transfers(router, 3);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &buy, &[]);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::TooManyTransfers {
+                count: 4,
+                max: 3,
+            }
+            .to_string(),
+        here("Bundle over the transfer limit", line!(), column!())
+    );
+    let res = set_max_transfers(router, 4);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &buy, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // 10 doesn't split evenly in 3, the shares still add up to 10
+    let q = crate::msg::QueryMsg::GetRecentSales {
+        limit: None,
+    };
+    let res: crate::query::RecentSalesResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let prices: Vec<u128> =
+        res.sales.iter().map(|sale| sale.price.native[0].amount.u128()).collect();
+    ensure!(
+        prices.len() == 3 && prices.iter().sum::<u128>() == 10,
+        here(format!("{prices:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Auction Sale Callback
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Seller's contract is sent the callback when the auction is accepted
+#[test]
+fn auction_sale_callback() -> Result<(), anyhow::Error> {
+    use self::create_contract::SaleCallbackExecuteMsg;
+
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let callback_id = router.store_code(create_contract::sale_callback_contract());
+    let callback_contract = router.instantiate_contract(
+        callback_id,
+        john.address.clone(),
+        &Empty {},
+        &[],
+        "sale_callback",
+        None,
+    )?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John auctions 10 VALID_NATIVE with a reserve of 100 & a callback
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(5),
+            whitelisted_buyer: None,
+            whitelist_until: None,
+            payout_address: None,
+            min_buyer_purchases: None,
+            vesting_seconds: None,
+            on_expire: None,
+            private: None,
+            ask_options: None,
+            auto_swap_to: None,
+            units: None,
+            on_sale_callback: Some(crate::msg::SaleCallbackMsg {
+                contract_addr: callback_contract.to_string(),
+                msg: to_binary(&SaleCallbackExecuteMsg::ListingSold {
+                    listing_id: "1".to_string(),
+                })?,
+            }),
+            min_net_proceeds: None,
+            max_offers: None,
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create,
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 2000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let convert = crate::msg::ExecuteMsg::ConvertToAuction {
+        listing_id: "1".to_string(),
+        reserve: coin(100, VALID_NATIVE),
+        min_increment: Uint128::new(10),
+        end_time: router.block_info().time.plus_seconds(500),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &convert, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam bids 100 & John accepts
+    let bid = crate::msg::ExecuteMsg::PlaceBid {
+        listing_id: "1".to_string(),
+        amount: Uint128::new(100),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &bid,
+        &coins(100, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let accept = crate::msg::ExecuteMsg::AcceptHighestBid {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &accept, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // The callback went through
+    let sold: Vec<String> = router.wrap().query_wasm_smart(callback_contract, &Empty {})?;
+    ensure!(sold == ["1"], here(format!("{sold:?}"), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Accept Partial Offer
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Seller can only accept assets held by the offered bucket
+// <X> Listing sells for the accepted assets, the rest stays with the buyer & unlocks
+#[test]
+fn accept_partial_offer() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 10 VALID_NATIVE asking 50
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(50),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam offers NeonPeepz #3 & #4 with 20 VALID_NATIVE
+    let sends = [
+        crate::msg::ReceiveNftMsg::CreateBucketCw721 {
+            bucket_id: "b1".to_string(),
+        },
+        crate::msg::ReceiveNftMsg::AddToBucketCw721 {
+            bucket_id: "b1".to_string(),
+        },
+    ];
+    for (token_id, msg) in ["3", "4"].into_iter().zip(sends) {
+        let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+            cw721_base::msg::ExecuteMsg::SendNft {
+                contract: junovaults.to_string(),
+                token_id: token_id.to_string(),
+                msg: to_binary(&msg)?,
+            };
+        let res = router.execute_contract(sam.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let add = crate::msg::ExecuteMsg::AddToBucket {
+        bucket_id: "b1".to_string(),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &add,
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let offer = crate::msg::ExecuteMsg::LockBucketToListing {
+        bucket_id: "b1".to_string(),
+        listing_id: "1".to_string(),
+        expiration_seconds: None,
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &offer, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let nft = |token_id: &str| Nft {
+        contract_address: neonpeepz.addr(),
+        token_id: token_id.to_string(),
+    };
+    let accept = |nfts: Vec<Nft>| crate::msg::ExecuteMsg::AcceptPartialOffer {
+        listing_id: "1".to_string(),
+        buyer: sam.address.to_string(),
+        bucket_id: "b1".to_string(),
+        accepted_assets: GenericBalance {
+            native: coins(10, VALID_NATIVE),
+            cw20: vec![],
+            nfts,
+        },
+    };
+
+    // NeonPeepz #5 isn't offered
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &accept(vec![nft("5")]),
+        &[],
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NotInOffer {}.to_string(),
+        here("Accepted an asset that wasn't offered", line!(), column!())
+    );
+
+    // John accepts NeonPeepz #3 & 10 VALID_NATIVE
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &accept(vec![nft("3")]),
+        &[],
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let buckets = |owner: &Addr| {
+        let q = crate::msg::QueryMsg::GetBuckets {
+            bucket_owner: owner.to_string(),
+        };
+        router.wrap().query_wasm_smart::<crate::query::GetBucketsResponse>(junovaults.clone(), &q)
+    };
+    let res = buckets(&john.address)?;
+    ensure!(
+        res.buckets.len() == 1
+            && res.buckets[0].1.funds.native == coins(10, VALID_NATIVE)
+            && res.buckets[0].1.funds.nfts == [nft("3")],
+        here(format!("{res:?}"), line!(), column!())
+    );
+    let res = buckets(&sam.address)?;
+    ensure!(
+        res.buckets.len() == 1
+            && res.buckets[0].1.funds.native == coins(10, VALID_NATIVE)
+            && res.buckets[0].1.funds.nfts == [nft("4")],
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    // Sam's rest is unlocked & NeonPeepz #4 comes back
+    let remove = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "b1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &remove, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "4".to_string(), false)?.owner;
+    ensure!(owner == sam.address, here(owner, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Min Net Proceeds
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Buy is rejected when the maker fee takes the seller's net under the floor
+// <X> Buy goes through once the net reaches the floor
+#[test]
+fn min_net_proceeds() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    let set_maker_fee = |router: &mut App, maker_fee_bps: u64| {
+        let update = crate::msg::ExecuteMsg::UpdateConfig {
+            config_msg: crate::msg::UpdateConfigMsg {
+                refund_grace_seconds: None,
+                auto_match: None,
+                rounding: None,
+                validate_cw20_asks: None,
+                max_transfers_per_buy: None,
+                maker_fee_bps: Some(maker_fee_bps),
+                taker_fee_bps: None,
+                fee_collector: Some(max.address.to_string()),
+                admin_timelock_seconds: None,
+                listing_bond: None,
+                min_market_ask_value: None,
+                default_listing_ttl: None,
+                referral_bps: None,
+                max_page_size: None,
+                min_active_seconds: None,
+                swap_router: None,
+                max_nfts_per_collection_per_listing: None,
+                restrict_creators: None,
+                fee_holiday_until: None,
+                reveal_deposit_slash_bps: None,
+                buy_cooldown_seconds: None,
+                min_offer_value: None,
+                max_offers: None,
+                dust_threshold: None,
+            },
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[])
+    };
+
+    // John lists 10 VALID_NATIVE asking 1_000, netting at least 990
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(1_000),
+            min_net_proceeds: Some(coins(990, VALID_NATIVE)),
+            max_offers: None,
+            ..create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None)
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create,
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(1_000, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // A 2% maker fee would net John 980
+    let res = set_maker_fee(router, 200);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::NetProceedsTooLow {}.to_string(),
+        here("Bought under the floor", line!(), column!())
+    );
+
+    // A 1% maker fee nets John 990
+    let res = set_maker_fee(router, 100);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetBuckets {
+        bucket_owner: john.address.to_string(),
+    };
+    let res: crate::query::GetBucketsResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.buckets.len() == 1 && res.buckets[0].1.funds.native == coins(990, VALID_NATIVE),
+        here(format!("{res:?}"), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Zero Limits
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Instantiating with a max transfers per buy or max page size of 0 is rejected
+// <X> Updating either to 0 is rejected
+#[test]
+fn zero_limits() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let invalid = |field: &str| {
+        crate::ContractError::InvalidConfig {
+            field: field.to_string(),
+        }
+        .to_string()
+    };
+
+    let jv_id = router.store_code(junovaults_contract());
+    for (max_transfers_per_buy, max_page_size, field) in
+        [(Some(0), None, "max_transfers_per_buy"), (None, Some(0), "max_page_size")]
+    {
+        let msg = InstantiateMsg {
+            admin: None,
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: None,
+        };
+        let res = router.instantiate_contract(
+            jv_id,
+            contract_admin.address.clone(),
+            &msg,
+            &[],
+            "jv",
+            None,
+        );
+        ensure!(
+            res.map_or_else(|e| e.root_cause().to_string(), |_| "Ok".to_string()) == invalid(field),
+            here(format!("Instantiated with {field} of 0"), line!(), column!())
+        );
+
+        let update = crate::msg::ExecuteMsg::UpdateConfig {
+            config_msg: crate::msg::UpdateConfigMsg {
+                refund_grace_seconds: None,
+                auto_match: None,
+                rounding: None,
+                validate_cw20_asks: None,
+                max_transfers_per_buy,
+                maker_fee_bps: None,
+                taker_fee_bps: None,
+                fee_collector: None,
+                admin_timelock_seconds: None,
+                listing_bond: None,
+                min_market_ask_value: None,
+                default_listing_ttl: None,
+                referral_bps: None,
+                max_page_size,
+                min_active_seconds: None,
+                swap_router: None,
+                max_nfts_per_collection_per_listing: None,
+                restrict_creators: None,
+                fee_holiday_until: None,
+                reveal_deposit_slash_bps: None,
+                buy_cooldown_seconds: None,
+                min_offer_value: None,
+                max_offers: None,
+                dust_threshold: None,
+            },
+        };
+        let res = router.execute_contract(
+            contract_admin.address.clone(),
+            junovaults.clone(),
+            &update,
+            &[],
+        );
+        ensure!(
+            native_actions::err_string(res) == invalid(field),
+            here(format!("Updated {field} to 0"), line!(), column!())
+        );
+    }
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Non Owner Finalize
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Finalizing another owner's listing by its ID is Unauthorized, not ListingNotFound
+// <X> Listing is still being prepared & its owner can finalize it
+#[test]
+fn non_owner_finalize() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(10),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam finds John's listing by its ID, but can't finalize it
+    let res = native_actions::finalize(router, &junovaults, &sam.address, "1", 1000);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Sam finalized John's listing", line!(), column!())
+    );
+    let q = crate::msg::QueryMsg::GetListingInfo {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::ListingInfoResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.status == "Being Prepared", here(format!("{res:?}"), line!(), column!()));
+
+    // A listing that doesn't exist is still ListingNotFound
+    let res = native_actions::finalize(router, &junovaults, &sam.address, "2", 1000);
+    ensure!(
+        native_actions::err_string(res)
+            == crate::ContractError::ListingNotFound {
+                id: "2".to_string()
+            }
+            .to_string(),
+        here("Finalized a missing listing", line!(), column!())
+    );
+
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Add NFT To Finalized Listing
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Adding an NFT to a finalized listing is ListingFinalized
+// <X> Owner keeps the NFT & the listing's contents don't change
+#[test]
+fn add_nft_to_finalized_listing() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let send_nft = |router: &mut App, token_id: &str, msg: &crate::msg::ReceiveNftMsg| {
+        let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+            cw721_base::msg::ExecuteMsg::SendNft {
+                contract: junovaults.to_string(),
+                token_id: token_id.to_string(),
+                msg: to_binary(msg)?,
+            };
+        router.execute_contract(john.address.clone(), neonpeepz.addr(), &send_nft, &[])
+    };
+
+    // John lists NeonPeepz #1 & finalizes
+    let create = crate::msg::ReceiveNftMsg::CreateListingCw721 {
+        create_msg: create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None),
+    };
+    let res = send_nft(&mut router, "1", &create);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(&mut router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Adding NeonPeepz #2 is rejected
+    let add = crate::msg::ReceiveNftMsg::AddToListingCw721 {
+        listing_id: "1".to_string(),
+    };
+    let res = send_nft(&mut router, "2", &add);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::ListingFinalized {}.to_string(),
+        here("Added an NFT to a finalized listing", line!(), column!())
+    );
+    let owner = neonpeepz.owner_of(&router.wrap(), "2".to_string(), false)?.owner;
+    ensure!(owner == john.address, here(owner, line!(), column!()));
+    let q = crate::msg::QueryMsg::GetBundleContents {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::BundleContentsResponse =
+        router.wrap().query_wasm_smart(junovaults, &q)?;
+    ensure!(
+        res.for_sale.nfts.len() == 1 && res.for_sale.nfts[0].token_id == "1",
+        here(format!("{:?}", res.for_sale), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Offers Sorted
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Listing's live offers are sorted highest first by the amount of the denom
+// <X> Offers without the denom come last
+// <X> Expired offers & offers on other listings are left out
+#[test]
+fn offers_sorted() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    // John lists 10 VALID_NATIVE twice, asking 100
+    for listing_id in ["1", "2"] {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(100),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Max's b1 only holds NeonPeepz #5
+    let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: junovaults.to_string(),
+            token_id: "5".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::CreateBucketCw721 {
+                bucket_id: "b1".to_string(),
+            })?,
+        };
+    let res = router.execute_contract(max.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Sam offers 30, 50 & 70 for 10 seconds on listing 1 & 60 on listing 2, Max offers 40 & the NFT
+    let offers = [
+        (&sam, "b1", 30, "1", None),
+        (&sam, "b2", 50, "1", None),
+        (&sam, "b3", 70, "1", Some(10)),
+        (&sam, "b4", 60, "2", None),
+        (&max, "b2", 40, "1", None),
+        (&max, "b1", 0, "1", None),
+    ];
+    for (buyer, bucket_id, amount, listing_id, expiration_seconds) in offers {
+        if amount > 0 {
+            let res = native_actions::create_bucket(
+                router,
+                &junovaults,
+                &buyer.address,
+                bucket_id,
+                &coins(amount, VALID_NATIVE),
+            );
+            ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        }
+        let offer = crate::msg::ExecuteMsg::LockBucketToListing {
+            bucket_id: bucket_id.to_string(),
+            listing_id: listing_id.to_string(),
+            expiration_seconds,
+        };
+        let res = router.execute_contract(buyer.address.clone(), junovaults.clone(), &offer, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam's offer of 70 lapses
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(10);
+    });
+
+    let q = crate::msg::QueryMsg::GetOffersSorted {
+        listing_id: "1".to_string(),
+        denom: VALID_NATIVE.to_string(),
+    };
+    let res: crate::query::BucketOffersResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let sorted: Vec<(&Addr, &str)> =
+        res.offers.iter().map(|offer| (&offer.buyer, offer.bucket_id.as_str())).collect();
+    ensure!(
+        sorted
+            == [
+                (&sam.address, "b2"),
+                (&max.address, "b2"),
+                (&sam.address, "b1"),
+                (&max.address, "b1")
+            ],
+        here(format!("{sorted:?}"), line!(), column!())
+    );
+    ensure!(
+        res.offers.iter().all(|offer| offer.listing_id == "1")
+            && res.offers[0].funds.native == coins(50, VALID_NATIVE),
+        here(format!("{:?}", res.offers), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Offers By Buyer
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Offers show up once made, up to the limit
+// <X> Accepted offers are gone
+// <X> Offers of buckets spent on a buy are gone
+// <X> Lapsed offers are gone, & their bucket can be withdrawn
+#[test]
+fn offers_by_buyer() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // Sam's offered bucket_ids, in bucket_id order
+    let sams_offers = |router: &mut App, limit: Option<u32>| -> anyhow::Result<Vec<String>> {
+        let q = crate::msg::QueryMsg::GetOffersByBuyer {
+            buyer: sam.address.to_string(),
+            limit,
+        };
+        let res: crate::query::BucketOffersResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok(res.offers.into_iter().map(|offer| offer.bucket_id).collect())
+    };
+
+    // John lists 10 VALID_NATIVE 3 times asking 100, Sam offers a bucket of 100 on each
+    // Sam's offer on listing 1 lapses after 10 seconds
+    for (listing_id, bucket_id, expiration_seconds) in
+        [("1", "b1", Some(10)), ("2", "b2", None), ("3", "b3", None)]
+    {
+        let res = native_actions::create_listing(
+            router,
+            &junovaults,
+            &john.address,
+            &coins(10, VALID_NATIVE),
+            native_actions::native_ask(100),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &sam.address,
+            bucket_id,
+            &coins(100, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let offer = crate::msg::ExecuteMsg::LockBucketToListing {
+            bucket_id: bucket_id.to_string(),
+            listing_id: listing_id.to_string(),
+            expiration_seconds,
+        };
+        let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &offer, &[]);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // All 3 offers show up, the limit caps them
+    let offers = sams_offers(router, None)?;
+    ensure!(offers == ["b1", "b2", "b3"], here(format!("{offers:?}"), line!(), column!()));
+    let offers = sams_offers(router, Some(2))?;
+    ensure!(offers == ["b1", "b2"], here(format!("{offers:?}"), line!(), column!()));
+
+    // John accepts Sam's offer on listing 2
+    let accept = crate::msg::ExecuteMsg::AcceptOffer {
+        listing_id: "2".to_string(),
+        buyer: sam.address.to_string(),
+        bucket_id: "b2".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &accept, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let offers = sams_offers(router, None)?;
+    ensure!(offers == ["b1", "b3"], here(format!("{offers:?}"), line!(), column!()));
+
+    // Sam buys listing 3 with the offered bucket
+    let res = native_actions::buy(router, &junovaults, &sam.address, "3", "b3");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let offers = sams_offers(router, None)?;
+    ensure!(offers == ["b1"], here(format!("{offers:?}"), line!(), column!()));
+
+    // Offers are binding, Sam's offer on listing 1 is only gone once it lapses
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(10);
+    });
+    let offers = sams_offers(router, None)?;
+    ensure!(offers.is_empty(), here(format!("{offers:?}"), line!(), column!()));
+
+    // Sam can withdraw its bucket after
+    let withdraw = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "b1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Max Offers
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Offer not beating the lowest is rejected at the cap
+// <X> Offer beating the lowest evicts it, freeing its bucket
+// <X> Listing's max_offers overrides the config's
+#[test]
+fn max_offers() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+
+    // Listings take 2 offers
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: Some(2),
+            dust_threshold: None,
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John lists 10 VALID_NATIVE twice asking 100, listing 2 only takes 1 offer
+    for (listing_id, max_offers) in [("1", None), ("2", Some(1))] {
+        let create = crate::msg::ExecuteMsg::CreateListing {
+            create_msg: CreateListingMsg {
+                ask: native_actions::native_ask(100),
+                max_offers,
+                ..create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None)
+            },
+        };
+        let res = router.execute_contract(
+            john.address.clone(),
+            junovaults.clone(),
+            &create,
+            &coins(10, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+        let res = native_actions::finalize(router, &junovaults, &john.address, listing_id, 1000);
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Bucket IDs hold their amount of VALID_NATIVE
+    for (user, bucket_id) in
+        [(&sam, "10"), (&sam, "20"), (&sam, "5"), (&sam, "30"), (&sam, "1"), (&max, "1")]
+    {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &user.address,
+            bucket_id,
+            &coins(bucket_id.parse()?, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+    let offer = |router: &mut App, user: &Addr, bucket_id: &str, listing_id: &str| {
+        let msg = crate::msg::ExecuteMsg::LockBucketToListing {
+            bucket_id: bucket_id.to_string(),
+            listing_id: listing_id.to_string(),
+            expiration_seconds: None,
+        };
+        native_actions::err_string(router.execute_contract(
+            user.clone(),
+            junovaults.clone(),
+            &msg,
+            &[],
+        ))
+    };
+
+    // Sam offers 10 & 20 on listing 1, which is then at the cap
+    for bucket_id in ["10", "20"] {
+        let res = offer(router, &sam.address, bucket_id, "1");
+        ensure!(res == "Ok", here(res, line!(), column!()));
+    }
+
+    // 5 doesn't beat the lowest offer of 10
+    let res = offer(router, &sam.address, "5", "1");
+    let expected = crate::ContractError::TooManyOffers {
+        max: 2,
+    }
+    .to_string();
+    ensure!(res == expected, here(res, line!(), column!()));
+
+    // 30 does & evicts it
+    let res = offer(router, &sam.address, "30", "1");
+    ensure!(res == "Ok", here(res, line!(), column!()));
+    let q = crate::msg::QueryMsg::GetOffersSorted {
+        listing_id: "1".to_string(),
+        denom: VALID_NATIVE.to_string(),
+    };
+    let res: crate::query::BucketOffersResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let offers: Vec<&str> = res.offers.iter().map(|offer| offer.bucket_id.as_str()).collect();
+    ensure!(offers == ["30", "20"], here(format!("{offers:?}"), line!(), column!()));
+
+    // The evicted bucket is free to withdraw
+    let withdraw = crate::msg::ExecuteMsg::RemoveBucket {
+        bucket_id: "10".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Listing 2 is at its own cap after Sam's offer, Max's equal offer doesn't beat it
+    let res = offer(router, &sam.address, "1", "2");
+    ensure!(res == "Ok", here(res, line!(), column!()));
+    let res = offer(router, &max.address, "1", "2");
+    let expected = crate::ContractError::TooManyOffers {
+        max: 1,
+    }
+    .to_string();
+    ensure!(res == expected, here(res, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Update Auction Params
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Only the owner can update them
+// <X> End time is held to the same limits as converting
+// <X> Update before a bid is accepted & applies to the next bid
+// <X> Update after a bid is rejected
+#[test]
+fn update_auction_params() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (_jvone, _jvtwo, _jvtre, _neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John auctions 10 VALID_NATIVE with a reserve of 100
+    let res = native_actions::create_listing(
+        router,
+        &junovaults,
+        &john.address,
+        &coins(10, VALID_NATIVE),
+        native_actions::native_ask(5),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 2000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let convert = crate::msg::ExecuteMsg::ConvertToAuction {
+        listing_id: "1".to_string(),
+        reserve: coin(100, VALID_NATIVE),
+        min_increment: Uint128::new(10),
+        end_time: router.block_info().time.plus_seconds(500),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &convert, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let update = |end_time: cosmwasm_std::Timestamp| crate::msg::ExecuteMsg::UpdateAuctionParams {
+        listing_id: "1".to_string(),
+        reserve: coin(50, VALID_NATIVE),
+        min_increment: Uint128::new(5),
+        end_time,
+    };
+    let end_time = router.block_info().time.plus_seconds(1000);
+
+    // Only John can update it
+    let res =
+        router.execute_contract(sam.address.clone(), junovaults.clone(), &update(end_time), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Updated another owner's auction", line!(), column!())
+    );
+
+    // Not with an end time in the past
+    let past = router.block_info().time;
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &update(past), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::InvalidExpiration {}.to_string(),
+        here("Ended the auction in the past", line!(), column!())
+    );
+
+    // John lowers the reserve to 50 before any bid
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &update(end_time), &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetAuction {
+        listing_id: "1".to_string(),
+    };
+    let res: crate::query::AuctionResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(
+        res.auction.reserve == coin(50, VALID_NATIVE)
+            && res.auction.min_increment == Uint128::new(5)
+            && res.auction.end_time == end_time,
+        here(format!("{:?}", res.auction), line!(), column!())
+    );
+
+    // Sam's bid of 50 meets the new reserve
+    let bid = crate::msg::ExecuteMsg::PlaceBid {
+        listing_id: "1".to_string(),
+        amount: Uint128::new(50),
+    };
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &bid,
+        &coins(50, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John can't change it anymore
+    let res =
+        router.execute_contract(john.address.clone(), junovaults.clone(), &update(end_time), &[]);
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::AuctionHasBids {}.to_string(),
+        here("Updated an auction with a bid", line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Clone Sold Listing
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Buyer can't clone a bought listing, before or after withdrawing it
+// <X> Seller can clone it, with its payout address
+#[test]
+fn clone_sold_listing() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John & Sam VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+
+    // John lists 10 VALID_NATIVE asking 5, paid out to Max, & Sam buys it
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(5),
+            payout_address: Some(max.address.to_string()),
+            ..create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None)
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create,
+        &coins(10, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::create_bucket(
+        router,
+        &junovaults,
+        &sam.address,
+        "b1",
+        &coins(5, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    let clone = crate::msg::ExecuteMsg::CloneListing {
+        source_listing_id: "1".to_string(),
+    };
+
+    // Sam is the bought listing's creator now, but can't clone it
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &clone,
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Buyer cloned the bought listing", line!(), column!())
+    );
+
+    // Nor once it's withdrawn & archived
+    let withdraw = crate::msg::ExecuteMsg::WithdrawPurchased {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(sam.address.clone(), junovaults.clone(), &withdraw, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = router.execute_contract(
+        sam.address.clone(),
+        junovaults.clone(),
+        &clone,
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(
+        native_actions::err_string(res) == crate::ContractError::Unauthorized {}.to_string(),
+        here("Buyer cloned the archived listing", line!(), column!())
+    );
+
+    // John clones it, still paid out to Max
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &clone,
+        &coins(20, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingsByOwner {
+        owner: john.address.to_string(),
+    };
+    let res: crate::query::MultiListingResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    let cloned = &res.listings[0];
+    ensure!(
+        res.listings.len() == 1
+            && cloned.id == "2"
+            && cloned.ask == native_actions::native_ask(5)
+            && cloned.payout_address == Some(max.address.clone())
+            && cloned.for_sale.native == coins(20, VALID_NATIVE),
+        here(format!("{:?}", res.listings), line!(), column!())
+    );
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Expiry NFT Claims
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Failed NFT refund on expiry doesn't revert, the listing is still processed
+// <X> Owner claims the NFT after
+#[test]
+fn expiry_claim_nft() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    let set_receiver = |router: &mut App, known: bool| {
+        let msg = crate::msg::ExecuteMsg::SetNftReceiver {
+            address: john.address.to_string(),
+            known,
+        };
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &msg, &[])
+    };
+
+    // John lists NeonPeepz #1 for 1000 seconds
+    let send_nft: cw721_base::ExecuteMsg<Option<Empty>, Empty> =
+        cw721_base::msg::ExecuteMsg::SendNft {
+            contract: junovaults.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&crate::msg::ReceiveNftMsg::CreateListingCw721 {
+                create_msg: CreateListingMsg {
+                    ask: native_actions::native_ask(10),
+                    ..create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None)
+                },
+            })?,
+        };
+    let res = router.execute_contract(john.address.clone(), neonpeepz.addr(), &send_nft, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(&mut router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John isn't a contract, so refunding him the NFT with SendNft fails
+    let res = set_receiver(&mut router, true);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // Max processes the expired listing anyway
+    router.update_block(|current_blockinfo| {
+        current_blockinfo.time = current_blockinfo.time.plus_seconds(100_000);
+    });
+    let process = crate::msg::ExecuteMsg::ProcessExpiry {
+        listing_id: "1".to_string(),
+    };
+    let res = router.execute_contract(max.address.clone(), junovaults.clone(), &process, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    ensure!(owner == junovaults, here(owner, line!(), column!()));
+    let q = crate::msg::QueryMsg::GetListingsByOwner {
+        owner: john.address.to_string(),
+    };
+    let res: crate::query::MultiListingResponse =
+        router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+    ensure!(res.listings.is_empty(), here(format!("{:?}", res.listings), line!(), column!()));
+
+    // John claims it once it can be transferred to him
+    let res = set_receiver(&mut router, false);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let claim = crate::msg::ExecuteMsg::ClaimNft {
+        contract: neonpeepz.addr().to_string(),
+        token_id: "1".to_string(),
+    };
+    let res = router.execute_contract(john.address.clone(), junovaults.clone(), &claim, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let owner = neonpeepz.owner_of(&router.wrap(), "1".to_string(), false)?.owner;
+    ensure!(owner == john.address, here(owner, line!(), column!()));
+
+    Ok(())
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Dust Threshold
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// <X> Unit listing stays up while what's left is at least the threshold
+// <X> Unit buy leaving less than the threshold closes the listing & refunds the rest to the seller
+#[test]
+fn dust_threshold() -> Result<(), anyhow::Error> {
+    // Setup
+    let mut router = App::default();
+    let contract_admin = create_users::fake_user("admin".to_string());
+    let john = create_users::fake_user("john".to_string());
+    let sam = create_users::fake_user("sam".to_string());
+    let max = create_users::fake_user("max".to_string());
+
+    // Instantiate all contracts
+    let (jvone, _jvtwo, _jvtre, neonpeepz, _shittykittyz, junovaults) =
+        init_all_contracts(&mut router, &contract_admin, &john, &sam, &max)?;
+
+    // Give John, Sam & Max VALID_NATIVE
+    let router = give_natives(&john, &mut router);
+    let router = give_natives(&sam, router);
+    let router = give_natives(&max, router);
+    let balance = |router: &App, user: &Addr| -> Uint128 {
+        router.wrap().query_balance(user.to_string(), VALID_NATIVE).unwrap().amount
+    };
+    let listings = |router: &App| -> Result<Vec<crate::state::Listing>, anyhow::Error> {
+        let q = crate::msg::QueryMsg::GetListingsByOwner {
+            owner: john.address.to_string(),
+        };
+        let res: crate::query::MultiListingResponse =
+            router.wrap().query_wasm_smart(junovaults.clone(), &q)?;
+        Ok(res.listings)
+    };
+
+    // Unit listings with less than 60 left close
+    let update = crate::msg::ExecuteMsg::UpdateConfig {
+        config_msg: crate::msg::UpdateConfigMsg {
+            refund_grace_seconds: None,
+            auto_match: None,
+            rounding: None,
+            validate_cw20_asks: None,
+            max_transfers_per_buy: None,
+            maker_fee_bps: None,
+            taker_fee_bps: None,
+            fee_collector: None,
+            admin_timelock_seconds: None,
+            listing_bond: None,
+            min_market_ask_value: None,
+            default_listing_ttl: None,
+            referral_bps: None,
+            max_page_size: None,
+            min_active_seconds: None,
+            swap_router: None,
+            max_nfts_per_collection_per_listing: None,
+            restrict_creators: None,
+            fee_holiday_until: None,
+            reveal_deposit_slash_bps: None,
+            buy_cooldown_seconds: None,
+            min_offer_value: None,
+            max_offers: None,
+            dust_threshold: Some(Uint128::new(60)),
+        },
+    };
+    let res =
+        router.execute_contract(contract_admin.address.clone(), junovaults.clone(), &update, &[]);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    // John lists 100 VALID_NATIVE at 5 a unit in 4 units
+    let create = crate::msg::ExecuteMsg::CreateListing {
+        create_msg: CreateListingMsg {
+            ask: native_actions::native_ask(5),
+            units: Some(4),
+            ..create_valid_listing::create_listing_msg(jvone.addr(), neonpeepz.addr(), None)
+        },
+    };
+    let res = router.execute_contract(
+        john.address.clone(),
+        junovaults.clone(),
+        &create,
+        &coins(100, VALID_NATIVE),
+    );
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let res = native_actions::finalize(router, &junovaults, &john.address, "1", 1000);
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+
+    for user in [&sam, &max] {
+        let res = native_actions::create_bucket(
+            router,
+            &junovaults,
+            &user.address,
+            "b1",
+            &coins(5, VALID_NATIVE),
+        );
+        ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    }
+
+    // Sam buys a unit, 75 is left
+    let res = native_actions::buy(router, &junovaults, &sam.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let all = listings(router)?;
+    ensure!(
+        all.len() == 1 && all[0].for_sale.native == coins(75, VALID_NATIVE),
+        here(format!("{all:?}"), line!(), column!())
+    );
+
+    // Max buys a unit, the 50 left is under the threshold & goes back to John
+    let john_before = balance(router, &john.address);
+    let res = native_actions::buy(router, &junovaults, &max.address, "1", "b1");
+    ensure!(res.is_ok(), here(native_actions::err_string(res), line!(), column!()));
+    let all = listings(router)?;
+    ensure!(all.is_empty(), here(format!("{all:?}"), line!(), column!()));
+    let john_after = balance(router, &john.address);
+    ensure!(
+        john_after == john_before + Uint128::new(50),
+        here(format!("{john_before} -> {john_after}"), line!(), column!())
+    );
+
+    Ok(())
+}
@@ -1,9 +1,17 @@
 #[allow(unused_imports)]
 use crate::query::{
-    AdminResponse, ConfigResponse, GetBucketsResponse, ListingInfoResponse, MultiListingResponse,
+    ActiveCollectionsResponse, ActiveDenomsResponse, AddressActivityResponse, AdminResponse,
+    ArchivedListingResponse, AuctionResponse, BucketOffersResponse, BucketPowerResponse,
+    BucketShortfallResponse, BundleContentsResponse, BundleResponse, CanBuyResponse,
+    ConfigResponse, FloorHistoryResponse, GetBucketsResponse, LastSalePriceResponse,
+    LimitsResponse, ListingForNftResponse, ListingHistoryResponse, ListingInfoResponse,
+    MultiListingResponse, NextListingIdResponse, OffersResponse, QueuedActionsResponse,
+    RecentSalesResponse, ReconciliationResponse, SealedAuctionResponse, SellerEarningsResponse,
+    SimulateBuyResponse, TrendingListingsResponse, TvlResponse, UniqueUsersResponse,
 };
-use crate::state::GenericBalance;
+use crate::state::{GenericBalance, OnExpire, Rounding, Status};
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Binary, Coin, Decimal, HexBinary, Timestamp, Uint128};
 use cw20::Cw20ReceiveMsg;
 use cw721::Cw721ReceiveMsg;
 
@@ -13,6 +21,53 @@ use cw721::Cw721ReceiveMsg;
 #[cw_serde]
 pub struct InstantiateMsg {
     pub admin: Option<String>,
+    // Defaults to 0
+    pub refund_grace_seconds: Option<u64>,
+    // Defaults to false
+    pub auto_match: Option<bool>,
+    // Defaults to Floor
+    pub rounding: Option<Rounding>,
+    // Defaults to false
+    pub validate_cw20_asks: Option<bool>,
+    // Defaults to 50
+    pub max_transfers_per_buy: Option<u32>,
+    // Default to 0, at most 1000 (10%)
+    pub maker_fee_bps: Option<u64>,
+    pub taker_fee_bps: Option<u64>,
+    // Defaults to the admin
+    pub fee_collector: Option<String>,
+    // Defaults to 0, config changes apply immediately
+    pub admin_timelock_seconds: Option<u64>,
+    // Defaults to no bond
+    pub listing_bond: Option<Coin>,
+    // Defaults to no minimums
+    pub min_market_ask_value: Option<Vec<Coin>>,
+    // Defaults to 0, finalizing must then set the seconds
+    pub default_listing_ttl: Option<u64>,
+    // Defaults to 0, at most 10000 (the whole fee)
+    pub referral_bps: Option<u64>,
+    // Defaults to 30
+    pub max_page_size: Option<u32>,
+    // Defaults to 0, the admin's ForceRemoveListing ignores it
+    pub min_active_seconds: Option<u64>,
+    // Defaults to no router, auto_swap_to is then ignored
+    pub swap_router: Option<String>,
+    // Defaults to 25
+    pub max_nfts_per_collection_per_listing: Option<u32>,
+    // Defaults to false, anyone can create listings
+    pub restrict_creators: Option<bool>,
+    // Defaults to no holiday, maker & taker fees are waived until this time
+    pub fee_holiday_until: Option<Timestamp>,
+    // Defaults to 0, at most 10000 (the whole escrow)
+    pub reveal_deposit_slash_bps: Option<u64>,
+    // Defaults to 0, addresses can buy back to back
+    pub buy_cooldown_seconds: Option<u64>,
+    // Defaults to no minimums, offers in any denom are allowed
+    pub min_offer_value: Option<Vec<Coin>>,
+    // Defaults to 0, listings take any number of offers
+    pub max_offers: Option<u32>,
+    // Defaults to 0, unit listings sell every unit
+    pub dust_threshold: Option<Uint128>,
 }
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -27,6 +82,17 @@ pub enum ExecuteMsg {
     CreateListing {
         create_msg: CreateListingMsg,
     },
+    // Seller only, lists the native funds sent under the terms of an open or archived listing
+    // Cw20 & NFTs can't be sent here, add them to the clone while it's being prepared
+    CloneListing {
+        source_listing_id: String,
+    },
+    // Pulls cw20 tokens the sender has approved, listing is created once they arrive
+    CreateListingCw20ViaAllowance {
+        create_msg: CreateListingMsg,
+        token: String,
+        amount: Uint128,
+    },
     // Edit Listing
     AddFundsToSaleNative {
         listing_id: String,
@@ -42,18 +108,45 @@ pub enum ExecuteMsg {
     RemoveWhitelistedBuyer {
         listing_id: String,
     },
+    // Replaces the whole whitelist, bounded in size
+    SetWhitelistedBuyers {
+        listing_id: String,
+        addresses: Vec<String>,
+    },
+    // Owner only, works on finalized listings too, a posted bond is still refunded to its poster
+    TransferListing {
+        listing_id: String,
+        new_owner: String,
+    },
     RemoveListing {
         listing_id: String,
     },
+    // Removes all of the sender's listings that can be removed, paginated for gas safety
+    RemoveAllListings {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     // Makes Listing available for purchase & sets expiration time
+    // Seconds defaults to the config's default_listing_ttl
+    // A future expiration_height makes the listing expire by block height instead, seconds are ignored
+    // Listings that relist on expiry can only expire by time
     Finalize {
         listing_id: String,
-        seconds: u64,
+        seconds: Option<u64>,
+        expiration_height: Option<u64>,
     },
     // Only callable when Listing is expired
     RefundExpired {
         listing_id: String,
     },
+    // Anyone can call once a listing is expired, runs the listing's on_expire action
+    ProcessExpiry {
+        listing_id: String,
+    },
+    // Counts once per address, used for ranking listings
+    ExpressInterest {
+        listing_id: String,
+    },
     CreateBucket {
         bucket_id: String,
     },
@@ -63,19 +156,197 @@ pub enum ExecuteMsg {
     RemoveBucket {
         bucket_id: String,
     },
+    // Owner only, committed buckets can't be transferred
+    TransferBucket {
+        bucket_id: String,
+        new_owner: String,
+    },
+    // Refunds all of the sender's buckets, paginated for gas safety
+    WithdrawAllBuckets {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Advisory hint of which listing a bucket is meant to buy
+    SetBucketTarget {
+        bucket_id: String,
+        target_listing: Option<String>,
+    },
+    // Rejected with PriceSlippage if the ask costs more than max_price
+    // The referrer gets the config's referral_bps of the maker & taker fees
     BuyListing {
         listing_id: String,
         bucket_id: String,
+        max_price: Option<GenericBalance>,
+        referrer: Option<String>,
+    },
+    // Locks the bucket until the listing is bought with it, or the listing can't be bought anymore
+    CommitToListing {
+        listing_id: String,
+        bucket_id: String,
+    },
+    // Anyone can settle, buys the listing with the committed bucket
+    SettleCommitment {
+        listing_id: String,
+    },
+    // Locks the bucket as an offer the seller can accept, until it expires or the listing is gone
+    LockBucketToListing {
+        bucket_id: String,
+        listing_id: String,
+        expiration_seconds: Option<u64>,
+    },
+    // Seller only, sells the listing for the offered bucket
+    AcceptOffer {
+        listing_id: String,
+        buyer: String,
+        bucket_id: String,
+    },
+    // Seller only, sells the listing for only accepted_assets of the offered bucket
+    // The rest stays in the buyer's bucket, unlocked
+    AcceptPartialOffer {
+        listing_id: String,
+        buyer: String,
+        bucket_id: String,
+        accepted_assets: GenericBalance,
     },
     WithdrawPurchased {
         listing_id: String,
     },
+    // Owner only, sells an unfinalized listing to the highest sealed bid, its ask is the reserve
+    StartSealedAuction {
+        listing_id: String,
+        commit_seconds: u64,
+        reveal_seconds: u64,
+    },
+    // Send the most you'd pay in the reserve's denom
+    // The commitment is the sha256 of "{bidder}:{amount}:{nonce}", revealed once bidding closes
+    CommitBid {
+        listing_id: String,
+        commitment: HexBinary,
+    },
+    RevealBid {
+        listing_id: String,
+        amount: Uint128,
+        nonce: String,
+    },
+    // Anyone, once the reveal phase is over, sells to the highest revealed bid & refunds the rest
+    // Open auctions are settled the same way once their end_time has passed
+    SettleAuction {
+        listing_id: String,
+    },
+    // Owner only, sells a finalized listing to the highest open bid by end_time instead of its ask
+    ConvertToAuction {
+        listing_id: String,
+        reserve: Coin,
+        min_increment: Uint128,
+        end_time: Timestamp,
+    },
+    // Owner only, replaces the params of an open auction until its first bid
+    UpdateAuctionParams {
+        listing_id: String,
+        reserve: Coin,
+        min_increment: Uint128,
+        end_time: Timestamp,
+    },
+    // Send at least amount plus the taker fee in the reserve's denom, an outbid escrow is refunded
+    PlaceBid {
+        listing_id: String,
+        amount: Uint128,
+    },
+    // Owner only, sells to the highest bid before end_time
+    // The first bid must already meet the reserve, so this only fails before anyone has bid
+    AcceptHighestBid {
+        listing_id: String,
+    },
+    // Owner of listing_id only, offers its assets for those of another owner's listing
+    // Both listings must be finalized, so neither's contents can change before it's accepted
+    ProposeSwap {
+        listing_id: String,
+        counter_listing_id: String,
+    },
+    // Owner of listing_id only, each owner is sent the other listing's assets & both are closed
+    AcceptSwap {
+        proposer_listing_id: String,
+        listing_id: String,
+    },
+    // Either owner, drops the proposal
+    RejectSwap {
+        proposer_listing_id: String,
+        listing_id: String,
+    },
+    // Owner of every listing only, offers them together for price
+    // The listings must share a payout address & auto_swap_to, the proceeds are paid out together
+    CreateBundle {
+        listing_ids: Vec<String>,
+        price: GenericBalance,
+    },
+    // Buys every listing in the bundle or none, the bucket must hold the price plus the taker fee
+    BuyBundle {
+        bundle_id: u64,
+        bucket_id: String,
+    },
+    // Withdraws only these assets of a bought listing, the rest can be withdrawn later
+    WithdrawPurchasedPartial {
+        listing_id: String,
+        assets: GenericBalance,
+    },
+    // Send exactly the config's listing bond, required before finalizing when set
+    PostBond {
+        listing_id: String,
+    },
+    // Admin only, returns the listing to its creator & forfeits its bond to the fee collector
+    ForceRemoveListing {
+        listing_id: String,
+    },
+    // Admin only, blocks buying & editing a listing during a dispute
+    FreezeListing {
+        listing_id: String,
+        frozen: bool,
+    },
+    // Admin only, fields left as None are unchanged
+    UpdateConfig {
+        config_msg: UpdateConfigMsg,
+    },
+    // Admin only, the action can be executed once the timelock has passed
+    QueueAdminAction {
+        action: AdminAction,
+    },
+    ExecuteQueuedAction {
+        action_id: u64,
+    },
+    // Admin only & permanent, blocks new listings, buckets & buys to sunset the contract
+    WindDown {},
+    // Admin only, deletes the listings from the archive
+    PruneArchive {
+        listing_ids: Vec<String>,
+    },
+    // Admin only, known receivers are paid out NFTs with SendNft instead of TransferNft
+    SetNftReceiver {
+        address: String,
+        known: bool,
+    },
+    // Admin only, who can create listings while the config's restrict_creators is set
+    SetCreatorAllowed {
+        address: String,
+        allowed: bool,
+    },
+    // Admin only, rate of a denom or cw20 address in a common denom, None removes it
+    SetConversionRate {
+        from: String,
+        to: String,
+        rate: Option<Decimal>,
+    },
+    // Retries sending an NFT whose payout failed when its listing was removed
+    ClaimNft {
+        contract: String,
+        token_id: String,
+    },
 }
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // cw20 entry point
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 #[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ReceiveMsg {
     CreateListingCw20 {
         create_msg: CreateListingMsg,
@@ -95,6 +366,7 @@ pub enum ReceiveMsg {
 // cw721 entry point
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 #[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ReceiveNftMsg {
     CreateListingCw721 {
         create_msg: CreateListingMsg,
@@ -120,12 +392,49 @@ pub enum QueryMsg {
     GetAdmin {},
     #[returns(ConfigResponse)]
     GetConfig {},
+    #[returns(LimitsResponse)]
+    GetLimits {},
+    #[returns(QueuedActionsResponse)]
+    GetQueuedActions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(NextListingIdResponse)]
+    GetNextListingId {},
+    #[returns(UniqueUsersResponse)]
+    GetUniqueUsers {},
     #[returns(MultiListingResponse)]
     GetAllListings {},
     #[returns(ListingInfoResponse)]
     GetListingInfo {
         listing_id: String,
     },
+    #[returns(BundleContentsResponse)]
+    GetBundleContents {
+        listing_id: String,
+    },
+    // Ask changes of a listing, oldest first
+    #[returns(ListingHistoryResponse)]
+    GetListingHistory {
+        listing_id: String,
+    },
+    // Collections with NFTs in open listings
+    #[returns(ActiveCollectionsResponse)]
+    GetActiveCollections {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Native denoms & cw20 addresses held or asked for by open listings
+    #[returns(ActiveDenomsResponse)]
+    GetActiveDenoms {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Listing that was sold, removed or refunded, unless pruned
+    #[returns(ArchivedListingResponse)]
+    GetArchivedListing {
+        listing_id: String,
+    },
     #[returns(MultiListingResponse)]
     GetListingsByOwner {
         owner: String,
@@ -134,19 +443,245 @@ pub enum QueryMsg {
     GetBuckets {
         bucket_owner: String,
     },
+    // What a bucket still needs to buy a listing, empty once nothing is missing
+    #[returns(BucketShortfallResponse)]
+    GetBucketShortfall {
+        bucket_owner: String,
+        bucket_id: String,
+        listing_id: String,
+    },
+    // Finalized listings the bucket's owner can buy & the bucket holds enough for, for dashboards
+    #[returns(BucketPowerResponse)]
+    GetBucketPower {
+        bucket_owner: String,
+        bucket_id: String,
+    },
+    // Whether the address can buy the listing, ignoring what its buckets hold
+    #[returns(CanBuyResponse)]
+    CanBuy {
+        listing_id: String,
+        address: String,
+    },
+    // Payout of buying the listing with the bucket, without the checks on the listing's state
+    #[returns(SimulateBuyResponse)]
+    SimulateBuy {
+        bucket_owner: String,
+        bucket_id: String,
+        listing_id: String,
+    },
+    #[returns(GetBucketsResponse)]
+    GetOrphanedBuckets {
+        owner: String,
+    },
+    // Buckets holding at least `min` of a native denom or cw20 address
+    #[returns(GetBucketsResponse)]
+    GetBucketsWithMinValue {
+        denom: String,
+        min: Uint128,
+        limit: Option<u32>,
+    },
     #[returns(MultiListingResponse)]
     GetListingsForMarket {
         page_num: u8,
     },
+    // Most expressed interest first, among the oldest 100 finalized listings
+    #[returns(TrendingListingsResponse)]
+    GetTrendingListings {
+        limit: Option<u32>,
+    },
     #[returns(MultiListingResponse)]
     GetWhitelistedListings {
         address: String,
     },
+    // A buyer's invited deals, the listings whitelisting them at the given stage by listing ID
+    #[returns(MultiListingResponse)]
+    GetMyWhitelistedListings {
+        address: String,
+        status: ListingStage,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(MultiListingResponse)]
+    GetListingsAcceptingNft {
+        contract: String,
+        token_id: String,
+    },
+    #[returns(MultiListingResponse)]
+    GetListingsByStatus {
+        status: Status,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(AddressActivityResponse)]
+    GetAddressActivity {
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(LastSalePriceResponse)]
+    GetLastSalePrice {
+        contract: String,
+        denom: String,
+    },
+    #[returns(SellerEarningsResponse)]
+    GetSellerEarnings {
+        seller: String,
+    },
+    #[returns(FloorHistoryResponse)]
+    GetFloorHistory {
+        contract: String,
+        denom: String,
+        limit: Option<u32>,
+    },
+    // Assets without a conversion rate to the denom are listed apart
+    #[returns(TvlResponse)]
+    GetTvlInDenom {
+        denom: String,
+    },
+    // Contract balance vs the tracked holdings of a native denom, for audits
+    #[returns(ReconciliationResponse)]
+    GetReconciliation {
+        denom: String,
+    },
+    #[returns(SealedAuctionResponse)]
+    GetSealedAuction {
+        listing_id: String,
+    },
+    #[returns(AuctionResponse)]
+    GetAuction {
+        listing_id: String,
+    },
+    #[returns(BundleResponse)]
+    GetBundle {
+        bundle_id: u64,
+    },
+    // Newest first, at most the last 20 sales are kept
+    #[returns(RecentSalesResponse)]
+    GetRecentSales {
+        limit: Option<u32>,
+    },
+    #[returns(ListingForNftResponse)]
+    GetListingForNft {
+        contract: String,
+        token_id: String,
+    },
+    // Open listings of every owner holding an NFT of the collection, by listing ID
+    #[returns(MultiListingResponse)]
+    GetListingsByCollection {
+        contract: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Live offers expiring within the next within_seconds, soonest first
+    #[returns(OffersResponse)]
+    GetOffersExpiringSoon {
+        within_seconds: u64,
+        limit: Option<u32>,
+    },
+    // Live offers on the listing, highest first by their amount of denom, a native or cw20
+    // Offers without any of denom come last
+    #[returns(BucketOffersResponse)]
+    GetOffersSorted {
+        listing_id: String,
+        denom: String,
+    },
+    // Live offers made by buyer, with the funds each locked bucket holds
+    #[returns(BucketOffersResponse)]
+    GetOffersByBuyer {
+        buyer: String,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct UpdateConfigMsg {
+    pub refund_grace_seconds: Option<u64>,
+    pub auto_match: Option<bool>,
+    pub rounding: Option<Rounding>,
+    pub validate_cw20_asks: Option<bool>,
+    pub max_transfers_per_buy: Option<u32>,
+    pub maker_fee_bps: Option<u64>,
+    pub taker_fee_bps: Option<u64>,
+    pub fee_collector: Option<String>,
+    pub admin_timelock_seconds: Option<u64>,
+    // A zero amount stops requiring a bond
+    pub listing_bond: Option<Coin>,
+    // Replaces every minimum, an empty list removes them
+    pub min_market_ask_value: Option<Vec<Coin>>,
+    // 0 removes the default
+    pub default_listing_ttl: Option<u64>,
+    pub referral_bps: Option<u64>,
+    pub max_page_size: Option<u32>,
+    pub min_active_seconds: Option<u64>,
+    // An empty address removes the router
+    pub swap_router: Option<String>,
+    pub max_nfts_per_collection_per_listing: Option<u32>,
+    pub restrict_creators: Option<bool>,
+    // A time in the past ends the holiday
+    pub fee_holiday_until: Option<Timestamp>,
+    pub reveal_deposit_slash_bps: Option<u64>,
+    pub buy_cooldown_seconds: Option<u64>,
+    // Replaces every minimum, an empty list removes them
+    pub min_offer_value: Option<Vec<Coin>>,
+    pub max_offers: Option<u32>,
+    pub dust_threshold: Option<Uint128>,
+}
+
+// Open listings are being prepared, expired ones are finalized but can no longer be bought
+#[cw_serde]
+pub enum ListingStage {
+    Open,
+    Finalized,
+    Expired,
+}
+
+#[cw_serde]
+pub enum AdminAction {
+    UpdateConfig {
+        config_msg: UpdateConfigMsg,
+    },
 }
 
 #[cw_serde]
 pub struct CreateListingMsg {
-    pub id: String,
     pub ask: GenericBalance,
     pub whitelisted_buyer: Option<String>,
+    // End of the presale, anyone can buy after it
+    pub whitelist_until: Option<Timestamp>,
+    pub payout_address: Option<String>,
+    pub min_buyer_purchases: Option<u64>,
+    // Fungible assets unlock linearly over this many seconds after purchase, NFTs at the end
+    pub vesting_seconds: Option<u64>,
+    // Defaults to Refund
+    pub on_expire: Option<OnExpire>,
+    // Defaults to false
+    pub private: Option<bool>,
+    // Alternatives to the ask, the buyer's bucket picks which one it pays
+    pub ask_options: Option<Vec<GenericBalance>>,
+    // Native denom to swap the sale proceeds to, falls back to the paid denoms if the swap fails
+    pub auto_swap_to: Option<String>,
+    // Sells for_sale in this many equal units at the ask each, fungible only & without vesting
+    pub units: Option<u32>,
+    // Contract & msg executed when the listing sells, a failing callback doesn't revert the sale
+    pub on_sale_callback: Option<SaleCallbackMsg>,
+    // Sales netting the seller less than this in a denom after fees fail, auctions use the reserve
+    pub min_net_proceeds: Option<Vec<Coin>>,
+    // Cap on the listing's live offers instead of the config's max_offers, 0 = no cap
+    pub max_offers: Option<u32>,
+}
+
+#[cw_serde]
+pub struct SaleCallbackMsg {
+    pub contract_addr: String,
+    pub msg: Binary,
+}
+
+// Interface the config's swap router must implement
+#[cw_serde]
+pub enum SwapRouterExecuteMsg {
+    // Swaps the funds sent to ask_denom & sends them to the recipient
+    Swap {
+        ask_denom: String,
+        recipient: String,
+    },
 }
@@ -1,13 +1,63 @@
-use crate::state::{listingz, Bucket, Config, Listing, Status, BUCKETS, CONFIG};
+use crate::error::ContractError;
+use crate::execute::{
+    ensure_can_buy, live_offer, matching_ask, past_expiry, MAX_ASK_OPTIONS,
+    MAX_BUCKETS_PER_WITHDRAW, MAX_FEE_BPS, MAX_FINALIZE_SECONDS, MAX_LISTINGS_PER_REMOVE,
+    MAX_WHITELISTED_BUYERS, MIN_FINALIZE_SECONDS,
+};
+use crate::msg::{AdminAction, ListingStage};
+use crate::state::{
+    bucket_offers, listingz, ActivityRole, AskChange, Auction, Bucket, Bundle, Config,
+    FloorSnapshot, GenericBalance, Listing, Offer, Sale, Status, ACTIVE_COLLECTIONS,
+    ADDRESS_ACTIVITY, ARCHIVED_LISTINGS, ASK_HISTORY, AUCTIONS, BUCKETS, BUNDLES,
+    COLLECTION_LISTINGS, CONFIG, CONVERSION_RATES, FLOOR_HISTORY, INTEREST_COUNT, LAST_SALE_PRICE,
+    LISTING_BOND, MAX_ASK_HISTORY, NEXT_LISTING_ID, NFT_LISTING, QUEUED_ACTIONS, RECENT_SALES,
+    SEALED_AUCTION, SEALED_BIDS, SELLER_EARNINGS, UNIQUE_USERS,
+};
+use crate::utils::{
+    add_juno, amount_of, ask_meets_market_minimum, ask_shortfall, calc_fee, maker_taker_fees,
+    sub_juno, NATIVE,
+};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::StdError;
-use cosmwasm_std::{Deps, Env, Order, StdResult};
-use cw_storage_plus::PrefixBound;
+use cosmwasm_std::{Addr, Coin, Deps, Env, Order, StdResult, Timestamp, Uint128};
+use cw_storage_plus::{Bound, KeyDeserialize, PrefixBound};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+// Pagination default for queries taking a limit, capped by the config's max_page_size
+const DEFAULT_LIMIT: u32 = 10;
+
+// Finalized listings GetTrendingListings ranks, oldest first
+const MAX_TRENDING_SCAN: usize = 100;
+
+// Finalized listings GetBucketPower checks, oldest first
+const MAX_BUCKET_POWER_SCAN: usize = 100;
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Queries
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+// Requested limit clamped to the config's max page size
+fn page_limit(deps: Deps, limit: Option<u32>) -> StdResult<usize> {
+    let max_page_size = CONFIG.load(deps.storage)?.max_page_size;
+    Ok(limit.unwrap_or(DEFAULT_LIMIT).min(max_page_size) as usize)
+}
+
+// Queries that look up a single listing or bucket fail with the same typed errors as execute
+fn load_listing(deps: Deps, listing_id: String) -> Result<Listing, ContractError> {
+    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id.clone())? else {
+        return Err(ContractError::ListingNotFound { id: listing_id });
+    };
+    Ok(listing)
+}
+
+fn load_bucket(deps: Deps, bucket_owner: &str, bucket_id: &str) -> Result<Bucket, ContractError> {
+    let bucket_owner = deps.api.addr_validate(bucket_owner)?;
+    let Some(bucket) = BUCKETS.may_load(deps.storage, (bucket_owner, bucket_id))? else {
+        return Err(ContractError::BucketNotFound { id: bucket_id.to_string() });
+    };
+    Ok(bucket)
+}
+
 // Get contract admin
 pub fn get_admin(deps: Deps) -> StdResult<AdminResponse> {
     let storage = CONFIG.load(deps.storage)?;
@@ -24,6 +74,65 @@ pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
     })
 }
 
+// Get every numeric limit the contract enforces, so clients don't hardcode them
+pub fn get_limits(deps: Deps) -> StdResult<LimitsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(LimitsResponse {
+        min_finalize_seconds: MIN_FINALIZE_SECONDS,
+        max_finalize_seconds: MAX_FINALIZE_SECONDS,
+        refund_grace_seconds: config.refund_grace_seconds,
+        max_whitelisted_buyers: MAX_WHITELISTED_BUYERS as u64,
+        max_buckets_per_withdraw: MAX_BUCKETS_PER_WITHDRAW,
+        max_listings_per_remove: MAX_LISTINGS_PER_REMOVE,
+        max_ask_history: MAX_ASK_HISTORY as u64,
+        max_page_size: config.max_page_size,
+        max_transfers_per_buy: config.max_transfers_per_buy,
+        max_fee_bps: MAX_FEE_BPS,
+        max_ask_options: MAX_ASK_OPTIONS as u64,
+        max_nfts_per_collection_per_listing: config.max_nfts_per_collection_per_listing,
+    })
+}
+
+// Get queued admin actions & when they can be executed
+pub fn get_queued_actions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<QueuedActionsResponse> {
+    let limit = page_limit(deps, limit)?;
+    let start = start_after.map(Bound::exclusive);
+
+    let actions = QUEUED_ACTIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(action_id, queued)| QueuedActionInfo {
+                action_id,
+                action: queued.action,
+                eta: queued.eta.seconds(),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(QueuedActionsResponse {
+        actions,
+    })
+}
+
+// Get how many addresses have ever created a listing or bucket
+pub fn get_unique_users(deps: Deps) -> StdResult<UniqueUsersResponse> {
+    Ok(UniqueUsersResponse {
+        unique_users: UNIQUE_USERS.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+// Get the ID the next created listing will be given
+pub fn get_next_listing_id(deps: Deps) -> StdResult<NextListingIdResponse> {
+    Ok(NextListingIdResponse {
+        next_listing_id: NEXT_LISTING_ID.load(deps.storage)?,
+    })
+}
+
 // Get all buckets owned by an address
 pub fn get_buckets(deps: Deps, bucket_owner: &str) -> StdResult<GetBucketsResponse> {
     let bucket_ownerx = deps.api.addr_validate(bucket_owner)?;
@@ -36,11 +145,336 @@ pub fn get_buckets(deps: Deps, bucket_owner: &str) -> StdResult<GetBucketsRespon
     })
 }
 
-// Get a single listing by a Listing ID
-pub fn get_listing_info(deps: Deps, listing_id: String) -> StdResult<ListingInfoResponse> {
-    let Some((_pk, listing)): Option<(_, Listing)> = listingz().idx.id.item(deps.storage, listing_id)? else {
-        return Err(StdError::GenericErr { msg: "Invalid listing ID".to_string() });
+// Get the assets a bucket is missing to satisfy a listing's ask
+pub fn get_bucket_shortfall(
+    deps: Deps,
+    bucket_owner: &str,
+    bucket_id: &str,
+    listing_id: String,
+) -> Result<BucketShortfallResponse, ContractError> {
+    let bucket = load_bucket(deps, bucket_owner, bucket_id)?;
+    let listing = load_listing(deps, listing_id)?;
+
+    Ok(BucketShortfallResponse {
+        missing: ask_shortfall(&bucket.funds, &listing.ask),
+    })
+}
+
+// How many finalized listings a bucket holds enough for, plus the taker fee, & what they ask in all
+// A listing counts once, priced at the first of its asks the bucket covers
+pub fn get_bucket_power(
+    deps: Deps,
+    env: &Env,
+    bucket_owner: &str,
+    bucket_id: &str,
+) -> Result<BucketPowerResponse, ContractError> {
+    let bucket = load_bucket(deps, bucket_owner, bucket_id)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut affordable_listings = 0_u64;
+    let mut affordable_value: BTreeMap<String, Uint128> = BTreeMap::new();
+    for entry in listingz()
+        .idx
+        .status
+        .prefix(Status::FinalizedReady as u8)
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(MAX_BUCKET_POWER_SCAN)
+    {
+        let (_pk, listing) = entry?;
+        if ensure_can_buy(deps.storage, env, &config, &listing, &bucket.owner).is_err() {
+            continue;
+        }
+        let mut covered = None;
+        for ask in std::iter::once(&listing.ask).chain(&listing.ask_options) {
+            let (_maker_fee, taker_fee) = maker_taker_fees(ask, &config, env.block.time)?;
+            let missing = ask_shortfall(&bucket.funds, &add_juno(ask, taker_fee)?);
+            if missing.native.is_empty() && missing.cw20.is_empty() && missing.nfts.is_empty() {
+                covered = Some(ask);
+                break;
+            }
+        }
+        let Some(ask) = covered else {
+            continue;
+        };
+        affordable_listings += 1;
+        let amounts = ask.native.iter().map(|coin| (coin.denom.clone(), coin.amount)).chain(
+            ask.cw20.iter().map(|cw20_coin| (cw20_coin.address.to_string(), cw20_coin.amount)),
+        );
+        for (denom, amount) in amounts {
+            *affordable_value.entry(denom).or_default() += amount;
+        }
+    }
+
+    Ok(BucketPowerResponse {
+        affordable_listings,
+        affordable_value: affordable_value
+            .into_iter()
+            .map(|(denom, amount)| Coin {
+                denom,
+                amount,
+            })
+            .collect(),
+    })
+}
+
+// Simulate buying a listing with a bucket, the payout is only set when the bucket pays an ask
+// The whole bucket is paid, so nothing is ever refunded to the buyer
+pub fn simulate_buy(
+    deps: Deps,
+    env: &Env,
+    bucket_owner: &str,
+    bucket_id: &str,
+    listing_id: String,
+) -> Result<SimulateBuyResponse, ContractError> {
+    let bucket = load_bucket(deps, bucket_owner, bucket_id)?;
+    let listing = load_listing(deps, listing_id)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let Some((paid_ask, maker_fee, taker_fee)) = matching_ask(&listing, &bucket.funds, &config, env.block.time)? else {
+        return Ok(SimulateBuyResponse { satisfies_ask: false, payout: None });
+    };
+
+    // The buyer's withdrawal pays the community pool fee out of the bundle
+    let buyer_receives = calc_fee(&listing.for_sale, &config.rounding)?
+        .map_or_else(|| listing.for_sale.clone(), |(_fee_msg, received)| received);
+    let juno = |balance: &GenericBalance| {
+        balance.native.iter().filter(|n| n.denom == NATIVE).map(|n| n.amount).sum::<Uint128>()
+    };
+
+    Ok(SimulateBuyResponse {
+        satisfies_ask: true,
+        payout: Some(BuyPayout {
+            paid_ask,
+            seller_receives: sub_juno(&bucket.funds, maker_fee + taker_fee)?,
+            marketplace_fee: maker_fee + taker_fee,
+            community_pool_fee: juno(&listing.for_sale) - juno(&buyer_receives),
+            buyer_receives,
+        }),
+    })
+}
+
+// Check if an address passes every gate on buying a listing, the reason is the error a buy would return
+pub fn can_buy(
+    deps: Deps,
+    env: &Env,
+    listing_id: String,
+    address: &str,
+) -> StdResult<CanBuyResponse> {
+    let address = deps.api.addr_validate(address)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let listing = listingz().idx.id.item(deps.storage, listing_id.clone())?;
+    let gate = if config.wind_down {
+        Err(ContractError::WoundDown {})
+    } else if let Some((_pk, listing)) = listing {
+        ensure_can_buy(deps.storage, env, &config, &listing, &address)
+    } else {
+        Err(ContractError::ListingNotFound {
+            id: listing_id,
+        })
+    };
+
+    Ok(CanBuyResponse {
+        can_buy: gate.is_ok(),
+        reason: gate.err().map(|e| e.to_string()),
+    })
+}
+
+// Get buckets owned by an address whose target listing no longer exists
+pub fn get_orphaned_buckets(deps: Deps, owner: &str) -> StdResult<GetBucketsResponse> {
+    let owner = deps.api.addr_validate(owner)?;
+
+    let mut orphaned: Vec<(String, Bucket)> = Vec::new();
+
+    for entry in BUCKETS.prefix(owner).range(deps.storage, None, None, Order::Ascending) {
+        let (bucket_id, bucket) = entry?;
+        let Some(target) = bucket.target_listing.clone() else {
+            continue;
+        };
+        if listingz().idx.id.item(deps.storage, target)?.is_none() {
+            orphaned.push((bucket_id, bucket));
+        }
+    }
+
+    Ok(GetBucketsResponse {
+        buckets: orphaned,
+    })
+}
+
+// Get buckets from any owner holding at least `min` of a native denom or cw20 address
+pub fn get_buckets_with_min_value(
+    deps: Deps,
+    denom: &str,
+    min: Uint128,
+    limit: Option<u32>,
+) -> StdResult<GetBucketsResponse> {
+    let limit = page_limit(deps, limit)?;
+
+    let held = |bucket: &Bucket| -> Uint128 {
+        let native =
+            bucket.funds.native.iter().find(|coin| coin.denom == denom).map(|coin| coin.amount);
+        let cw20 =
+            bucket.funds.cw20.iter().find(|coin| coin.address == denom).map(|coin| coin.amount);
+        native.or(cw20).unwrap_or_default()
+    };
+
+    let mut buckets: Vec<(String, Bucket)> = Vec::new();
+
+    for entry in BUCKETS.range(deps.storage, None, None, Order::Ascending) {
+        let ((_owner, bucket_id), bucket) = entry?;
+        if held(&bucket) >= min {
+            buckets.push((bucket_id, bucket));
+            if buckets.len() == limit {
+                break;
+            }
+        }
+    }
+
+    Ok(GetBucketsResponse {
+        buckets,
+    })
+}
+
+// Get the fungible assets held in listings & buckets, converted to `denom` where a rate is set
+pub fn get_tvl_in_denom(deps: Deps, denom: &str) -> StdResult<TvlResponse> {
+    let mut held: BTreeMap<String, Uint128> = BTreeMap::new();
+    let mut add = |balance: &GenericBalance| {
+        for coin in &balance.native {
+            *held.entry(coin.denom.clone()).or_default() += coin.amount;
+        }
+        for coin in &balance.cw20 {
+            *held.entry(coin.address.to_string()).or_default() += coin.amount;
+        }
+    };
+
+    for entry in listingz().range(deps.storage, None, None, Order::Ascending) {
+        let (_pk, listing) = entry?;
+        add(&listing.for_sale);
+    }
+    for entry in BUCKETS.range(deps.storage, None, None, Order::Ascending) {
+        let (_pk, bucket) = entry?;
+        add(&bucket.funds);
+    }
+
+    let mut total = Uint128::zero();
+    let mut unconverted: Vec<Coin> = Vec::new();
+    for (held_denom, amount) in held {
+        if held_denom == denom {
+            total += amount;
+        } else if let Some(rate) = CONVERSION_RATES.may_load(deps.storage, (&held_denom, denom))? {
+            total += amount * rate;
+        } else {
+            unconverted.push(Coin {
+                denom: held_denom,
+                amount,
+            });
+        }
+    }
+
+    Ok(TvlResponse {
+        total,
+        unconverted,
+    })
+}
+
+// Compare the contract's bank balance of `denom` with what listings, buckets & bonds account for
+pub fn get_reconciliation(deps: Deps, env: &Env, denom: &str) -> StdResult<ReconciliationResponse> {
+    let balance = deps.querier.query_balance(&env.contract.address, denom)?.amount;
+
+    let held = |balance: &GenericBalance| -> Uint128 {
+        balance.native.iter().filter(|coin| coin.denom == denom).map(|coin| coin.amount).sum()
+    };
+    let mut tracked = Uint128::zero();
+    for entry in listingz().range(deps.storage, None, None, Order::Ascending) {
+        let (_pk, listing) = entry?;
+        tracked += held(&listing.for_sale);
+    }
+    for entry in BUCKETS.range(deps.storage, None, None, Order::Ascending) {
+        let (_pk, bucket) = entry?;
+        tracked += held(&bucket.funds);
+    }
+    for entry in LISTING_BOND.range(deps.storage, None, None, Order::Ascending) {
+        let (_listing_id, (_poster, bond)) = entry?;
+        if bond.denom == denom {
+            tracked += bond.amount;
+        }
+    }
+    for entry in SEALED_AUCTION.range(deps.storage, None, None, Order::Ascending) {
+        let (listing_id, auction) = entry?;
+        if auction.reserve.denom != denom {
+            continue;
+        }
+        for bid in SEALED_BIDS.prefix(&listing_id).range(deps.storage, None, None, Order::Ascending)
+        {
+            tracked += bid?.1.escrow;
+        }
+    }
+    for entry in AUCTIONS.range(deps.storage, None, None, Order::Ascending) {
+        let (_listing_id, auction) = entry?;
+        if let Some(high_bid) = auction.high_bid.filter(|_| auction.reserve.denom == denom) {
+            tracked += high_bid.escrow;
+        }
+    }
+
+    Ok(ReconciliationResponse {
+        balance,
+        tracked,
+        surplus: balance.saturating_sub(tracked),
+        deficit: tracked.saturating_sub(balance),
+    })
+}
+
+// A listing's sealed-bid auction, bids stay hidden until they're revealed
+pub fn get_sealed_auction(
+    deps: Deps,
+    listing_id: String,
+) -> Result<SealedAuctionResponse, ContractError> {
+    let Some(auction) = SEALED_AUCTION.may_load(deps.storage, &listing_id)? else {
+        return Err(ContractError::NotSealedAuction {});
+    };
+    let bids = SEALED_BIDS
+        .prefix(&listing_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let revealed = bids.iter().filter(|(_bidder, bid)| bid.revealed.is_some()).count();
+
+    Ok(SealedAuctionResponse {
+        reserve: auction.reserve,
+        commit_end: auction.commit_end,
+        reveal_end: auction.reveal_end,
+        bids: bids.len() as u64,
+        revealed: revealed as u64,
+    })
+}
+
+// A listing's open auction & its highest bid
+pub fn get_auction(deps: Deps, listing_id: String) -> Result<AuctionResponse, ContractError> {
+    let Some(auction) = AUCTIONS.may_load(deps.storage, &listing_id)? else {
+        return Err(ContractError::NotAuction {});
     };
+    Ok(AuctionResponse {
+        auction,
+    })
+}
+
+pub fn get_bundle(deps: Deps, bundle_id: u64) -> Result<BundleResponse, ContractError> {
+    let Some(bundle) = BUNDLES.may_load(deps.storage, bundle_id)? else {
+        return Err(ContractError::NotFound {
+            typ: "Bundle".to_string(),
+            id: bundle_id.to_string(),
+        });
+    };
+    Ok(BundleResponse {
+        bundle,
+    })
+}
+
+// Get a single listing by a Listing ID
+pub fn get_listing_info(
+    deps: Deps,
+    listing_id: String,
+) -> Result<ListingInfoResponse, ContractError> {
+    let listing = load_listing(deps, listing_id.clone())?;
 
     let status = match listing.status {
         Status::BeingPrepared => "Being Prepared".to_string(),
@@ -92,8 +526,10 @@ pub fn get_listing_info(deps: Deps, listing_id: String) -> StdResult<ListingInfo
         ));
     });
 
-    let whitelist_buyer: String =
-        listing.whitelisted_buyer.map_or_else(|| "None".to_string(), |x| x.to_string());
+    let whitelisted_buyers: Vec<String> =
+        listing.whitelisted_buyers.iter().map(ToString::to_string).collect();
+
+    let interest = INTEREST_COUNT.may_load(deps.storage, &listing_id)?.unwrap_or_default();
 
     let mut res: ListingInfoResponse = ListingInfoResponse {
         creator: listing.creator.to_string(),
@@ -101,16 +537,86 @@ pub fn get_listing_info(deps: Deps, listing_id: String) -> StdResult<ListingInfo
         for_sale: the_sale,
         ask: the_ask,
         expiration: "None".to_string(),
-        whitelisted_buyer: whitelist_buyer,
+        whitelisted_buyers,
+        whitelist_until: listing.whitelist_until,
+        interest,
     };
 
     if let Some(x) = listing.expiration_time {
         res.expiration = x.seconds().to_string();
     };
+    if let Some(height) = listing.expiration_height {
+        res.expiration = format!("block {height}");
+    };
 
     Ok(res)
 }
 
+// Get every asset in a listing's for_sale bundle, bought together or not at all
+pub fn get_bundle_contents(
+    deps: Deps,
+    listing_id: String,
+) -> Result<BundleContentsResponse, ContractError> {
+    let listing = load_listing(deps, listing_id.clone())?;
+
+    Ok(BundleContentsResponse {
+        listing_id,
+        for_sale: listing.for_sale,
+    })
+}
+
+// Get the ask changes of a listing, oldest first
+pub fn get_listing_history(deps: Deps, listing_id: &str) -> StdResult<ListingHistoryResponse> {
+    Ok(ListingHistoryResponse {
+        changes: ASK_HISTORY.may_load(deps.storage, listing_id)?.unwrap_or_default(),
+    })
+}
+
+pub fn get_archived_listing(
+    deps: Deps,
+    listing_id: &str,
+) -> Result<ArchivedListingResponse, ContractError> {
+    let Some(listing) = ARCHIVED_LISTINGS.may_load(deps.storage, listing_id)? else {
+        return Err(ContractError::ListingNotFound { id: listing_id.to_string() });
+    };
+    Ok(ArchivedListingResponse {
+        listing,
+    })
+}
+
+// Collections with NFTs in open listings, by contract address
+pub fn get_active_collections(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ActiveCollectionsResponse> {
+    let limit = page_limit(deps, limit)?;
+    let start_after = start_after.map(|s| deps.api.addr_validate(&s)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let collections = ACTIVE_COLLECTIONS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|collection| collection.map(Addr::into_string))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ActiveCollectionsResponse {
+        collections,
+    })
+}
+
+pub fn get_active_denoms(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ActiveDenomsResponse> {
+    let limit = page_limit(deps, limit)?;
+
+    Ok(ActiveDenomsResponse {
+        denoms: listingz().idx.denoms.keys(deps.storage, start_after, limit)?,
+    })
+}
+
 // Get all listings owned by an Address
 pub fn get_listings_by_owner(deps: Deps, owner: &str) -> StdResult<MultiListingResponse> {
     let owner = deps.api.addr_validate(owner)?;
@@ -138,19 +644,370 @@ pub fn get_all_listings(deps: Deps) -> StdResult<MultiListingResponse> {
 }
 
 pub fn get_whitelisted_listings(deps: Deps, address: &str) -> StdResult<MultiListingResponse> {
-    let all_listings: Vec<Listing> = listingz()
-        .idx
-        .whitelisted_buyer
-        .prefix(address.to_string())
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect::<StdResult<Vec<_>>>() // StdResult<Vec<(PK, Listing)>>
+    let address = deps.api.addr_validate(address)?;
+
+    let mut all_listings: Vec<Listing> = Vec::new();
+
+    for listing_id in listingz().idx.whitelisted_buyers.listing_ids(deps.storage, address)? {
+        if let Some((_pk, listing)) = listingz().idx.id.item(deps.storage, listing_id)? {
+            all_listings.push(listing);
+        }
+    }
+
+    Ok(MultiListingResponse {
+        listings: all_listings,
+    })
+}
+
+// Whitelisted listings are few per buyer, so they're all loaded & filtered by stage
+pub fn get_my_whitelisted_listings(
+    deps: Deps,
+    env: &Env,
+    address: &str,
+    status: ListingStage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MultiListingResponse> {
+    let address = deps.api.addr_validate(address)?;
+    let limit = page_limit(deps, limit)?;
+    let refund_grace_seconds = CONFIG.load(deps.storage)?.refund_grace_seconds;
+
+    let mut listings: Vec<Listing> = Vec::new();
+    for listing_id in listingz().idx.whitelisted_buyers.listing_ids(deps.storage, address)? {
+        if listings.len() == limit {
+            break;
+        }
+        if start_after.as_ref().is_some_and(|start_after| &listing_id <= start_after) {
+            continue;
+        }
+        let Some((_pk, listing)) = listingz().idx.id.item(deps.storage, listing_id)? else {
+            continue;
+        };
+        // Bought listings aren't a deal anymore
+        let stage = match listing.status {
+            Status::BeingPrepared => ListingStage::Open,
+            Status::FinalizedReady if past_expiry(&listing, env, refund_grace_seconds) => {
+                ListingStage::Expired
+            }
+            Status::FinalizedReady => ListingStage::Finalized,
+            Status::Closed => continue,
+        };
+        if stage == status {
+            listings.push(listing);
+        }
+    }
+
+    Ok(MultiListingResponse {
+        listings,
+    })
+}
+
+// Get the ask amount in a denom (or cw20 address) of the last sale including this collection
+pub fn get_last_sale_price(
+    deps: Deps,
+    contract: &str,
+    denom: &str,
+) -> StdResult<LastSalePriceResponse> {
+    let contract = deps.api.addr_validate(contract)?;
+
+    Ok(LastSalePriceResponse {
+        price: LAST_SALE_PRICE.may_load(deps.storage, (&contract, denom))?,
+    })
+}
+
+// Get a seller's lifetime proceeds by denom or cw20 address
+pub fn get_seller_earnings(deps: Deps, seller: &str) -> StdResult<SellerEarningsResponse> {
+    let seller = deps.api.addr_validate(seller)?;
+
+    Ok(SellerEarningsResponse {
+        earnings: SELLER_EARNINGS
+            .prefix(&seller)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?,
+    })
+}
+
+// Get the open listing selling a specific NFT
+pub fn get_listing_for_nft(
+    deps: Deps,
+    contract: &str,
+    token_id: &str,
+) -> StdResult<ListingForNftResponse> {
+    let contract = deps.api.addr_validate(contract)?;
+
+    Ok(ListingForNftResponse {
+        listing_id: NFT_LISTING.may_load(deps.storage, (&contract, token_id))?,
+    })
+}
+
+// Get the listings holding NFTs of a collection, found through the NFT -> listing index
+pub fn get_listings_by_collection(
+    deps: Deps,
+    contract: &str,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MultiListingResponse> {
+    let contract = deps.api.addr_validate(contract)?;
+    let limit = page_limit(deps, limit)?;
+
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    // A listing can hold many NFTs of the collection, it's indexed once
+    let listing_ids = COLLECTION_LISTINGS
+        .prefix(&contract)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<String>>>()?;
+
+    let mut listings: Vec<Listing> = Vec::new();
+    for listing_id in listing_ids {
+        if let Some((_pk, listing)) = listingz().idx.id.item(deps.storage, listing_id)? {
+            listings.push(listing);
+        }
+    }
+
+    Ok(MultiListingResponse {
+        listings,
+    })
+}
+
+// Live offers lapsing within the next within_seconds, soonest first
+pub fn get_offers_expiring_soon(
+    deps: Deps,
+    env: &Env,
+    within_seconds: u64,
+    limit: Option<u32>,
+) -> StdResult<OffersResponse> {
+    let limit = page_limit(deps, limit)?;
+    let now = env.block.time.seconds();
+
+    let mut offers = Vec::new();
+    for entry in bucket_offers().idx.expiration.prefix_range_raw(
+        deps.storage,
+        Some(PrefixBound::inclusive(now)),
+        Some(PrefixBound::inclusive(now.saturating_add(within_seconds))),
+        Order::Ascending,
+    ) {
+        if offers.len() == limit {
+            break;
+        }
+        let (pk, offer) = entry?;
+        // Offers without an expiration are indexed at u64::MAX & never lapse
+        let Some(expiration) = offer.expiration else {
+            continue;
+        };
+        if !live_offer(deps.storage, env, &offer)? {
+            continue;
+        }
+        let (buyer, bucket_id) = <(&Addr, &str)>::from_vec(pk)?;
+        offers.push(ExpiringOffer {
+            buyer,
+            bucket_id,
+            listing_id: offer.listing_id,
+            expiration,
+        });
+    }
+
+    Ok(OffersResponse {
+        offers,
+    })
+}
+
+// Live offers on a listing, highest first by the offered amount of denom, offers without it last
+pub fn get_offers_sorted(
+    deps: Deps,
+    env: &Env,
+    listing_id: String,
+    denom: &str,
+) -> StdResult<BucketOffersResponse> {
+    let mut offers = Vec::new();
+    for entry in bucket_offers().idx.listing.prefix(listing_id).range_raw(
+        deps.storage,
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let (pk, offer) = entry?;
+        let (buyer, bucket_id) = <(&Addr, &str)>::from_vec(pk)?;
+        if let Some(offer) = load_bucket_offer(deps, env, buyer, bucket_id, offer)? {
+            offers.push(offer);
+        }
+    }
+
+    // None sorts below any amount, so reversed it puts offers without the denom last
+    offers.sort_by_key(|offer| Reverse(amount_of(&offer.funds, denom)));
+
+    Ok(BucketOffersResponse {
+        offers,
+    })
+}
+
+// Live offers made by a buyer, in bucket_id order
+pub fn get_offers_by_buyer(
+    deps: Deps,
+    env: &Env,
+    buyer: &str,
+    limit: Option<u32>,
+) -> StdResult<BucketOffersResponse> {
+    let limit = page_limit(deps, limit)?;
+    let buyer = deps.api.addr_validate(buyer)?;
+
+    let mut offers = Vec::new();
+    for entry in bucket_offers().prefix(&buyer).range(deps.storage, None, None, Order::Ascending) {
+        if offers.len() == limit {
+            break;
+        }
+        let (bucket_id, offer) = entry?;
+        if let Some(offer) = load_bucket_offer(deps, env, buyer.clone(), bucket_id, offer)? {
+            offers.push(offer);
+        }
+    }
+
+    Ok(BucketOffersResponse {
+        offers,
+    })
+}
+
+// A live offer with the funds of the bucket it locks
+fn load_bucket_offer(
+    deps: Deps,
+    env: &Env,
+    buyer: Addr,
+    bucket_id: String,
+    offer: Offer,
+) -> StdResult<Option<BucketOffer>> {
+    if !live_offer(deps.storage, env, &offer)? {
+        return Ok(None);
+    }
+    let Some(bucket) = BUCKETS.may_load(deps.storage, (buyer.clone(), &bucket_id))? else {
+        return Ok(None);
+    };
+    Ok(Some(BucketOffer {
+        buyer,
+        bucket_id,
+        listing_id: offer.listing_id,
+        funds: bucket.funds,
+        expiration: offer.expiration,
+    }))
+}
+
+// Get the newest `limit` daily floor snapshots of a collection, oldest to newest
+pub fn get_floor_history(
+    deps: Deps,
+    contract: &str,
+    denom: &str,
+    limit: Option<u32>,
+) -> StdResult<FloorHistoryResponse> {
+    let contract = deps.api.addr_validate(contract)?;
+
+    let mut snapshots =
+        FLOOR_HISTORY.may_load(deps.storage, (&contract, denom))?.unwrap_or_default();
+    let limit = limit.map_or(snapshots.len(), |limit| limit as usize);
+    snapshots.drain(..snapshots.len().saturating_sub(limit));
+
+    Ok(FloorHistoryResponse {
+        snapshots,
+    })
+}
+
+// Get the newest `limit` completed sales, newest first
+pub fn get_recent_sales(deps: Deps, limit: Option<u32>) -> StdResult<RecentSalesResponse> {
+    let limit = page_limit(deps, limit)?;
+
+    let sales = RECENT_SALES
+        .may_load(deps.storage)?
         .unwrap_or_default()
-        .iter()
-        .map(|entry| entry.1.clone())
+        .into_iter()
+        .rev()
+        .take(limit)
         .collect();
 
+    Ok(RecentSalesResponse {
+        sales,
+    })
+}
+
+// Get open listings whose ask includes a specific NFT
+pub fn get_listings_accepting_nft(
+    deps: Deps,
+    contract: &str,
+    token_id: &str,
+) -> StdResult<MultiListingResponse> {
+    let contract = deps.api.addr_validate(contract)?;
+
+    let mut listings: Vec<Listing> = Vec::new();
+
+    for listing_id in
+        listingz().idx.ask_nfts.listing_ids(deps.storage, (contract, token_id.to_string()))?
+    {
+        if let Some((_pk, listing)) = listingz().idx.id.item(deps.storage, listing_id)? {
+            listings.push(listing);
+        }
+    }
+
     Ok(MultiListingResponse {
-        listings: all_listings,
+        listings,
+    })
+}
+
+// Get listings in a status, paginated by the listing ID to start after
+pub fn get_listings_by_status(
+    deps: Deps,
+    status: Status,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<MultiListingResponse, ContractError> {
+    let limit = page_limit(deps, limit)?;
+
+    // The index is ordered by primary key, so resolve the cursor listing's key
+    let start_pk = match start_after {
+        Some(listing_id) => {
+            let listing = load_listing(deps, listing_id)?;
+            Some((listing.creator, listing.id))
+        }
+        None => None,
+    };
+    let start = start_pk.as_ref().map(|(creator, id)| Bound::exclusive((creator, id.clone())));
+
+    let listings: Vec<Listing> = listingz()
+        .idx
+        .status
+        .prefix(status as u8)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| entry.map(|(_pk, listing)| listing))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MultiListingResponse {
+        listings,
+    })
+}
+
+// Listings an address created, bid on with a bucket, was interested in or bought, by listing ID
+pub fn get_address_activity(
+    deps: Deps,
+    address: &str,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AddressActivityResponse> {
+    let address = deps.api.addr_validate(address)?;
+    let limit = page_limit(deps, limit)?;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let activity: Vec<ListingActivity> = ADDRESS_ACTIVITY
+        .prefix(&address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| {
+            entry.map(|(listing_id, roles)| ListingActivity {
+                listing_id,
+                roles,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AddressActivityResponse {
+        activity,
     })
 }
 
@@ -164,6 +1021,7 @@ pub fn get_listings_for_market(
     let two_weeks_ago_in_seconds = current_time - 1_209_600;
 
     let to_skip_usize = usize::from(page_num * 20 - 20);
+    let min_market_ask_value = CONFIG.load(deps.storage)?.min_market_ask_value;
 
     let listings_in_range: Vec<_> = listingz()
         .idx
@@ -177,6 +1035,9 @@ pub fn get_listings_for_market(
         .collect::<StdResult<Vec<_>>>()
         .unwrap_or_default()
         .iter()
+        .filter(|entry| {
+            !entry.1.private && ask_meets_market_minimum(&entry.1.ask, &min_market_ask_value)
+        })
         .skip(to_skip_usize)
         .take(20)
         .map(|entry| entry.1.clone())
@@ -188,6 +1049,41 @@ pub fn get_listings_for_market(
     })
 }
 
+// Get buyable public listings with the most interest first, ties in listing order
+pub fn get_trending_listings(
+    deps: Deps,
+    env: &Env,
+    limit: Option<u32>,
+) -> StdResult<TrendingListingsResponse> {
+    let limit = page_limit(deps, limit)?;
+    let refund_grace_seconds = CONFIG.load(deps.storage)?.refund_grace_seconds;
+
+    let mut listings: Vec<TrendingListing> = Vec::new();
+    for entry in listingz()
+        .idx
+        .status
+        .prefix(Status::FinalizedReady as u8)
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(MAX_TRENDING_SCAN)
+    {
+        let (_pk, listing) = entry?;
+        if listing.private || listing.frozen || past_expiry(&listing, env, refund_grace_seconds) {
+            continue;
+        }
+        let interest = INTEREST_COUNT.may_load(deps.storage, &listing.id)?.unwrap_or_default();
+        listings.push(TrendingListing {
+            listing,
+            interest,
+        });
+    }
+    listings.sort_by_key(|entry| std::cmp::Reverse(entry.interest));
+    listings.truncate(limit);
+
+    Ok(TrendingListingsResponse {
+        listings,
+    })
+}
+
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Responses
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -202,16 +1098,204 @@ pub struct ConfigResponse {
     pub config: Config,
 }
 
+#[cw_serde]
+pub struct LimitsResponse {
+    pub min_finalize_seconds: u64,
+    pub max_finalize_seconds: u64,
+    pub refund_grace_seconds: u64,
+    pub max_whitelisted_buyers: u64,
+    pub max_buckets_per_withdraw: u32,
+    pub max_listings_per_remove: u32,
+    pub max_ask_history: u64,
+    pub max_page_size: u32,
+    pub max_transfers_per_buy: u32,
+    pub max_fee_bps: u64,
+    pub max_ask_options: u64,
+    pub max_nfts_per_collection_per_listing: u32,
+}
+
+#[cw_serde]
+pub struct QueuedActionInfo {
+    pub action_id: u64,
+    pub action: AdminAction,
+    pub eta: u64,
+}
+
+#[cw_serde]
+pub struct QueuedActionsResponse {
+    pub actions: Vec<QueuedActionInfo>,
+}
+
+#[cw_serde]
+pub struct UniqueUsersResponse {
+    pub unique_users: u64,
+}
+
+#[cw_serde]
+pub struct NextListingIdResponse {
+    pub next_listing_id: u64,
+}
+
 #[cw_serde]
 pub struct GetBucketsResponse {
     pub buckets: Vec<(String, Bucket)>,
 }
 
+#[cw_serde]
+pub struct BucketShortfallResponse {
+    pub missing: GenericBalance,
+}
+
+#[cw_serde]
+pub struct BucketPowerResponse {
+    pub affordable_listings: u64,
+    // Sum of the asks of those listings, by denom or cw20 address
+    pub affordable_value: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct CanBuyResponse {
+    pub can_buy: bool,
+    pub reason: Option<String>,
+}
+
+#[cw_serde]
+pub struct SimulateBuyResponse {
+    pub satisfies_ask: bool,
+    pub payout: Option<BuyPayout>,
+}
+
+#[cw_serde]
+pub struct BuyPayout {
+    // Ask or ask option the bucket pays
+    pub paid_ask: GenericBalance,
+    // Bucket handed to the payee, minus the maker & taker fees
+    pub seller_receives: GenericBalance,
+    // Maker & taker fees in JUNO, before any referral cut
+    pub marketplace_fee: Uint128,
+    // Bundle the buyer withdraws, minus the community pool fee
+    pub buyer_receives: GenericBalance,
+    pub community_pool_fee: Uint128,
+}
+
 #[cw_serde]
 pub struct MultiListingResponse {
     pub listings: Vec<Listing>,
 }
 
+#[cw_serde]
+pub struct ListingActivity {
+    pub listing_id: String,
+    pub roles: Vec<ActivityRole>,
+}
+
+#[cw_serde]
+pub struct AddressActivityResponse {
+    pub activity: Vec<ListingActivity>,
+}
+
+#[cw_serde]
+pub struct LastSalePriceResponse {
+    pub price: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct SellerEarningsResponse {
+    // (denom or cw20 address, amount)
+    pub earnings: Vec<(String, Uint128)>,
+}
+
+#[cw_serde]
+pub struct ReconciliationResponse {
+    // Bank balance of the contract
+    pub balance: Uint128,
+    // Held in listings, buckets, listing bonds & sealed bid escrows
+    pub tracked: Uint128,
+    // Sent to the contract without being tracked, e.g. a direct transfer
+    pub surplus: Uint128,
+    // Tracked but missing from the balance, should always be 0
+    pub deficit: Uint128,
+}
+
+#[cw_serde]
+pub struct TrendingListing {
+    pub listing: Listing,
+    pub interest: u64,
+}
+
+#[cw_serde]
+pub struct TrendingListingsResponse {
+    pub listings: Vec<TrendingListing>,
+}
+
+#[cw_serde]
+pub struct SealedAuctionResponse {
+    pub reserve: Coin,
+    pub commit_end: Timestamp,
+    pub reveal_end: Timestamp,
+    pub bids: u64,
+    pub revealed: u64,
+}
+
+#[cw_serde]
+pub struct AuctionResponse {
+    pub auction: Auction,
+}
+
+#[cw_serde]
+pub struct BundleResponse {
+    pub bundle: Bundle,
+}
+
+#[cw_serde]
+pub struct TvlResponse {
+    // Assets in the requested denom & those with a rate to it
+    pub total: Uint128,
+    // Assets without a rate, by denom or cw20 address
+    pub unconverted: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct RecentSalesResponse {
+    pub sales: Vec<Sale>,
+}
+
+#[cw_serde]
+pub struct FloorHistoryResponse {
+    pub snapshots: Vec<FloorSnapshot>,
+}
+
+#[cw_serde]
+pub struct ListingForNftResponse {
+    pub listing_id: Option<String>,
+}
+
+#[cw_serde]
+pub struct ActiveCollectionsResponse {
+    pub collections: Vec<String>,
+}
+
+#[cw_serde]
+pub struct ActiveDenomsResponse {
+    pub denoms: Vec<String>,
+}
+
+#[cw_serde]
+pub struct ArchivedListingResponse {
+    pub listing: Listing,
+}
+
+#[cw_serde]
+pub struct ListingHistoryResponse {
+    pub changes: Vec<AskChange>,
+}
+
+#[cw_serde]
+pub struct BundleContentsResponse {
+    pub listing_id: String,
+    pub for_sale: GenericBalance,
+}
+
 #[cw_serde]
 pub struct ListingInfoResponse {
     pub creator: String,
@@ -219,5 +1303,34 @@ pub struct ListingInfoResponse {
     pub for_sale: Vec<(String, u128)>,
     pub ask: Vec<(String, u128)>,
     pub expiration: String,
-    pub whitelisted_buyer: String,
+    pub whitelisted_buyers: Vec<String>,
+    pub whitelist_until: Option<Timestamp>,
+    pub interest: u64,
+}
+
+#[cw_serde]
+pub struct OffersResponse {
+    pub offers: Vec<ExpiringOffer>,
+}
+
+#[cw_serde]
+pub struct BucketOffersResponse {
+    pub offers: Vec<BucketOffer>,
+}
+
+#[cw_serde]
+pub struct BucketOffer {
+    pub buyer: Addr,
+    pub bucket_id: String,
+    pub listing_id: String,
+    pub funds: GenericBalance,
+    pub expiration: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct ExpiringOffer {
+    pub buyer: Addr,
+    pub bucket_id: String,
+    pub listing_id: String,
+    pub expiration: Timestamp,
 }
@@ -1,6 +1,15 @@
-use cosmwasm_std::{Addr, Coin, Timestamp};
+use crate::error::ContractError;
+use crate::msg::{AdminAction, CreateListingMsg};
+use crate::utils::{ask_within_max_price, checked_add, sub_balance};
+use cosmwasm_std::{
+    Addr, Binary, Coin, Decimal, HexBinary, Order, StdResult, Storage, Timestamp, Uint128,
+};
 use cw20::{Balance, Cw20CoinVerified};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, KeyDeserialize, Map, MultiIndex, Prefixer,
+    PrimaryKey, UniqueIndex,
+};
+use std::collections::BTreeSet;
 
 use cosmwasm_schema::cw_serde;
 
@@ -14,6 +23,77 @@ pub const CONFIG: Item<Config> = Item::new("junovaults_config");
 pub struct Config {
     // Admin of contract
     pub admin: Addr,
+    // Seconds after expiration before a listing can be refunded, still purchasable meanwhile
+    pub refund_grace_seconds: u64,
+    // New fungible only listings are filled against crossing finalized listings
+    pub auto_match: bool,
+    // Rounding of the fee when it doesn't divide evenly
+    pub rounding: Rounding,
+    // Cuts of the JUNO in the ask, the maker's is taken from the seller's proceeds
+    // & the taker's is paid by the buyer on top of the ask
+    pub maker_fee_bps: u64,
+    pub taker_fee_bps: u64,
+    // Receives the maker & taker fees
+    pub fee_collector: Addr,
+    // Max transfers a buy can lead to, counting the bundle, the bucket & the fee
+    pub max_transfers_per_buy: u32,
+    // Cw20 asks must answer a TokenInfo query when a listing is created or its ask changes
+    pub validate_cw20_asks: bool,
+    // Permanently blocks new listings, buckets & buys, withdrawals & refunds stay open
+    pub wind_down: bool,
+    // Delay on queued admin actions, while non zero config changes must go through the queue
+    pub admin_timelock_seconds: u64,
+    // Native bond a listing must post before it's finalized, returned unless an admin force removes it
+    pub listing_bond: Option<Coin>,
+    // Listings asking less than this of a denom are left out of the market query
+    pub min_market_ask_value: Vec<Coin>,
+    // Seconds a listing is finalized for when the seller doesn't set them, 0 = no default
+    pub default_listing_ttl: u64,
+    // Share of the maker & taker fees a buy's referrer gets instead of the fee collector
+    pub referral_bps: u64,
+    // Cap on the limit of paginated queries
+    pub max_page_size: u32,
+    // Seconds after creation before an owner can remove a listing, 0 = no minimum
+    pub min_active_seconds: u64,
+    // DEX router that swaps the proceeds of listings with auto_swap_to, None = no swaps
+    pub swap_router: Option<Addr>,
+    // Max NFTs from one collection a listing can hold, bounds the transfers of a buy
+    pub max_nfts_per_collection_per_listing: u32,
+    // Only addresses in CREATOR_ALLOWLIST can create listings
+    pub restrict_creators: bool,
+    // No maker or taker fees are charged before this time, None = no holiday
+    pub fee_holiday_until: Option<Timestamp>,
+    // Share of a sealed bid's escrow paid to the seller when it's never revealed
+    pub reveal_deposit_slash_bps: u64,
+    // Seconds an address must wait after a buy before buying again, 0 = no cooldown
+    pub buy_cooldown_seconds: u64,
+    // Offers without NFTs must hold at least this of every denom they offer, empty = no minimums
+    pub min_offer_value: Vec<Coin>,
+    // Live offers a listing holds before a new one must beat the lowest, 0 = no cap
+    pub max_offers: u32,
+    // Unit listings with less than this of every denom left after a buy close & refund it, 0 = off
+    pub dust_threshold: Uint128,
+}
+
+// Admin actions waiting out the timelock, keyed by action ID
+pub const QUEUED_ACTIONS: Map<u64, QueuedAction> = Map::new("queued_actions");
+pub const NEXT_ACTION_ID: Item<u64> = Item::new("next_action_id");
+
+#[cw_serde]
+pub struct QueuedAction {
+    pub action: AdminAction,
+    // Earliest time the action can be executed
+    pub eta: Timestamp,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum Rounding {
+    // Round in favor of users
+    #[default]
+    Floor,
+    // Round in favor of the protocol
+    Ceil,
 }
 
 //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -23,14 +103,26 @@ pub struct Config {
 pub struct ListingIndexes<'a> {
     pub id: UniqueIndex<'a, String, Listing, (&'a Addr, String)>,
     pub finalized_date: MultiIndex<'a, u64, Listing, (&'a Addr, String)>,
-    // Key = (whitelisted buyer, listing_id)
-    pub whitelisted_buyer: UniqueIndex<'a, (String, String), Listing, (&'a Addr, String)>,
+    // Key = Status discriminant
+    pub status: MultiIndex<'a, u8, Listing, (&'a Addr, String)>,
+    // Key = (whitelisted buyer, listing_id) for every buyer in the whitelist
+    pub whitelisted_buyers: ListIndex<'a, Addr>,
+    // Key = ((nft contract, token_id), listing_id) for every NFT in an open listing's ask
+    pub ask_nfts: ListIndex<'a, (Addr, String)>,
+    // Key = native denom or cw20 address in an open listing's for_sale, ask or ask options
+    pub denoms: CountIndex<'a>,
 }
 
 impl IndexList<Listing> for ListingIndexes<'_> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
-        let v: Vec<&dyn Index<Listing>> =
-            vec![&self.id, &self.finalized_date, &self.whitelisted_buyer];
+        let v: Vec<&dyn Index<Listing>> = vec![
+            &self.id,
+            &self.finalized_date,
+            &self.status,
+            &self.whitelisted_buyers,
+            &self.ask_nfts,
+            &self.denoms,
+        ];
         Box::new(v.into_iter())
     }
 }
@@ -44,25 +136,441 @@ pub fn listingz<'a>() -> IndexedMap<'a, (&'a Addr, String), Listing, ListingInde
             "listings_im",
             "listing__finalized__date",
         ),
-        whitelisted_buyer: UniqueIndex::new(
+        status: MultiIndex::new(
+            |_pk, a_listing| a_listing.status.clone() as u8,
+            "listings_im",
+            "listing__status",
+        ),
+        whitelisted_buyers: ListIndex::new(
+            |listing| listing.whitelisted_buyers.clone(),
+            "listing__whitelisted__buyers",
+        ),
+        ask_nfts: ListIndex::new(
             |listing| {
-                (
+                if listing.status == Status::Closed {
+                    Vec::new()
+                } else {
                     listing
-                        .whitelisted_buyer
-                        .clone()
-                        .map_or_else(|| "1".to_string(), |addr| addr.to_string()),
-                    listing.id.clone(),
-                )
+                        .ask
+                        .nfts
+                        .iter()
+                        .map(|nft| (nft.contract_address.clone(), nft.token_id.clone()))
+                        .collect()
+                }
             },
-            "listing__whitelisted__one",
+            "listing__ask__nfts",
+        ),
+        denoms: CountIndex::new(
+            |listing| {
+                if listing.status == Status::Closed {
+                    return BTreeSet::new();
+                }
+                std::iter::once(&listing.for_sale)
+                    .chain(std::iter::once(&listing.ask))
+                    .chain(&listing.ask_options)
+                    .flat_map(|balance| {
+                        let native = balance.native.iter().map(|coin| coin.denom.clone());
+                        native.chain(balance.cw20.iter().map(|coin| coin.address.to_string()))
+                    })
+                    .collect()
+            },
+            "listing__denoms",
         ),
     };
 
     IndexedMap::new("listings_im", indexes)
 }
 
+// Index from each value in a listing's list to the listing, a listing can be under many values
+pub struct ListIndex<'a, K> {
+    idx_fn: fn(&Listing) -> Vec<K>,
+    idx_map: Map<'a, (K, String), bool>,
+}
+
+impl<'a, K> ListIndex<'a, K>
+where
+    K: PrimaryKey<'a> + Prefixer<'a> + KeyDeserialize,
+{
+    pub const fn new(idx_fn: fn(&Listing) -> Vec<K>, namespace: &'a str) -> Self {
+        ListIndex {
+            idx_fn,
+            idx_map: Map::new(namespace),
+        }
+    }
+
+    // IDs of the listings indexed under this value
+    pub fn listing_ids(&self, store: &dyn Storage, key: K) -> StdResult<Vec<String>> {
+        self.idx_map.prefix(key).keys(store, None, None, Order::Ascending).collect()
+    }
+}
+
+impl<'a, K> Index<Listing> for ListIndex<'a, K>
+where
+    K: PrimaryKey<'a> + Prefixer<'a> + KeyDeserialize,
+{
+    fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &Listing) -> StdResult<()> {
+        for key in (self.idx_fn)(data) {
+            self.idx_map.save(store, (key, data.id.clone()), &true)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &Listing) -> StdResult<()> {
+        for key in (self.idx_fn)(old_data) {
+            self.idx_map.remove(store, (key, old_data.id.clone()));
+        }
+        Ok(())
+    }
+}
+
+// Number of listings under each value, a value is dropped once no listing has it
+pub struct CountIndex<'a> {
+    idx_fn: fn(&Listing) -> BTreeSet<String>,
+    counts: Map<'a, String, u64>,
+}
+
+impl<'a> CountIndex<'a> {
+    pub const fn new(idx_fn: fn(&Listing) -> BTreeSet<String>, namespace: &'a str) -> Self {
+        CountIndex {
+            idx_fn,
+            counts: Map::new(namespace),
+        }
+    }
+
+    // Values with at least one listing, in order
+    pub fn keys(
+        &self,
+        store: &dyn Storage,
+        start_after: Option<String>,
+        limit: usize,
+    ) -> StdResult<Vec<String>> {
+        let start = start_after.map(Bound::exclusive);
+        self.counts.keys(store, start, None, Order::Ascending).take(limit).collect()
+    }
+}
+
+impl Index<Listing> for CountIndex<'_> {
+    fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &Listing) -> StdResult<()> {
+        for key in (self.idx_fn)(data) {
+            self.counts.update(store, key, |count| -> StdResult<_> {
+                Ok(count.unwrap_or_default() + 1)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &Listing) -> StdResult<()> {
+        for key in (self.idx_fn)(old_data) {
+            match self.counts.may_load(store, key.clone())?.unwrap_or_default() {
+                0 | 1 => self.counts.remove(store, key),
+                count => self.counts.save(store, key, &(count - 1))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Next ID given to a created listing, starts at 1
+pub const NEXT_LISTING_ID: Item<u64> = Item::new("next_listing_id");
+
 pub const BUCKETS: Map<(Addr, &str), Bucket> = Map::new("buckets");
 
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Allowance Listings
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Listing waiting on a cw20 TransferFrom, saved in reply once the tokens are pulled
+pub const PENDING_ALLOWANCE_LISTING: Item<PendingAllowanceListing> =
+    Item::new("pending_allowance_listing");
+
+#[cw_serde]
+pub struct PendingAllowanceListing {
+    pub creator: Addr,
+    pub token: Addr,
+    pub amount: Uint128,
+    pub create_msg: CreateListingMsg,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Commitments & Offers
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = listing_id, value = (buyer, bucket_id) committed to buying it
+pub const LISTING_COMMITMENT: Map<&str, (Addr, String)> = Map::new("listing_commitment");
+
+// Key = (buyer, bucket_id), value = listing_id the bucket is committed to
+pub const BUCKET_COMMITMENT: Map<(&Addr, &str), String> = Map::new("bucket_commitment");
+
+pub struct OfferIndexes<'a> {
+    // Key = expiration in seconds, u64::MAX for offers that don't expire
+    pub expiration: MultiIndex<'a, u64, Offer, (&'a Addr, &'a str)>,
+    // Key = listing_id the offer is for
+    pub listing: MultiIndex<'a, String, Offer, (&'a Addr, &'a str)>,
+}
+
+impl IndexList<Offer> for OfferIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Offer>> + '_> {
+        let v: Vec<&dyn Index<Offer>> = vec![&self.expiration, &self.listing];
+        Box::new(v.into_iter())
+    }
+}
+
+// Key = (buyer, bucket_id), value = the standing offer the bucket is locked to
+#[must_use]
+pub fn bucket_offers<'a>() -> IndexedMap<'a, (&'a Addr, &'a str), Offer, OfferIndexes<'a>> {
+    let indexes = OfferIndexes {
+        expiration: MultiIndex::new(
+            |_pk, offer| offer.expiration.map_or(u64::MAX, |x| x.seconds()),
+            "bucket_offer",
+            "bucket_offer__expiration",
+        ),
+        listing: MultiIndex::new(
+            |_pk, offer| offer.listing_id.clone(),
+            "bucket_offer",
+            "bucket_offer__listing",
+        ),
+    };
+
+    IndexedMap::new("bucket_offer", indexes)
+}
+
+#[cw_serde]
+pub struct Offer {
+    pub listing_id: String,
+    // No expiration holds the offer until the listing is removed or bought
+    pub expiration: Option<Timestamp>,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing Interest
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = (listing_id, address), used to only count an address once per listing
+pub const LISTING_INTEREST: Map<(&str, &Addr), bool> = Map::new("listing_interest");
+
+// Key = listing_id, value = number of unique addresses that expressed interest
+pub const INTEREST_COUNT: Map<&str, u64> = Map::new("interest_count");
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Sale History
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = (nft contract, denom or cw20 address), value = ask amount of the last sale including it
+pub const LAST_SALE_PRICE: Map<(&Addr, &str), Uint128> = Map::new("last_sale_price");
+
+// Key = (seller, denom or cw20 address), value = lifetime proceeds after the maker & taker fees
+pub const SELLER_EARNINGS: Map<(&Addr, &str), Uint128> = Map::new("seller_earnings");
+
+// Key = nft contract, value = NFTs of the collection in open listings, removed at 0
+pub const ACTIVE_COLLECTIONS: Map<&Addr, u64> = Map::new("active_collections");
+
+// Key = listing ID, value = (poster, bond), refunded to the poster even after a transfer
+pub const LISTING_BOND: Map<&str, (Addr, Coin)> = Map::new("listing_bond");
+
+// Key = (nft contract, token id) of an NFT in an open listing, value = listing ID
+pub const NFT_LISTING: Map<(&Addr, &str), String> = Map::new("nft_listing");
+
+// Key = (nft contract, listing ID) of an open listing, value = NFTs of the collection it holds
+pub const COLLECTION_LISTINGS: Map<(&Addr, &str), u64> = Map::new("collection_listings");
+
+// Key = buyer, value = number of listings they've bought
+pub const PURCHASE_COUNT: Map<&Addr, u64> = Map::new("purchase_count");
+
+// Addresses that ever created a listing or bucket, counted instead of listed to keep queries cheap
+pub const SEEN_USERS: Map<&Addr, bool> = Map::new("seen_users");
+pub const UNIQUE_USERS: Item<u64> = Item::new("unique_users");
+
+// Key = buyer, value = time of their last buy, checked against the config's buy_cooldown_seconds
+pub const LAST_BUY: Map<&Addr, Timestamp> = Map::new("last_buy");
+
+// Key = (address, listing_id), value = every role the address had in the listing
+pub const ADDRESS_ACTIVITY: Map<(&Addr, &str), Vec<ActivityRole>> = Map::new("address_activity");
+
+#[cw_serde]
+pub enum ActivityRole {
+    Creator,
+    // Pointed a bucket at the listing, or bid in its sealed-bid auction
+    Bidder,
+    Interested,
+    Buyer,
+}
+
+// Addresses that can create listings while the config's restrict_creators is set, set by the admin
+pub const CREATOR_ALLOWLIST: Map<&Addr, bool> = Map::new("creator_allowlist");
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// NFT Receivers
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Contracts known to implement the cw721 ReceiveNft hook, set by the admin
+pub const NFT_RECEIVERS: Map<&Addr, bool> = Map::new("nft_receivers");
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Conversion Rates
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = (denom or cw20 address, common denom), value = common denom per unit, set by the admin
+// Only hints for queries, never used to price a trade
+pub const CONVERSION_RATES: Map<(&str, &str), Decimal> = Map::new("conversion_rates");
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// NFT Claims
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = (nft contract, token_id), value = owner whose payout of the NFT failed
+pub const NFT_CLAIMS: Map<(&Addr, &str), Addr> = Map::new("nft_claims");
+
+// NFT payouts sent in the current tx, in order, each reply takes the first one
+pub const PENDING_NFT_PAYOUTS: Item<Vec<(Addr, Nft)>> = Item::new("pending_nft_payouts");
+
+// Proceeds sent to the swap router in the current tx, put back in the payee's bucket if it fails
+pub const PENDING_SWAP: Item<PendingSwap> = Item::new("pending_swap");
+
+#[cw_serde]
+pub struct PendingSwap {
+    pub payee: Addr,
+    pub bucket_id: String,
+    pub offer: Vec<Coin>,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Sealed-Bid Auctions
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = listing_id, listings sold to the highest revealed bid instead of at their ask
+pub const SEALED_AUCTION: Map<&str, SealedAuction> = Map::new("sealed_auction");
+
+// Key = (listing_id, bidder), the escrow is held by the contract until settlement
+pub const SEALED_BIDS: Map<(&str, &Addr), SealedBid> = Map::new("sealed_bids");
+
+#[cw_serde]
+pub struct SealedAuction {
+    // The listing's ask, bids are in its denom & at least its amount
+    pub reserve: Coin,
+    // Bids are committed before commit_end, then revealed before reveal_end
+    pub commit_end: Timestamp,
+    pub reveal_end: Timestamp,
+}
+
+#[cw_serde]
+pub struct SealedBid {
+    // sha256 of "{bidder}:{amount}:{nonce}"
+    pub commitment: HexBinary,
+    // Most the bidder can pay including the taker fee, what's left is refunded at settlement
+    pub escrow: Uint128,
+    pub revealed: Option<Uint128>,
+    // Earlier bids win ties
+    pub committed_height: u64,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Open Auctions
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = listing_id, finalized listings converted to sell to the highest open bid
+pub const AUCTIONS: Map<&str, Auction> = Map::new("auctions");
+
+#[cw_serde]
+pub struct Auction {
+    // Bids are in the reserve's denom & the first must be at least its amount
+    pub reserve: Coin,
+    // Each later bid must beat the highest by at least this much
+    pub min_increment: Uint128,
+    pub end_time: Timestamp,
+    // Only the highest bid's escrow is held, an outbid one is refunded
+    pub high_bid: Option<AuctionBid>,
+}
+
+#[cw_serde]
+pub struct AuctionBid {
+    pub bidder: Addr,
+    pub amount: Uint128,
+    // Most the bidder can pay including the taker fee, what's left is refunded at settlement
+    pub escrow: Uint128,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing Swaps
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = (proposer's listing_id, counter listing_id), value = the proposer
+// Voided if the proposer's listing changes owner before the swap is accepted
+pub const SWAP_PROPOSALS: Map<(&str, &str), Addr> = Map::new("swap_proposals");
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing Bundles
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Key = bundle ID, removed once bought
+pub const BUNDLES: Map<u64, Bundle> = Map::new("bundles");
+// Next ID given to a created bundle, starts at 1
+pub const NEXT_BUNDLE_ID: Item<u64> = Item::new("next_bundle_id");
+
+// Listings of one owner bought together for a single price, usually under the sum of their asks
+#[cw_serde]
+pub struct Bundle {
+    pub creator: Addr,
+    pub listing_ids: Vec<String>,
+    pub price: GenericBalance,
+}
+
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Listing History
+//~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Max ask changes kept per listing, the oldest are dropped first
+pub const MAX_ASK_HISTORY: usize = 20;
+
+// Key = listing_id, value = ask changes oldest to newest
+pub const ASK_HISTORY: Map<&str, Vec<AskChange>> = Map::new("ask_history");
+
+// Max days of floor snapshots kept per collection & denom
+pub const MAX_FLOOR_HISTORY: usize = 90;
+
+// Key = (nft contract, denom or cw20 address), value = daily floor snapshots oldest to newest
+// The day's floor is lowered by every sale & finalized listing of the collection
+pub const FLOOR_HISTORY: Map<(&Addr, &str), Vec<FloorSnapshot>> = Map::new("floor_history");
+
+#[cw_serde]
+pub struct FloorSnapshot {
+    // Days since the unix epoch
+    pub day: u64,
+    pub price: Uint128,
+}
+
+// Max sales kept in the recent sales feed, the oldest are dropped first
+pub const MAX_RECENT_SALES: usize = 20;
+
+// Completed sales oldest to newest
+pub const RECENT_SALES: Item<Vec<Sale>> = Item::new("recent_sales");
+
+#[cw_serde]
+pub struct Sale {
+    pub listing_id: String,
+    // Ask or ask option the buyer paid, without the taker fee
+    pub price: GenericBalance,
+    pub buyer: Addr,
+    pub seller: Addr,
+    pub timestamp: Timestamp,
+}
+
+// Key = listing_id, value = block time the listing was created
+pub const LISTING_CREATED: Map<&str, Timestamp> = Map::new("listing_created");
+
+// Key = listing_id, value = the listing as it was when it left listingz (sold, removed or refunded)
+// Pruned by the admin to bound its growth
+pub const ARCHIVED_LISTINGS: Map<&str, Listing> = Map::new("archived_listings");
+
+// Key = listing_id, value = the seller of a bought listing, whose creator is the buyer from then on
+pub const LISTING_SELLER: Map<&str, Addr> = Map::new("listing_seller");
+
+#[cw_serde]
+pub struct AskChange {
+    pub timestamp: Timestamp,
+    pub old_ask: GenericBalance,
+    pub new_ask: GenericBalance,
+}
+
 #[cw_serde]
 pub struct GenericBalance {
     pub native: Vec<Coin>,
@@ -76,13 +584,83 @@ pub struct Listing {
     pub id: String,
     pub finalized_time: Option<Timestamp>,
     pub expiration_time: Option<Timestamp>,
+    // Block height the listing expires after, set instead of expiration_time
+    // The refund grace period only extends a time expiry
+    pub expiration_height: Option<u64>,
     pub status: Status,
     pub claimant: Option<Addr>,
-    pub whitelisted_buyer: Option<Addr>,
+    // Empty = anyone can buy
+    pub whitelisted_buyers: Vec<Addr>,
+    // The whitelist only applies before this time, None = it always applies
+    pub whitelist_until: Option<Timestamp>,
+    // Receives the sale proceeds bucket instead of the creator
+    pub payout_address: Option<Addr>,
+    // Native proceeds are swapped to this denom & sent to the payee, when the config has a router
+    pub auto_swap_to: Option<String>,
+    // Each buy takes an equal share of for_sale & the listing closes at 0, None = sold whole
+    pub units_available: Option<u32>,
+    // Executed on a contract of the seller's after each sale, its failure doesn't revert the sale
+    pub on_sale_callback: Option<SaleCallback>,
+    // Least the seller nets from a sale in each denom, after the maker fee, empty = no floor
+    pub min_net_proceeds: Vec<Coin>,
+    // Overrides the config's max_offers for this listing
+    pub max_offers: Option<u32>,
+    // Completed purchases a buyer needs before buying this listing, 0 = anyone
+    pub min_buyer_purchases: u64,
+    pub vesting: Option<Vesting>,
+    pub on_expire: OnExpire,
+    // Hidden from the market query, still buyable & returned by direct queries
+    pub private: bool,
+    // Set by the admin during disputes, blocks everything except withdrawing a purchase
+    pub frozen: bool,
 
     pub for_sale: GenericBalance,
 
     pub ask: GenericBalance,
+    // Alternatives to the ask, a buyer pays exactly one of them
+    pub ask_options: Vec<GenericBalance>,
+}
+
+#[cw_serde]
+pub struct SaleCallback {
+    pub contract_addr: Addr,
+    // Sent as is, without funds
+    pub msg: Binary,
+}
+
+// What ProcessExpiry does with an expired listing
+#[cw_serde]
+#[derive(Default)]
+pub enum OnExpire {
+    // Send the for_sale back to the owner
+    #[default]
+    Refund,
+    // Finalize again for the same duration
+    Relist,
+}
+
+#[cw_serde]
+pub struct Vesting {
+    pub duration_seconds: u64,
+    // Set when the listing is bought
+    pub start: Option<Timestamp>,
+    // Already released to the buyer
+    pub withdrawn: GenericBalance,
+}
+
+impl Vesting {
+    #[must_use]
+    pub fn new(duration_seconds: u64) -> Self {
+        Vesting {
+            duration_seconds,
+            start: None,
+            withdrawn: GenericBalance {
+                native: Vec::new(),
+                cw20: Vec::new(),
+                nfts: Vec::new(),
+            },
+        }
+    }
 }
 
 #[cw_serde]
@@ -95,6 +673,8 @@ pub struct Nft {
 pub struct Bucket {
     pub funds: GenericBalance,
     pub owner: Addr,
+    // Advisory only, the listing this bucket is meant to purchase
+    pub target_listing: Option<String>,
 }
 
 #[cw_serde]
@@ -105,12 +685,18 @@ pub enum Status {
 }
 
 pub trait GenericBalanceUtil {
-    fn add_tokens(&mut self, add: Balance);
+    fn add_tokens(&mut self, add: Balance) -> Result<(), ContractError>;
     fn add_nft(&mut self, nft: Nft);
+    // Adds every asset in `other`, NFTs already held aren't added twice
+    fn merge(&mut self, other: &GenericBalance) -> Result<(), ContractError>;
+    // What's left once `other` is taken out, see `sub_balance`
+    fn subtract(&self, other: &GenericBalance) -> Result<GenericBalance, ContractError>;
+    // Whether at least every asset in `other` is held
+    fn contains(&self, other: &GenericBalance) -> bool;
 }
 
 impl GenericBalanceUtil for GenericBalance {
-    fn add_tokens(&mut self, add: Balance) {
+    fn add_tokens(&mut self, add: Balance) -> Result<(), ContractError> {
         match add {
             Balance::Native(balance) => {
                 for token in balance.0 {
@@ -122,7 +708,10 @@ impl GenericBalanceUtil for GenericBalance {
                         }
                     });
                     match index {
-                        Some(idx) => self.native[idx].amount += token.amount,
+                        Some(idx) => {
+                            self.native[idx].amount =
+                                checked_add(self.native[idx].amount, token.amount)?;
+                        }
                         None => self.native.push(token),
                     }
                 }
@@ -136,16 +725,40 @@ impl GenericBalanceUtil for GenericBalance {
                     }
                 });
                 match index {
-                    Some(idx) => self.cw20[idx].amount += token.amount,
+                    Some(idx) => {
+                        self.cw20[idx].amount = checked_add(self.cw20[idx].amount, token.amount)?;
+                    }
                     None => self.cw20.push(token),
                 }
             }
         };
+        Ok(())
     }
 
     fn add_nft(&mut self, nft: Nft) {
         self.nfts.push(nft);
     }
+
+    fn merge(&mut self, other: &GenericBalance) -> Result<(), ContractError> {
+        self.add_tokens(Balance::from(other.native.clone()))?;
+        for token in &other.cw20 {
+            self.add_tokens(Balance::Cw20(token.clone()))?;
+        }
+        for nft in &other.nfts {
+            if !self.nfts.contains(nft) {
+                self.add_nft(nft.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn subtract(&self, other: &GenericBalance) -> Result<GenericBalance, ContractError> {
+        sub_balance(self, other)
+    }
+
+    fn contains(&self, other: &GenericBalance) -> bool {
+        ask_within_max_price(other, self)
+    }
 }
 
 #[must_use]
@@ -1,9 +1,12 @@
 use crate::error::ContractError;
-use crate::state::GenericBalance;
+use crate::state::{Config, GenericBalance, Rounding};
 
 use cosmwasm_std::coins;
-use cosmwasm_std::{to_binary, Addr, BankMsg, CosmosMsg, Empty, StdResult, WasmMsg};
-use cw20::Cw20ExecuteMsg;
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Empty, StdResult, Timestamp, Uint128,
+    Uint256, WasmMsg,
+};
+use cw20::{Cw20CoinVerified, Cw20ExecuteMsg};
 use cw721::Cw721ExecuteMsg;
 
 // Actual community pool on mainnet
@@ -11,9 +14,14 @@ const COMMUNITY_POOL: &str = "juno1jv65s3grqf6v6jl3dp4t6c9t9rk99cd83d88wr";
 // use fake contract address for testnet
 //const COMMUNITY_POOL: &str = ""
 
-const NATIVE: &str = "ujunox";
+pub const NATIVE: &str = "ujunox";
 
-pub fn send_tokens_cosmos(to: &Addr, balance: &GenericBalance) -> StdResult<Vec<CosmosMsg>> {
+// NFTs are sent with SendNft when `nft_hook` is set, so the receiving contract is notified
+pub fn send_tokens_cosmos(
+    to: &Addr,
+    balance: &GenericBalance,
+    nft_hook: bool,
+) -> StdResult<Vec<CosmosMsg>> {
     let mut msgs = Vec::new();
 
     if !balance.native.is_empty() {
@@ -45,9 +53,17 @@ pub fn send_tokens_cosmos(to: &Addr, balance: &GenericBalance) -> StdResult<Vec<
         .nfts
         .iter()
         .map(|n| {
-            let msg = Cw721ExecuteMsg::TransferNft {
-                recipient: to.into(),
-                token_id: n.token_id.clone(),
+            let msg = if nft_hook {
+                Cw721ExecuteMsg::SendNft {
+                    contract: to.into(),
+                    token_id: n.token_id.clone(),
+                    msg: Binary::default(),
+                }
+            } else {
+                Cw721ExecuteMsg::TransferNft {
+                    recipient: to.into(),
+                    token_id: n.token_id.clone(),
+                }
             };
             let exec = CosmosMsg::from(WasmMsg::Execute {
                 contract_addr: n.contract_address.to_string(),
@@ -62,6 +78,12 @@ pub fn send_tokens_cosmos(to: &Addr, balance: &GenericBalance) -> StdResult<Vec<
     Ok(msgs)
 }
 
+// Number of messages send_tokens_cosmos creates for the balance
+#[must_use]
+pub fn transfer_count(balance: &GenericBalance) -> usize {
+    usize::from(!balance.native.is_empty()) + balance.cw20.len() + balance.nfts.len()
+}
+
 // Validate Ask
 // Removes any 0 values, returns error if duplicate is found
 pub fn normalize_ask_error_on_dup(ask: GenericBalance) -> Result<GenericBalance, ContractError> {
@@ -91,7 +113,107 @@ pub fn normalize_ask_error_on_dup(ask: GenericBalance) -> Result<GenericBalance,
     Ok(normalized)
 }
 
-pub fn calc_fee(balance: &GenericBalance) -> StdResult<Option<(CosmosMsg, GenericBalance)>> {
+// Checks that a bucket holds exactly the assets in an ask
+// Order of assets doesn't matter, denoms / addresses / token IDs are compared byte-exactly
+#[must_use]
+pub fn bucket_satisfies_ask(bucket_funds: &GenericBalance, ask: &GenericBalance) -> bool {
+    let sorted = |bal: &GenericBalance| -> GenericBalance {
+        let mut x = bal.clone();
+        x.native.sort_unstable_by(|a, b| a.denom.cmp(&b.denom));
+        x.cw20.sort_unstable_by(|a, b| a.address.cmp(&b.address));
+        x.nfts.sort_unstable_by(|a, b| {
+            (&a.contract_address, &a.token_id).cmp(&(&b.contract_address, &b.token_id))
+        });
+        x
+    };
+
+    sorted(bucket_funds) == sorted(ask)
+}
+
+// Checks that an ask doesn't cost more than a buyer's max price
+// Every native & cw20 amount must be within the max for its denom / address, every NFT must be in it
+#[must_use]
+pub fn ask_within_max_price(ask: &GenericBalance, max_price: &GenericBalance) -> bool {
+    let natives_ok = ask.native.iter().all(|coin| {
+        max_price.native.iter().any(|max| max.denom == coin.denom && coin.amount <= max.amount)
+    });
+    let cw20s_ok = ask.cw20.iter().all(|coin| {
+        max_price.cw20.iter().any(|max| max.address == coin.address && coin.amount <= max.amount)
+    });
+    let nfts_ok = ask.nfts.iter().all(|nft| max_price.nfts.contains(nft));
+
+    natives_ok && cw20s_ok && nfts_ok
+}
+
+// Checks that an ask isn't dust, every native in it must reach the minimum set for its denom
+#[must_use]
+pub fn ask_meets_market_minimum(ask: &GenericBalance, minimums: &[Coin]) -> bool {
+    ask.native
+        .iter()
+        .all(|coin| minimums.iter().all(|min| min.denom != coin.denom || coin.amount >= min.amount))
+}
+
+// Checks that an offer isn't spam, any NFT is enough, otherwise every native & cw20 in it must
+// have a minimum set for its denom or address & reach it, with no minimums anything goes
+#[must_use]
+pub fn offer_meets_minimum(funds: &GenericBalance, minimums: &[Coin]) -> bool {
+    let meets = |denom: &str, amount: Uint128| {
+        minimums.iter().any(|min| min.denom == denom && amount >= min.amount)
+    };
+    minimums.is_empty()
+        || !funds.nfts.is_empty()
+        || (funds.native.iter().all(|coin| meets(&coin.denom, coin.amount))
+            && funds.cw20.iter().all(|coin| meets(coin.address.as_str(), coin.amount)))
+}
+
+// Amount of a native denom or cw20 address held, None if there's none of it
+#[must_use]
+pub fn amount_of(funds: &GenericBalance, denom: &str) -> Option<Uint128> {
+    let native = funds.native.iter().find(|coin| coin.denom == denom);
+    let cw20 = funds.cw20.iter().find(|coin| coin.address == denom);
+    native.map(|coin| coin.amount).or(cw20.map(|coin| coin.amount))
+}
+
+// Assets an ask needs that a bucket doesn't hold yet, empty if nothing is missing
+#[must_use]
+pub fn ask_shortfall(bucket_funds: &GenericBalance, ask: &GenericBalance) -> GenericBalance {
+    let native = ask
+        .native
+        .iter()
+        .filter_map(|coin| {
+            let held = bucket_funds.native.iter().find(|c| c.denom == coin.denom);
+            let missing = coin.amount.saturating_sub(held.map(|c| c.amount).unwrap_or_default());
+            (!missing.is_zero()).then(|| Coin {
+                denom: coin.denom.clone(),
+                amount: missing,
+            })
+        })
+        .collect();
+    let cw20 = ask
+        .cw20
+        .iter()
+        .filter_map(|coin| {
+            let held = bucket_funds.cw20.iter().find(|c| c.address == coin.address);
+            let missing = coin.amount.saturating_sub(held.map(|c| c.amount).unwrap_or_default());
+            (!missing.is_zero()).then(|| Cw20CoinVerified {
+                address: coin.address.clone(),
+                amount: missing,
+            })
+        })
+        .collect();
+    let nfts = ask.nfts.iter().filter(|nft| !bucket_funds.nfts.contains(nft)).cloned().collect();
+
+    GenericBalance {
+        native,
+        cw20,
+        nfts,
+    }
+}
+
+pub fn calc_fee(
+    balance: &GenericBalance,
+    rounding: &Rounding,
+) -> StdResult<Option<(CosmosMsg, GenericBalance)>> {
     let juno_in_balance = balance.native.iter().find(|n| n.denom == *NATIVE);
 
     // If balance DOES NOT contain juno, return Ok(None)
@@ -100,9 +222,12 @@ pub fn calc_fee(balance: &GenericBalance) -> StdResult<Option<(CosmosMsg, Generi
     // and return this CosmosMsg + a generic balance with the fee removed for the user
     if let Some(juno) = juno_in_balance {
         // 0.1% = amount * 1 / 1000
-        let ten_pips = juno.amount.multiply_ratio(1_u128, 1000_u128);
+        let mut ten_pips = juno.amount.multiply_ratio(1_u128, 1000_u128);
+        if *rounding == Rounding::Ceil && ten_pips.checked_mul(1000_u128.into())? != juno.amount {
+            ten_pips += Uint128::one();
+        }
 
-        // small amounts (like 1ujuno) will be 0
+        // small amounts (like 1ujuno) will be 0 when rounding down
         if ten_pips.is_zero() {
             return Ok(None);
         }
@@ -126,3 +251,127 @@ pub fn calc_fee(balance: &GenericBalance) -> StdResult<Option<(CosmosMsg, Generi
         Ok(None)
     }
 }
+
+// Maker & taker cuts of the JUNO in an ask, in basis points, rounded like the withdrawal fee
+// Both are 0 during the config's fee holiday
+pub fn maker_taker_fees(
+    ask: &GenericBalance,
+    config: &Config,
+    now: Timestamp,
+) -> StdResult<(Uint128, Uint128)> {
+    if config.fee_holiday_until.is_some_and(|until| now < until) {
+        return Ok((Uint128::zero(), Uint128::zero()));
+    }
+    let juno = ask.native.iter().find(|n| n.denom == *NATIVE).map(|n| n.amount).unwrap_or_default();
+
+    let cut = |bps: u64| -> StdResult<Uint128> {
+        let product = juno.full_mul(bps);
+        let mut fee = product / Uint256::from(10_000_u128);
+        if config.rounding == Rounding::Ceil && !(product % Uint256::from(10_000_u128)).is_zero() {
+            fee += Uint256::one();
+        }
+        Ok(Uint128::try_from(fee)?)
+    };
+
+    Ok((cut(config.maker_fee_bps)?, cut(config.taker_fee_bps)?))
+}
+
+// Balance with `amount` JUNO added
+pub fn add_juno(
+    balance: &GenericBalance,
+    amount: Uint128,
+) -> Result<GenericBalance, ContractError> {
+    let mut x = balance.clone();
+    if amount.is_zero() {
+        return Ok(x);
+    }
+    match x.native.iter_mut().find(|n| n.denom == *NATIVE) {
+        Some(juno) => juno.amount = checked_add(juno.amount, amount)?,
+        None => x.native.append(&mut coins(amount.u128(), NATIVE)),
+    }
+    Ok(x)
+}
+
+// Balance with `amount` JUNO removed, the coin is dropped once empty
+pub fn sub_juno(
+    balance: &GenericBalance,
+    amount: Uint128,
+) -> Result<GenericBalance, ContractError> {
+    let mut x = balance.clone();
+    if amount.is_zero() {
+        return Ok(x);
+    }
+    let held = x.native.iter().find(|n| n.denom == *NATIVE).map(|n| n.amount).unwrap_or_default();
+    let left = checked_sub(held, amount)?;
+    x.native.retain(|n| n.denom != *NATIVE);
+    if !left.is_zero() {
+        x.native.append(&mut coins(left.u128(), NATIVE));
+    }
+    Ok(x)
+}
+
+// Assets in `balance` less those in `sub`, coins left at 0 are dropped so they're never sent
+// Errors when `sub` holds more of a coin than `balance` or an NFT it lacks, rather than skip it
+pub fn sub_balance(
+    balance: &GenericBalance,
+    sub: &GenericBalance,
+) -> Result<GenericBalance, ContractError> {
+    let mut native = Vec::new();
+    for coin in &balance.native {
+        let taken = sub.native.iter().find(|c| c.denom == coin.denom).map(|c| c.amount);
+        let left = checked_sub(coin.amount, taken.unwrap_or_default())?;
+        if !left.is_zero() {
+            native.push(Coin {
+                denom: coin.denom.clone(),
+                amount: left,
+            });
+        }
+    }
+    let mut cw20 = Vec::new();
+    for coin in &balance.cw20 {
+        let taken = sub.cw20.iter().find(|c| c.address == coin.address).map(|c| c.amount);
+        let left = checked_sub(coin.amount, taken.unwrap_or_default())?;
+        if !left.is_zero() {
+            cw20.push(Cw20CoinVerified {
+                address: coin.address.clone(),
+                amount: left,
+            });
+        }
+    }
+    // Everything taken must have been in the balance
+    let untracked =
+        sub.native.iter().any(|c| {
+            !c.amount.is_zero() && !balance.native.iter().any(|held| held.denom == c.denom)
+        }) || sub.cw20.iter().any(|c| {
+            !c.amount.is_zero() && !balance.cw20.iter().any(|held| held.address == c.address)
+        }) || sub.nfts.iter().any(|nft| !balance.nfts.contains(nft));
+    if untracked {
+        return Err(ContractError::Overflow {});
+    }
+    let nfts = balance.nfts.iter().filter(|nft| !sub.nfts.contains(nft)).cloned().collect();
+
+    Ok(GenericBalance {
+        native,
+        cw20,
+        nfts,
+    })
+}
+
+pub fn checked_add(a: Uint128, b: Uint128) -> Result<Uint128, ContractError> {
+    a.checked_add(b).map_err(|_e| ContractError::Overflow {})
+}
+
+pub fn checked_sub(a: Uint128, b: Uint128) -> Result<Uint128, ContractError> {
+    a.checked_sub(b).map_err(|_e| ContractError::Overflow {})
+}
+
+// Sends the maker & taker fees to the fee collector, None when there's nothing to send
+#[must_use]
+pub fn maker_taker_fee_msg(fee_collector: &Addr, amount: Uint128) -> Option<CosmosMsg> {
+    (!amount.is_zero()).then(|| {
+        CosmosMsg::from(BankMsg::Send {
+            to_address: fee_collector.to_string(),
+            amount: coins(amount.u128(), NATIVE),
+        })
+    })
+}